@@ -1,5 +1,3 @@
-use std::iter;
-
 use proc_macro2::TokenTree;
 use quote::{format_ident, quote};
 use syn::parse::{Parse, ParseStream};
@@ -75,61 +73,250 @@ derive_parse! {
     }
 }
 
+/// Marks a struct as a Dapr actor.
+///
+/// Actor method handlers take a `dapr::server::actor::ActorGuard<Self>`
+/// extractor to access the actor instance, so the attribute no longer needs
+/// to generate any code; it is kept so existing actor definitions compile.
 #[proc_macro_attribute]
 pub fn actor(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let actor_struct_name = match syn::parse::<syn::ItemStruct>(item.clone()) {
-        Ok(actor_struct) => actor_struct.ident.clone(),
-        Err(_) => match syn::parse::<syn::ItemType>(item.clone()) {
-            Ok(ty) => ty.ident.clone(),
-            Err(e) => panic!("Error parsing actor struct: {e}"),
-        },
-    };
+    if syn::parse::<syn::ItemStruct>(item.clone()).is_err()
+        && let Err(e) = syn::parse::<syn::ItemType>(item.clone())
+    {
+        panic!("Error parsing actor struct: {e}");
+    }
+
+    item
+}
+
+/// Declares an actor interface: the methods an actor type exposes to
+/// callers.
+///
+/// Every method must be `async`, take `&self` or `&mut self` plus at most one
+/// argument, and return a `Result<T, E>` where `T` is (de)serializable and
+/// `E: From<dapr::error::Error>` can be turned into a response (e.g.
+/// `dapr::server::actor::ActorError`). The macro generates:
+///
+/// - an implementation of the trait for `dapr::client::ActorProxy<dyn Trait>`
+///   that invokes the actor through the sidecar;
+/// - `dapr::server::actor::runtime::ActorMethods` for `dyn Trait`, so all
+///   methods are registered with
+///   `ActorTypeRegistration::register_interface::<dyn Trait, MyActor>()`.
+///
+/// The actor type defaults to the trait name and can be set with
+/// `#[actor_interface(actor_type = "MyActor")]`.
+#[proc_macro_attribute]
+pub fn actor_interface(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut actor_type: Option<LitStr> = None;
+    let attr_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("actor_type") {
+            actor_type = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported actor_interface property"))
+        }
+    });
+    parse_macro_input!(attr with attr_parser);
+    let mut item_trait = parse_macro_input!(item as syn::ItemTrait);
+
+    match expand_actor_interface(&mut item_trait, actor_type) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+struct InterfaceMethod {
+    sig: syn::Signature,
+    arg: Option<syn::Type>,
+    output: syn::Type,
+    error: syn::Type,
+}
+
+fn expand_actor_interface(
+    item_trait: &mut syn::ItemTrait,
+    actor_type: Option<LitStr>,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if !item_trait.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item_trait.generics,
+            "actor interfaces cannot be generic",
+        ));
+    }
+    let trait_name = &item_trait.ident;
+    let actor_type =
+        actor_type.unwrap_or_else(|| LitStr::new(&trait_name.to_string(), trait_name.span()));
+
+    let methods = item_trait
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::TraitItem::Fn(f) => Some(interface_method(&f.sig)),
+            _ => None,
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let has_async_trait = item_trait.attrs.iter().any(|a| {
+        a.path()
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "async_trait")
+    });
+    if !has_async_trait {
+        item_trait
+            .attrs
+            .push(syn::parse_quote!(#[::dapr::reexport::async_trait]));
+    }
 
-    let mut result = TokenStream::from(quote!(
-        #[async_trait::async_trait]
-        impl dapr::server::actor::axum::extract::FromRequestParts<dapr::server::actor::runtime::ActorState> for &#actor_struct_name {
-            type Rejection = dapr::server::actor::ActorRejection;
-
-            async fn from_request_parts(
-                parts: &mut dapr::server::actor::axum::http::request::Parts,
-                state: &dapr::server::actor::runtime::ActorState,
-            ) -> Result<Self, Self::Rejection> {
-                let path = match dapr::server::actor::axum::extract::Path::<dapr::server::actor::ActorPath>::from_request_parts(parts, state).await {
-                    Ok(path) => path,
-                    Err(e) => {
-                        log::error!("Error getting path: {}", e);
-                        return Err(dapr::server::actor::ActorRejection::Path(e));
-                    }
-                };
-                let actor_type = state.actor_type.clone();
-                let actor_id = path.actor_id.clone();
-                log::info!(
-                    "Request for actor_type: {}, actor_id: {}",
-                    actor_type,
-                    actor_id
-                );
-                let actor = match state
-                    .runtime
-                    .get_or_create_actor(&actor_type, &actor_id)
+    let proxy_methods = methods.iter().map(|m| {
+        let mut sig = m.sig.clone();
+        let name = sig.ident.to_string();
+        let input = match m.arg {
+            Some(_) => {
+                if let Some(syn::FnArg::Typed(arg)) = sig.inputs.iter_mut().nth(1) {
+                    *arg.pat = syn::parse_quote!(__dapr_input);
+                }
+                quote!(__dapr_input)
+            }
+            None => quote!(()),
+        };
+        quote! {
+            #sig {
+                self.invoke(#name, #input)
                     .await
-                {
-                    Ok(actor) => actor,
-                    Err(e) => {
-                        log::error!("Error getting actor: {}", e);
-                        return Err(dapr::server::actor::ActorRejection::ActorError(e.to_string()));
-                    }
-                };
-                let actor = actor.as_ref();
-                let well_known_actor =
-                    unsafe { &*(actor as *const dyn dapr::server::actor::Actor as *const #actor_struct_name) };
-                Ok(well_known_actor)
+                    .map_err(::core::convert::Into::into)
             }
         }
-    ));
+    });
 
-    result.extend(iter::once(item));
+    let handlers = methods.iter().map(|m| {
+        let method = &m.sig.ident;
+        let name = method.to_string();
+        let handler = format_ident!("__dapr_{}", method);
+        let output = &m.output;
+        let error = &m.error;
+        let (input_param, input_arg) = match &m.arg {
+            Some(ty) => (
+                quote!(, ::dapr::server::utils::DaprJson(__dapr_input): ::dapr::server::utils::DaprJson<#ty>),
+                quote!(, __dapr_input),
+            ),
+            None => (quote!(), quote!()),
+        };
+        quote! {
+            async fn #handler<__A>(
+                mut actor: ::dapr::server::actor::ActorGuard<__A>
+                #input_param
+            ) -> ::core::result::Result<::dapr::server::actor::axum::Json<#output>, #error>
+            where
+                __A: #trait_name + ::dapr::server::actor::Actor + 'static,
+            {
+                <__A as #trait_name>::#method(&mut *actor #input_arg)
+                    .await
+                    .map(::dapr::server::actor::axum::Json)
+            }
+            let registration = registration.register_method(#name, #handler::<__A>);
+        }
+    });
+
+    Ok(quote! {
+        #item_trait
+
+        impl ::dapr::client::ActorInterface for dyn #trait_name {
+            const ACTOR_TYPE: &'static str = #actor_type;
+        }
+
+        #[::dapr::reexport::async_trait]
+        impl<__T> #trait_name for ::dapr::client::ActorProxy<dyn #trait_name, __T>
+        where
+            __T: ::dapr::client::DaprInterface
+                + ::core::clone::Clone
+                + ::core::marker::Send
+                + ::core::marker::Sync
+                + 'static,
+        {
+            #(#proxy_methods)*
+        }
 
-    result
+        impl<__A> ::dapr::server::actor::runtime::ActorMethods<__A> for dyn #trait_name
+        where
+            __A: #trait_name + ::dapr::server::actor::Actor + 'static,
+        {
+            fn register_methods(
+                registration: ::dapr::server::actor::runtime::ActorTypeRegistration,
+            ) -> ::dapr::server::actor::runtime::ActorTypeRegistration {
+                #(#handlers)*
+                registration
+            }
+        }
+    })
+}
+
+fn interface_method(sig: &syn::Signature) -> syn::Result<InterfaceMethod> {
+    if sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            sig,
+            "actor interface methods must be async",
+        ));
+    }
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &sig.generics,
+            "actor interface methods cannot be generic",
+        ));
+    }
+    let mut inputs = sig.inputs.iter();
+    match inputs.next() {
+        Some(syn::FnArg::Receiver(r)) if r.reference.is_some() => {}
+        _ => {
+            return Err(syn::Error::new_spanned(
+                sig,
+                "actor interface methods must take `&self` or `&mut self`",
+            ));
+        }
+    }
+    let arg = match inputs.next() {
+        Some(syn::FnArg::Typed(arg)) => Some((*arg.ty).clone()),
+        _ => None,
+    };
+    if let Some(extra) = inputs.next() {
+        return Err(syn::Error::new_spanned(
+            extra,
+            "actor interface methods take at most one argument",
+        ));
+    }
+    let (output, error) = result_types(&sig.output).ok_or_else(|| {
+        syn::Error::new_spanned(
+            &sig.output,
+            "actor interface methods must return `Result<T, E>`",
+        )
+    })?;
+    Ok(InterfaceMethod {
+        sig: sig.clone(),
+        arg,
+        output,
+        error,
+    })
+}
+
+/// `(T, E)` of a `Result<T, E>` return type.
+fn result_types(output: &syn::ReturnType) -> Option<(syn::Type, syn::Type)> {
+    let syn::ReturnType::Type(_, ty) = output else {
+        return None;
+    };
+    let syn::Type::Path(path) = &**ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Result" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    let mut types = args.args.iter().filter_map(|a| match a {
+        syn::GenericArgument::Type(t) => Some(t.clone()),
+        _ => None,
+    });
+    Some((types.next()?, types.next()?))
 }
 
 #[proc_macro_attribute]
@@ -1,3 +1,4 @@
+use crate::client::{TraceContext, instrument};
 use crate::dapr::proto::runtime::v1::app_callback_alpha_server::AppCallbackAlpha;
 use crate::dapr::proto::runtime::v1::app_callback_server::AppCallback;
 use crate::dapr::proto::{common, runtime};
@@ -131,6 +132,7 @@ impl AppCallback for AppCallbackService {
         &self,
         request: Request<runtime::v1::TopicEventRequest>,
     ) -> Result<Response<runtime::v1::TopicEventResponse>, Status> {
+        let trace = TraceContext::from_metadata(request.metadata());
         let request_inner = request.into_inner();
         let pub_sub_name = request_inner.pubsub_name.clone();
         let topic_name = request_inner.topic.clone();
@@ -139,7 +141,12 @@ impl AppCallback for AppCallbackService {
             .iter()
             .find(|x| x.pub_sub_name == pub_sub_name && x.topic == topic_name);
         if let Some(handler) = handler {
-            return handler.handler.handler(request_inner).await;
+            return instrument::server_call(
+                "OnTopicEvent",
+                trace,
+                handler.handler.handler(request_inner),
+            )
+            .await;
         }
         Err(Status::new(Code::Internal, "Handler Not Found"))
     }
@@ -169,6 +176,7 @@ impl AppCallback for AppCallbackService {
         &self,
         request: Request<runtime::v1::JobEventRequest>,
     ) -> Result<Response<runtime::v1::JobEventResponse>, Status> {
+        let trace = TraceContext::from_metadata(request.metadata());
         let request_inner = request.into_inner();
         let job_name = if !request_inner.name.is_empty() {
             request_inner.name.clone()
@@ -182,7 +190,8 @@ impl AppCallback for AppCallbackService {
         };
 
         if let Some(handler) = self.job_handlers.get(&job_name) {
-            let handle_response = handler.handler(request_inner).await;
+            let handle_response =
+                instrument::server_call("OnJobEvent", trace, handler.handler(request_inner)).await;
             handle_response.map(Response::new)
         } else {
             Err(Status::not_found(format!(
@@ -245,6 +254,9 @@ impl AppCallbackService {
     ///
     /// The actor HTTP server ([`crate::server::DaprHttpServer`]) installs
     /// the layer automatically.
+    ///
+    /// Handlers run with the trace context received from the sidecar as the
+    /// current [`TraceContext`], so Dapr calls they make continue the trace.
     pub fn new() -> AppCallbackService {
         AppCallbackService {
             handlers: vec![],
@@ -0,0 +1,110 @@
+//! Typed clients for calling actors.
+//!
+//! Declare the methods of an actor type as a trait annotated with
+//! `#[dapr_macros::actor_interface]`; the macro implements the trait for
+//! [`ActorProxy<dyn Trait>`], so calls read like plain method calls:
+//!
+//! ```ignore
+//! #[dapr_macros::actor_interface]
+//! pub trait Counter {
+//!     async fn add(&self, by: u64) -> Result<u64, ActorError>;
+//!     async fn get(&self) -> Result<u64, ActorError>;
+//! }
+//!
+//! let counter = ActorProxy::<dyn Counter>::new(client, "counter-1");
+//! let total = counter.add(5).await?;
+//! ```
+//!
+//! Arguments and return values are JSON encoded. Methods take at most one
+//! argument besides the receiver, which is sent as the request body.
+
+#![warn(missing_docs)]
+
+use std::marker::PhantomData;
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use super::{Client, DaprInterface, TonicClientWithAuth};
+use crate::error::Error;
+
+/// An actor interface declared with `#[dapr_macros::actor_interface]`.
+///
+/// Implemented by the macro for `dyn Trait`.
+pub trait ActorInterface {
+    /// The actor type the interface is hosted as, unless overridden with
+    /// [`ActorProxy::with_actor_type`]. Defaults to the trait name.
+    const ACTOR_TYPE: &'static str;
+}
+
+/// A client for one actor, implementing its interface `I`.
+pub struct ActorProxy<I: ?Sized, T = TonicClientWithAuth> {
+    client: Client<T>,
+    actor_type: String,
+    actor_id: String,
+    _interface: PhantomData<fn() -> Box<I>>,
+}
+
+impl<I: ActorInterface + ?Sized, T> ActorProxy<I, T> {
+    /// A proxy for the actor `actor_id` of the interface's actor type.
+    pub fn new(client: Client<T>, actor_id: impl Into<String>) -> Self {
+        Self::with_actor_type(client, I::ACTOR_TYPE, actor_id)
+    }
+}
+
+impl<I: ?Sized, T> ActorProxy<I, T> {
+    /// A proxy for the actor `actor_id` of type `actor_type`.
+    pub fn with_actor_type(
+        client: Client<T>,
+        actor_type: impl Into<String>,
+        actor_id: impl Into<String>,
+    ) -> Self {
+        ActorProxy {
+            client,
+            actor_type: actor_type.into(),
+            actor_id: actor_id.into(),
+            _interface: PhantomData,
+        }
+    }
+
+    /// The type of the actor being called.
+    pub fn actor_type(&self) -> &str {
+        &self.actor_type
+    }
+
+    /// The id of the actor being called.
+    pub fn actor_id(&self) -> &str {
+        &self.actor_id
+    }
+
+    /// Invokes `method` on the actor. Used by the generated interface
+    /// implementations.
+    #[doc(hidden)]
+    pub async fn invoke<In, Out>(&self, method: &str, input: In) -> Result<Out, Error>
+    where
+        T: DaprInterface + Clone,
+        In: Serialize,
+        Out: DeserializeOwned,
+    {
+        let mut client = self.client.clone();
+        client
+            .invoke_actor(
+                self.actor_type.as_str(),
+                self.actor_id.as_str(),
+                method,
+                input,
+                None,
+            )
+            .await
+    }
+}
+
+impl<I: ?Sized, T: Clone> Clone for ActorProxy<I, T> {
+    fn clone(&self) -> Self {
+        ActorProxy {
+            client: self.client.clone(),
+            actor_type: self.actor_type.clone(),
+            actor_id: self.actor_id.clone(),
+            _interface: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! Actor reminders as reported by the sidecar.
+
+#![warn(missing_docs)]
+
+use prost_types::Any;
+use serde::de::DeserializeOwned;
+
+use crate::dapr::proto::runtime::v1 as dapr_v1;
+use crate::error::Error;
+use crate::server::actor::schedule;
+
+/// A reminder registered on an actor.
+///
+/// Returned by [`super::Client::get_actor_reminder`],
+/// [`super::Client::list_actor_reminders`] and their counterparts on
+/// [`crate::server::actor::context_client::ActorContextClient`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ActorReminder {
+    /// The type of the actor the reminder is registered on.
+    pub actor_type: String,
+    /// The id of the actor the reminder is registered on.
+    pub actor_id: String,
+    /// The name of the reminder.
+    pub name: String,
+    /// When the reminder first fires, as registered.
+    pub due_time: Option<String>,
+    /// How often the reminder fires again, as registered.
+    pub period: Option<String>,
+    /// When the reminder expires, as registered.
+    pub ttl: Option<String>,
+    /// The data the reminder was registered with.
+    pub data: Vec<u8>,
+}
+
+impl ActorReminder {
+    /// Deserializes [`ActorReminder::data`] attached with
+    /// [`schedule::ReminderSpec::with_data`].
+    pub fn data_as<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        serde_json::from_slice(&self.data).map_err(|_| Error::SerializationError)
+    }
+
+    pub(crate) fn from_response(name: String, response: dapr_v1::GetActorReminderResponse) -> Self {
+        ActorReminder {
+            actor_type: response.actor_type,
+            actor_id: response.actor_id,
+            name,
+            due_time: response.due_time,
+            period: response.period,
+            ttl: response.ttl,
+            data: reminder_data(response.data),
+        }
+    }
+}
+
+impl From<dapr_v1::NamedActorReminder> for ActorReminder {
+    fn from(named: dapr_v1::NamedActorReminder) -> Self {
+        let reminder = named.reminder.unwrap_or_default();
+        ActorReminder {
+            actor_type: reminder.actor_type,
+            actor_id: reminder.actor_id,
+            name: named.name,
+            due_time: reminder.due_time,
+            period: reminder.period,
+            ttl: reminder.ttl,
+            data: reminder_data(reminder.data),
+        }
+    }
+}
+
+/// The registered bytes of a reminder's `data`, which the sidecar stores
+/// as JSON, optionally wrapped in a `google.protobuf.BytesValue`.
+fn reminder_data(data: Option<Any>) -> Vec<u8> {
+    let Some(any) = data else {
+        return Vec::new();
+    };
+    let bytes = if any.type_url.ends_with("google.protobuf.BytesValue") {
+        <Vec<u8> as prost::Message>::decode(any.value.as_slice()).unwrap_or(any.value)
+    } else {
+        any.value
+    };
+    match serde_json::from_slice(&bytes) {
+        Ok(value) => schedule::decode_data(Some(value)),
+        Err(_) => bytes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_reminder_data() {
+        // Data registered over gRPC is stored as a base64 JSON string.
+        let named = dapr_v1::NamedActorReminder {
+            name: "r1".to_string(),
+            reminder: Some(dapr_v1::ActorReminder {
+                actor_type: "MyActor".to_string(),
+                actor_id: "a1".to_string(),
+                due_time: Some("PT5S".to_string()),
+                period: Some("R5/PT10S".to_string()),
+                data: Some(Any {
+                    type_url: "type.googleapis.com/google.protobuf.BytesValue".to_string(),
+                    value: prost::Message::encode_to_vec(&br#""eyJuIjoxfQ==""#.to_vec()),
+                }),
+                ttl: None,
+            }),
+        };
+        let reminder = ActorReminder::from(named);
+        assert_eq!(reminder.name, "r1");
+        assert_eq!(reminder.period.as_deref(), Some("R5/PT10S"));
+        assert_eq!(reminder.data, br#"{"n":1}"#);
+        assert_eq!(
+            reminder.data_as::<serde_json::Value>().unwrap(),
+            serde_json::json!({ "n": 1 })
+        );
+
+        // A base64 string that also reads as text is still registered bytes.
+        let data = Any {
+            type_url: "type.googleapis.com/google.protobuf.BytesValue".to_string(),
+            value: prost::Message::encode_to_vec(&br#""abcd""#.to_vec()),
+        };
+        assert_eq!(reminder_data(Some(data)), [0x69, 0xb7, 0x1d]);
+    }
+}
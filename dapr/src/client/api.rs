@@ -0,0 +1,423 @@
+//! Object-safe traits for the Dapr building blocks, implemented by
+//! [`Client`].
+//!
+//! Code that only needs one building block can depend on its trait, e.g.
+//! `Arc<dyn StateApi>`, and be tested against a hand-written fake or, with
+//! the `mocks` feature, a generated [mockall](https://docs.rs/mockall) mock
+//! such as `MockStateApi`:
+//!
+//! ```ignore
+//! use std::sync::Arc;
+//! use dapr::client::api::{MockStateApi, StateApi};
+//!
+//! async fn load_order(state: &dyn StateApi, id: &str) -> Result<Vec<u8>, dapr::error::Error> {
+//!     Ok(state.get_state("orders", id, None).await?.data)
+//! }
+//!
+//! let mut state = MockStateApi::new();
+//! state
+//!     .expect_get_state()
+//!     .withf(|store, key, _| store == "orders" && key == "1")
+//!     .returning(|_, _, _| Ok(Default::default()));
+//! let state: Arc<dyn StateApi> = Arc::new(state);
+//! assert!(load_order(&*state, "1").await?.is_empty());
+//! ```
+//!
+//! The methods take `&self` and run on a clone of the client, which shares
+//! its connection, so a single client can be shared as any of these traits.
+//!
+//! The traits are not re-exported from [`crate::client`]: their methods are
+//! named like the [`Client`] methods they wrap, and a trait in scope takes
+//! precedence over them on a `&Client`. Import them where they are used as
+//! trait objects, not next to code calling the [`Client`] methods.
+
+#![warn(missing_docs)]
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use prost_types::Any;
+use serde_json::Value;
+use tonic::Streaming;
+
+use super::{
+    Client, DaprInterface, Etag, GetBulkSecretResponse, GetConfigurationResponse,
+    GetMetadataResponse, GetSecretResponse, GetStateResponse, InvokeBindingResponse,
+    InvokeServiceResponse, QueryStateResponse, StateItem, StateOptions,
+    SubscribeConfigurationResponse, UnsubscribeConfigurationResponse,
+};
+use crate::error::Error;
+
+/// The state management building block, see [`Client::get_state`] and the
+/// neighbouring methods.
+#[cfg_attr(feature = "mocks", mockall::automock)]
+#[async_trait]
+pub trait StateApi: Send + Sync {
+    /// See [`Client::get_state`].
+    async fn get_state(
+        &self,
+        store_name: &str,
+        key: &str,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<GetStateResponse, Error>;
+
+    /// See [`Client::save_state`].
+    async fn save_state(
+        &self,
+        store_name: &str,
+        key: &str,
+        value: Vec<u8>,
+        etag: Option<Etag>,
+        metadata: Option<HashMap<String, String>>,
+        options: Option<StateOptions>,
+    ) -> Result<(), Error>;
+
+    /// See [`Client::save_bulk_states`].
+    async fn save_bulk_states(&self, store_name: &str, items: Vec<StateItem>) -> Result<(), Error>;
+
+    /// See [`Client::delete_state`].
+    async fn delete_state(
+        &self,
+        store_name: &str,
+        key: &str,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<(), Error>;
+
+    /// See [`Client::query_state_alpha1`].
+    async fn query_state_alpha1(
+        &self,
+        store_name: &str,
+        query: Value,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<QueryStateResponse, Error>;
+}
+
+/// The publish side of the pub/sub building block, see
+/// [`Client::publish_event`].
+#[cfg_attr(feature = "mocks", mockall::automock)]
+#[async_trait]
+pub trait PubSubApi: Send + Sync {
+    /// See [`Client::publish_event`].
+    async fn publish_event(
+        &self,
+        pubsub_name: &str,
+        topic: &str,
+        data_content_type: &str,
+        data: Vec<u8>,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<(), Error>;
+}
+
+/// The secrets building block, see [`Client::get_secret`].
+#[cfg_attr(feature = "mocks", mockall::automock)]
+#[async_trait]
+pub trait SecretsApi: Send + Sync {
+    /// See [`Client::get_secret`].
+    async fn get_secret(&self, store_name: &str, key: &str) -> Result<GetSecretResponse, Error>;
+
+    /// See [`Client::get_bulk_secret`].
+    async fn get_bulk_secret(
+        &self,
+        store_name: &str,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<GetBulkSecretResponse, Error>;
+}
+
+/// The configuration building block, see [`Client::get_configuration`].
+#[cfg_attr(feature = "mocks", mockall::automock)]
+#[async_trait]
+pub trait ConfigurationApi: Send + Sync {
+    /// See [`Client::get_configuration`].
+    async fn get_configuration(
+        &self,
+        store_name: &str,
+        keys: Vec<String>,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<GetConfigurationResponse, Error>;
+
+    /// See [`Client::subscribe_configuration`].
+    async fn subscribe_configuration(
+        &self,
+        store_name: &str,
+        keys: Vec<String>,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<Streaming<SubscribeConfigurationResponse>, Error>;
+
+    /// See [`Client::unsubscribe_configuration`].
+    async fn unsubscribe_configuration(
+        &self,
+        store_name: &str,
+        id: &str,
+    ) -> Result<UnsubscribeConfigurationResponse, Error>;
+}
+
+/// The output bindings building block, see [`Client::invoke_binding`].
+#[cfg_attr(feature = "mocks", mockall::automock)]
+#[async_trait]
+pub trait BindingsApi: Send + Sync {
+    /// See [`Client::invoke_binding`].
+    async fn invoke_binding(
+        &self,
+        name: &str,
+        data: Vec<u8>,
+        operation: &str,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<InvokeBindingResponse, Error>;
+}
+
+/// The service invocation building block, see [`Client::invoke_service`].
+#[cfg_attr(feature = "mocks", mockall::automock)]
+#[async_trait]
+pub trait InvocationApi: Send + Sync {
+    /// See [`Client::invoke_service`].
+    async fn invoke_service(
+        &self,
+        app_id: &str,
+        method_name: &str,
+        data: Option<Any>,
+    ) -> Result<InvokeServiceResponse, Error>;
+}
+
+/// The sidecar metadata API, see [`Client::get_metadata`].
+#[cfg_attr(feature = "mocks", mockall::automock)]
+#[async_trait]
+pub trait MetadataApi: Send + Sync {
+    /// See [`Client::get_metadata`].
+    async fn get_metadata(&self) -> Result<GetMetadataResponse, Error>;
+
+    /// See [`Client::set_metadata`].
+    async fn set_metadata(&self, key: &str, value: &str) -> Result<(), Error>;
+}
+
+// The inherent methods take `&mut self`, so each call runs on a clone. They
+// are called by path: method syntax would resolve to the trait method.
+
+#[async_trait]
+impl<T> StateApi for Client<T>
+where
+    T: DaprInterface + Clone + Sync,
+{
+    async fn get_state(
+        &self,
+        store_name: &str,
+        key: &str,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<GetStateResponse, Error> {
+        Client::get_state(&mut self.clone(), store_name, key, metadata).await
+    }
+
+    async fn save_state(
+        &self,
+        store_name: &str,
+        key: &str,
+        value: Vec<u8>,
+        etag: Option<Etag>,
+        metadata: Option<HashMap<String, String>>,
+        options: Option<StateOptions>,
+    ) -> Result<(), Error> {
+        Client::save_state(
+            &mut self.clone(),
+            store_name,
+            key,
+            value,
+            etag,
+            metadata,
+            options,
+        )
+        .await
+    }
+
+    async fn save_bulk_states(&self, store_name: &str, items: Vec<StateItem>) -> Result<(), Error> {
+        Client::save_bulk_states(&mut self.clone(), store_name, items).await
+    }
+
+    async fn delete_state(
+        &self,
+        store_name: &str,
+        key: &str,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<(), Error> {
+        Client::delete_state(&mut self.clone(), store_name, key, metadata).await
+    }
+
+    async fn query_state_alpha1(
+        &self,
+        store_name: &str,
+        query: Value,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<QueryStateResponse, Error> {
+        Client::query_state_alpha1(&mut self.clone(), store_name, query, metadata).await
+    }
+}
+
+#[async_trait]
+impl<T> PubSubApi for Client<T>
+where
+    T: DaprInterface + Clone + Sync,
+{
+    async fn publish_event(
+        &self,
+        pubsub_name: &str,
+        topic: &str,
+        data_content_type: &str,
+        data: Vec<u8>,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<(), Error> {
+        Client::publish_event(
+            &mut self.clone(),
+            pubsub_name,
+            topic,
+            data_content_type,
+            data,
+            metadata,
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl<T> SecretsApi for Client<T>
+where
+    T: DaprInterface + Clone + Sync,
+{
+    async fn get_secret(&self, store_name: &str, key: &str) -> Result<GetSecretResponse, Error> {
+        Client::get_secret(&mut self.clone(), store_name, key).await
+    }
+
+    async fn get_bulk_secret(
+        &self,
+        store_name: &str,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<GetBulkSecretResponse, Error> {
+        Client::get_bulk_secret(&mut self.clone(), store_name, metadata).await
+    }
+}
+
+#[async_trait]
+impl<T> ConfigurationApi for Client<T>
+where
+    T: DaprInterface + Clone + Sync,
+{
+    async fn get_configuration(
+        &self,
+        store_name: &str,
+        keys: Vec<String>,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<GetConfigurationResponse, Error> {
+        Client::get_configuration(&mut self.clone(), store_name, keys, metadata).await
+    }
+
+    async fn subscribe_configuration(
+        &self,
+        store_name: &str,
+        keys: Vec<String>,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<Streaming<SubscribeConfigurationResponse>, Error> {
+        Client::subscribe_configuration(&mut self.clone(), store_name.to_string(), keys, metadata)
+            .await
+    }
+
+    async fn unsubscribe_configuration(
+        &self,
+        store_name: &str,
+        id: &str,
+    ) -> Result<UnsubscribeConfigurationResponse, Error> {
+        Client::unsubscribe_configuration(&mut self.clone(), store_name, id).await
+    }
+}
+
+#[async_trait]
+impl<T> BindingsApi for Client<T>
+where
+    T: DaprInterface + Clone + Sync,
+{
+    async fn invoke_binding(
+        &self,
+        name: &str,
+        data: Vec<u8>,
+        operation: &str,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<InvokeBindingResponse, Error> {
+        Client::invoke_binding(&mut self.clone(), name, data, operation, metadata).await
+    }
+}
+
+#[async_trait]
+impl<T> InvocationApi for Client<T>
+where
+    T: DaprInterface + Clone + Sync,
+{
+    async fn invoke_service(
+        &self,
+        app_id: &str,
+        method_name: &str,
+        data: Option<Any>,
+    ) -> Result<InvokeServiceResponse, Error> {
+        Client::invoke_service(&mut self.clone(), app_id, method_name, data).await
+    }
+}
+
+#[async_trait]
+impl<T> MetadataApi for Client<T>
+where
+    T: DaprInterface + Clone + Sync,
+{
+    async fn get_metadata(&self) -> Result<GetMetadataResponse, Error> {
+        Client::get_metadata(&mut self.clone()).await
+    }
+
+    async fn set_metadata(&self, key: &str, value: &str) -> Result<(), Error> {
+        Client::set_metadata(&mut self.clone(), key, value).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::testing::MockSidecar;
+
+    async fn load(state: &dyn StateApi, key: &str) -> Result<Vec<u8>, Error> {
+        Ok(state.get_state("store", key, None).await?.data)
+    }
+
+    #[tokio::test]
+    async fn client_serves_the_building_block_traits() {
+        let sidecar = MockSidecar::new();
+        let client = Client::for_testing(&sidecar);
+        let state: Arc<dyn StateApi> = Arc::new(client.clone());
+        let pubsub: Arc<dyn PubSubApi> = Arc::new(client);
+
+        state
+            .save_state("store", "key", b"value".to_vec(), None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(load(&*state, "key").await.unwrap(), b"value");
+
+        pubsub
+            .publish_event("pubsub", "orders", "text/plain", b"1".to_vec(), None)
+            .await
+            .unwrap();
+        assert_eq!(sidecar.published("pubsub", "orders").len(), 1);
+    }
+
+    #[cfg(feature = "mocks")]
+    #[tokio::test]
+    async fn generated_mocks_stand_in_for_the_client() {
+        let mut state = MockStateApi::new();
+        state
+            .expect_get_state()
+            .withf(|store_name, key, _| store_name == "store" && key == "key")
+            .times(1)
+            .returning(|_, _, _| {
+                Ok(GetStateResponse {
+                    data: b"mocked".to_vec(),
+                    ..Default::default()
+                })
+            });
+
+        let state: Arc<dyn StateApi> = Arc::new(state);
+        assert_eq!(load(&*state, "key").await.unwrap(), b"mocked");
+    }
+}
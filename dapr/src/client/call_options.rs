@@ -0,0 +1,239 @@
+//! Per-call options: extra gRPC metadata and deadlines.
+//!
+//! [`CallOptions`] are attached to a [`crate::Client`] with
+//! [`crate::Client::with_call_options`] / [`crate::Client::with_metadata`],
+//! either for a single call (`client.with_call_options(options).get_state(..)`)
+//! or for every call made through the returned client. They can also be
+//! applied to any future making Dapr calls with [`CallOptions::scope`]; scoped
+//! options are task-local, so they do not carry over into tasks spawned
+//! inside the scope. Options set closer to the call win: metadata keys
+//! present in an inner scope replace the same keys from outer scopes and
+//! client defaults.
+
+#![warn(missing_docs)]
+
+use std::{future::Future, time::Duration};
+
+use tonic::{
+    Request,
+    metadata::{AsciiMetadataKey, AsciiMetadataValue, MetadataMap},
+};
+
+use crate::error::Error;
+
+/// gRPC metadata key Dapr uses to route a proxied gRPC call to a target app.
+pub const APP_ID_METADATA_KEY: &str = "dapr-app-id";
+
+tokio::task_local! {
+    static CURRENT: CallOptions;
+}
+
+/// Extra metadata and a deadline applied to outbound Dapr API calls.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use dapr::client::CallOptions;
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = dapr::Client::new().await?;
+///
+/// // Defaults for every call made through `tenant_client`.
+/// let tenant_client = client.with_metadata("x-tenant-id", "acme")?;
+///
+/// // Options for a single call.
+/// let options = CallOptions::new()
+///     .with_metadata("x-request-id", "42")?
+///     .with_deadline(Duration::from_millis(250));
+/// let state = tenant_client
+///     .with_call_options(options)
+///     .get_state("statestore", "key", None)
+///     .await?;
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CallOptions {
+    metadata: MetadataMap,
+    deadline: Option<Duration>,
+}
+
+impl CallOptions {
+    /// Create empty options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an ASCII metadata entry, replacing any existing value for `key`.
+    ///
+    /// Returns [`Error::InvalidMetadata`] if `key` or `value` is not valid
+    /// gRPC metadata.
+    pub fn with_metadata(mut self, key: &str, value: &str) -> Result<Self, Error> {
+        let key: AsciiMetadataKey = key.parse().map_err(|_| Error::InvalidMetadata)?;
+        let value: AsciiMetadataValue = value.parse()?;
+        self.metadata.insert(key, value);
+        Ok(self)
+    }
+
+    /// Merge a prepared [`MetadataMap`] (including binary `-bin` entries),
+    /// replacing existing values for the keys it contains.
+    pub fn with_metadata_map(mut self, metadata: MetadataMap) -> Self {
+        self.metadata = merge_metadata(&self.metadata, &metadata);
+        self
+    }
+
+    /// Set the `dapr-app-id` metadata used by the sidecar to route proxied
+    /// gRPC calls.
+    pub fn with_app_id(self, app_id: &str) -> Result<Self, Error> {
+        self.with_metadata(APP_ID_METADATA_KEY, app_id)
+    }
+
+    /// Fail calls that take longer than `deadline` with
+    /// [`tonic::Code::DeadlineExceeded`]. The deadline is also sent to the
+    /// sidecar as `grpc-timeout`.
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// The metadata attached to calls.
+    pub fn metadata(&self) -> &MetadataMap {
+        &self.metadata
+    }
+
+    /// The per-call deadline, if any.
+    pub fn deadline(&self) -> Option<Duration> {
+        self.deadline
+    }
+
+    /// Run `fut` with these options applied to every Dapr call made inside
+    /// it, on top of any options already in scope.
+    ///
+    /// The options are task-local: calls made from a task spawned inside
+    /// `fut` do not see them. Use [`crate::Client::with_call_options`] to
+    /// attach options to the calls themselves.
+    pub async fn scope<F: Future>(&self, fut: F) -> F::Output {
+        let options = match Self::current() {
+            Some(outer) => outer.merged(self),
+            None => self.clone(),
+        };
+        CURRENT.scope(options, fut).await
+    }
+
+    /// The options in scope for the current task, if any.
+    pub fn current() -> Option<Self> {
+        CURRENT.try_with(Clone::clone).ok()
+    }
+
+    /// Run `fut` with these options as defaults underneath whatever is
+    /// already in scope.
+    pub(crate) async fn scope_defaults<F: Future>(&self, fut: F) -> F::Output {
+        if self.is_empty() {
+            return fut.await;
+        }
+        let options = match Self::current() {
+            Some(inner) => self.merged(&inner),
+            None => self.clone(),
+        };
+        CURRENT.scope(options, fut).await
+    }
+
+    /// Wrap `message` in a [`Request`] carrying the options in scope.
+    pub(crate) fn request<T>(message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        // Outside any scope there is nothing to apply.
+        let _ = CURRENT.try_with(|options| options.apply(&mut request));
+        request
+    }
+
+    /// The deadline in scope for the current task, if any.
+    pub(crate) fn current_deadline() -> Option<Duration> {
+        CURRENT.try_with(|options| options.deadline).ok().flatten()
+    }
+
+    pub(crate) fn merged(&self, other: &CallOptions) -> CallOptions {
+        CallOptions {
+            metadata: merge_metadata(&self.metadata, &other.metadata),
+            deadline: other.deadline.or(self.deadline),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.metadata.is_empty() && self.deadline.is_none()
+    }
+
+    fn apply<T>(&self, request: &mut Request<T>) {
+        if !self.metadata.is_empty() {
+            let metadata = merge_metadata(request.metadata(), &self.metadata);
+            *request.metadata_mut() = metadata;
+        }
+        if let Some(deadline) = self.deadline {
+            request.set_timeout(deadline);
+        }
+    }
+}
+
+/// `base` with every key present in `overrides` replaced by its values there.
+fn merge_metadata(base: &MetadataMap, overrides: &MetadataMap) -> MetadataMap {
+    let mut headers = base.clone().into_headers();
+    headers.extend(overrides.clone().into_headers());
+    MetadataMap::from_headers(headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value<'a>(request: &'a Request<()>, key: &str) -> Option<&'a str> {
+        request.metadata().get(key).and_then(|v| v.to_str().ok())
+    }
+
+    #[test]
+    fn with_metadata_rejects_invalid_values() {
+        assert!(matches!(
+            CallOptions::new().with_metadata("bad key", "v"),
+            Err(Error::InvalidMetadata)
+        ));
+        assert!(matches!(
+            CallOptions::new().with_metadata("k", "bad\nvalue"),
+            Err(Error::InvalidMetadata)
+        ));
+    }
+
+    #[test]
+    fn request_outside_scope_has_no_metadata() {
+        let request = CallOptions::request(());
+        assert!(request.metadata().is_empty());
+    }
+
+    #[tokio::test]
+    async fn inner_scopes_override_outer_scopes_and_defaults() {
+        let defaults = CallOptions::new()
+            .with_metadata("x-tenant-id", "default")
+            .unwrap()
+            .with_metadata("x-default-only", "1")
+            .unwrap()
+            .with_deadline(Duration::from_secs(30));
+        let outer = CallOptions::new()
+            .with_metadata("x-tenant-id", "outer")
+            .unwrap()
+            .with_app_id("target")
+            .unwrap();
+        let inner = CallOptions::new().with_deadline(Duration::from_secs(1));
+
+        let request = outer
+            .scope(inner.scope(defaults.scope_defaults(async {
+                assert_eq!(
+                    CallOptions::current_deadline(),
+                    Some(Duration::from_secs(1))
+                );
+                CallOptions::request(())
+            })))
+            .await;
+
+        assert_eq!(value(&request, "x-tenant-id"), Some("outer"));
+        assert_eq!(value(&request, "x-default-only"), Some("1"));
+        assert_eq!(value(&request, APP_ID_METADATA_KEY), Some("target"));
+        assert!(request.metadata().get("grpc-timeout").is_some());
+    }
+}
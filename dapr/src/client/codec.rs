@@ -0,0 +1,160 @@
+//! Payload encodings for actor invocations.
+//!
+//! A codec turns the input of [`super::Client::invoke_actor_with_codec`]
+//! into the request body, names its content type, and decodes the
+//! response. [`Json`] is the default used by [`super::Client::invoke_actor`];
+//! [`Protobuf`] exchanges `prost` messages and [`Raw`] passes bytes through.
+//!
+//! The server side counterparts are
+//! [`crate::server::utils::DaprJson`], [`crate::server::utils::DaprProto`]
+//! and `axum::body::Bytes`.
+
+#![warn(missing_docs)]
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::error::Error;
+
+/// A payload encoding, identified by its content type.
+pub trait Codec {
+    /// The content type sent with payloads in this encoding.
+    const CONTENT_TYPE: &'static str;
+
+    /// Whether `content_type` (parameters such as `charset` are ignored)
+    /// names this encoding.
+    fn matches(content_type: &str) -> bool {
+        essence(content_type).eq_ignore_ascii_case(Self::CONTENT_TYPE)
+    }
+}
+
+/// Encodes values of type `T`.
+pub trait Encode<T: ?Sized>: Codec {
+    /// Encodes `value` into a request body.
+    fn encode(&self, value: &T) -> Result<Vec<u8>, Error>;
+}
+
+/// Decodes values of type `T`.
+pub trait Decode<T>: Codec {
+    /// Decodes a response body into a `T`.
+    fn decode(&self, bytes: &[u8]) -> Result<T, Error>;
+}
+
+/// JSON through `serde_json`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Json;
+
+impl Codec for Json {
+    const CONTENT_TYPE: &'static str = "application/json";
+
+    fn matches(content_type: &str) -> bool {
+        let essence = essence(content_type).to_ascii_lowercase();
+        essence == Self::CONTENT_TYPE || essence.ends_with("+json")
+    }
+}
+
+impl<T: Serialize + ?Sized> Encode<T> for Json {
+    fn encode(&self, value: &T) -> Result<Vec<u8>, Error> {
+        serde_json::to_vec(value).map_err(|_| Error::SerializationError)
+    }
+}
+
+impl<T: DeserializeOwned> Decode<T> for Json {
+    fn decode(&self, bytes: &[u8]) -> Result<T, Error> {
+        serde_json::from_slice(bytes).map_err(|_| Error::SerializationError)
+    }
+}
+
+/// Protocol buffers through `prost`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Protobuf;
+
+impl Codec for Protobuf {
+    const CONTENT_TYPE: &'static str = "application/x-protobuf";
+
+    fn matches(content_type: &str) -> bool {
+        let essence = essence(content_type).to_ascii_lowercase();
+        matches!(
+            essence.as_str(),
+            "application/x-protobuf" | "application/protobuf" | "application/vnd.google.protobuf"
+        )
+    }
+}
+
+impl<T: prost::Message> Encode<T> for Protobuf {
+    fn encode(&self, value: &T) -> Result<Vec<u8>, Error> {
+        Ok(value.encode_to_vec())
+    }
+}
+
+impl<T: prost::Message + Default> Decode<T> for Protobuf {
+    fn decode(&self, bytes: &[u8]) -> Result<T, Error> {
+        T::decode(bytes).map_err(|_| Error::SerializationError)
+    }
+}
+
+/// Bytes passed through unchanged.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Raw;
+
+impl Codec for Raw {
+    const CONTENT_TYPE: &'static str = "application/octet-stream";
+}
+
+impl Encode<[u8]> for Raw {
+    fn encode(&self, value: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(value.to_vec())
+    }
+}
+
+impl Encode<Vec<u8>> for Raw {
+    fn encode(&self, value: &Vec<u8>) -> Result<Vec<u8>, Error> {
+        Ok(value.clone())
+    }
+}
+
+impl Decode<Vec<u8>> for Raw {
+    fn decode(&self, bytes: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(bytes.to_vec())
+    }
+}
+
+/// The media type of `content_type`, without parameters.
+fn essence(content_type: &str) -> &str {
+    content_type.split(';').next().unwrap_or_default().trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_content_types() {
+        assert!(Json::matches("application/json; charset=utf-8"));
+        assert!(Json::matches("application/cloudevents+json"));
+        assert!(!Json::matches("application/x-protobuf"));
+        assert!(Protobuf::matches("application/protobuf"));
+        assert!(Raw::matches("Application/Octet-Stream"));
+        assert!(!Raw::matches("application/json"));
+    }
+
+    #[test]
+    fn round_trips_values() {
+        let bytes = Json.encode(&serde_json::json!({ "a": 1 })).unwrap();
+        assert_eq!(bytes, br#"{"a":1}"#);
+        assert_eq!(Decode::<u32>::decode(&Json, b"7").unwrap(), 7);
+
+        let ts = prost_types::Timestamp {
+            seconds: 5,
+            nanos: 6,
+        };
+        let bytes = Protobuf.encode(&ts).unwrap();
+        assert_eq!(
+            Decode::<prost_types::Timestamp>::decode(&Protobuf, &bytes).unwrap(),
+            ts
+        );
+        assert!(Decode::<prost_types::Timestamp>::decode(&Protobuf, &[0xff]).is_err());
+
+        assert_eq!(Raw.encode(&[1u8, 2][..]).unwrap(), vec![1, 2]);
+        assert_eq!(Raw.decode(&[3, 4]).unwrap(), vec![3, 4]);
+    }
+}
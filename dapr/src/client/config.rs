@@ -9,8 +9,9 @@
 
 #![warn(missing_docs)]
 
-use std::time::Duration;
+use std::{fmt, sync::Arc, time::Duration};
 
+use super::token::TokenProvider;
 use crate::error::Error;
 
 /// Environment variable holding the full gRPC endpoint of the Dapr sidecar
@@ -94,11 +95,12 @@ pub fn default_sidecar_address() -> String {
 /// assert_eq!(opts.api_token(), Some("super-secret"));
 /// assert_eq!(opts.timeout(), Duration::from_secs(10));
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 #[non_exhaustive]
 pub struct ClientOptions {
     address: String,
     api_token: Option<String>,
+    token_provider: Option<Arc<dyn TokenProvider>>,
     timeout: Duration,
 }
 
@@ -131,6 +133,7 @@ impl ClientOptions {
         Ok(Self {
             address,
             api_token,
+            token_provider: None,
             timeout,
         })
     }
@@ -150,9 +153,24 @@ impl ClientOptions {
         self
     }
 
-    /// Clear any previously-set Dapr API token.
+    /// Clear any previously-set Dapr API token or token provider.
     pub fn without_api_token(mut self) -> Self {
         self.api_token = None;
+        self.token_provider = None;
+        self
+    }
+
+    /// Ask `provider` for the Dapr API token on every outgoing call, so the
+    /// token can rotate without reconnecting. Takes precedence over
+    /// [`ClientOptions::with_api_token`] and `DAPR_API_TOKEN`.
+    pub fn with_token_provider(self, provider: impl TokenProvider + 'static) -> Self {
+        self.with_shared_token_provider(Arc::new(provider))
+    }
+
+    /// Like [`ClientOptions::with_token_provider`], for a provider that is
+    /// shared with other components.
+    pub fn with_shared_token_provider(mut self, provider: Arc<dyn TokenProvider>) -> Self {
+        self.token_provider = Some(provider);
         self
     }
 
@@ -172,12 +190,31 @@ impl ClientOptions {
         self.api_token.as_deref()
     }
 
+    /// The configured token provider, if any.
+    pub fn token_provider(&self) -> Option<&Arc<dyn TokenProvider>> {
+        self.token_provider.as_ref()
+    }
+
     /// The configured connect timeout.
     pub fn timeout(&self) -> Duration {
         self.timeout
     }
 }
 
+impl fmt::Debug for ClientOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientOptions")
+            .field("address", &self.address)
+            .field("api_token", &self.api_token)
+            .field(
+                "token_provider",
+                &self.token_provider.as_ref().map(|_| ".."),
+            )
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
 impl Default for ClientOptions {
     /// Read all options from the environment, silently falling back to
     /// defaults if any value is missing or invalid.
@@ -187,6 +224,7 @@ impl Default for ClientOptions {
         Self {
             address: default_sidecar_address(),
             api_token: read_optional_env(DAPR_API_TOKEN_ENV),
+            token_provider: None,
             timeout: read_timeout_env()
                 .unwrap_or_else(|_| Duration::from_secs(DEFAULT_CLIENT_TIMEOUT_SECONDS)),
         }
@@ -0,0 +1,342 @@
+//! Per-call instrumentation shared by the `DaprInterface` implementations
+//! and the app-callback / HTTP servers.
+//!
+//! Everything here compiles down to a plain `.await` unless the `tracing`,
+//! `opentelemetry` or `metrics` feature is enabled.
+
+use std::future::Future;
+
+use tonic::{Code, Status, Streaming};
+
+use super::{call_options::CallOptions, trace_context::TraceContext};
+use crate::error::Error;
+
+/// Identifies an outbound Dapr API call for spans and metrics.
+#[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+pub(crate) struct CallInfo<'a> {
+    pub building_block: &'static str,
+    pub method: &'static str,
+    pub component: Option<&'a str>,
+    pub request_bytes: usize,
+}
+
+impl<'a> CallInfo<'a> {
+    pub(crate) fn new(building_block: &'static str, method: &'static str) -> Self {
+        Self {
+            building_block,
+            method,
+            component: None,
+            request_bytes: 0,
+        }
+    }
+
+    pub(crate) fn component(mut self, component: &'a str) -> Self {
+        if !component.is_empty() {
+            self.component = Some(component);
+        }
+        self
+    }
+
+    /// Record the encoded size of the request. Only computed when metrics
+    /// are enabled.
+    pub(crate) fn request<M: prost::Message>(self, requests: &[M]) -> Self {
+        #[cfg(feature = "metrics")]
+        {
+            let mut this = self;
+            this.request_bytes = requests.iter().map(prost::Message::encoded_len).sum();
+            this
+        }
+        #[cfg(not(feature = "metrics"))]
+        {
+            let _ = requests;
+            self
+        }
+    }
+}
+
+/// The parts of a call result that instrumentation cares about.
+#[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+pub(crate) trait CallOutcome {
+    fn status_code(&self) -> Code;
+    fn response_bytes(&self) -> usize;
+    /// The result of a call cut short by its [`CallOptions`] deadline.
+    fn deadline_exceeded() -> Self;
+}
+
+/// Encoded size of a response payload.
+#[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+pub(crate) trait PayloadSize {
+    fn payload_size(&self) -> usize;
+}
+
+fn deadline_status() -> Status {
+    Status::deadline_exceeded("Dapr call exceeded its deadline")
+}
+
+impl<T: PayloadSize> CallOutcome for Result<T, Error> {
+    fn status_code(&self) -> Code {
+        match self {
+            Ok(_) => Code::Ok,
+            Err(Error::GrpcError(e)) => e.code(),
+            Err(Error::TransportError | Error::ConnectTimeout) => Code::Unavailable,
+            Err(_) => Code::Unknown,
+        }
+    }
+
+    fn response_bytes(&self) -> usize {
+        self.as_ref().map_or(0, PayloadSize::payload_size)
+    }
+
+    fn deadline_exceeded() -> Self {
+        Err(deadline_status().into())
+    }
+}
+
+impl<T: PayloadSize> CallOutcome for Result<T, Status> {
+    fn status_code(&self) -> Code {
+        self.as_ref().map_or_else(Status::code, |_| Code::Ok)
+    }
+
+    fn response_bytes(&self) -> usize {
+        self.as_ref().map_or(0, PayloadSize::payload_size)
+    }
+
+    fn deadline_exceeded() -> Self {
+        Err(deadline_status())
+    }
+}
+
+impl PayloadSize for Vec<u8> {
+    fn payload_size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl PayloadSize for Vec<super::StreamPayload> {
+    fn payload_size(&self) -> usize {
+        self.iter().map(prost::Message::encoded_len).sum()
+    }
+}
+
+impl<T> PayloadSize for Streaming<T> {
+    fn payload_size(&self) -> usize {
+        0
+    }
+}
+
+macro_rules! impl_payload_size_for_messages {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl PayloadSize for $ty {
+                fn payload_size(&self) -> usize {
+                    prost::Message::encoded_len(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_payload_size_for_messages!(
+    (),
+    super::InvokeServiceResponse,
+    super::InvokeBindingResponse,
+    super::GetSecretResponse,
+    super::GetBulkSecretResponse,
+    super::GetStateResponse,
+    super::QueryStateResponse,
+    super::GetMetadataResponse,
+    super::InvokeActorResponse,
+    super::GetActorReminderResponse,
+    super::ListActorRemindersResponse,
+    super::UnregisterActorRemindersByTypeResponse,
+    super::GetConfigurationResponse,
+    super::UnsubscribeConfigurationResponse,
+    super::ScheduleJobResponse,
+    super::GetJobResponse,
+    super::DeleteJobResponse,
+    super::DeleteJobsByPrefixResponse,
+    super::ListJobsResponse,
+    super::ConversationResponse,
+    super::ConversationResponseAlpha2,
+);
+
+/// Run an outbound call inside a client span named after its building block,
+/// bounded by the deadline in scope, and report it to the metrics recorder.
+pub(crate) async fn client_call<F>(info: CallInfo<'_>, fut: F) -> F::Output
+where
+    F: Future,
+    F::Output: CallOutcome,
+{
+    let deadline = CallOptions::current_deadline();
+    let fut = async move {
+        match deadline {
+            Some(deadline) => tokio::time::timeout(deadline, fut)
+                .await
+                .unwrap_or_else(|_| F::Output::deadline_exceeded()),
+            None => fut.await,
+        }
+    };
+
+    // Continue the caller's trace from a client span of its own, so the
+    // sidecar's span is parented on this call rather than on the caller.
+    #[cfg(feature = "opentelemetry")]
+    let fut = {
+        use opentelemetry::{
+            Context, KeyValue,
+            trace::{FutureExt, SpanKind, Status as SpanStatus, TraceContextExt, Tracer},
+        };
+
+        let cx = TraceContext::current().map(|parent| {
+            let parent = Context::current().with_remote_span_context(parent.to_opentelemetry());
+            let tracer = opentelemetry::global::tracer("dapr");
+            let span = tracer
+                .span_builder(format!("dapr.proto.runtime.v1.Dapr/{}", info.method))
+                .with_kind(SpanKind::Client)
+                .with_attributes([
+                    KeyValue::new("rpc.system", "grpc"),
+                    KeyValue::new("rpc.service", "dapr.proto.runtime.v1.Dapr"),
+                    KeyValue::new("rpc.method", info.method),
+                ])
+                .start_with_context(&tracer, &parent);
+            parent.with_span(span)
+        });
+        async move {
+            let Some(cx) = cx else {
+                return fut.await;
+            };
+            // Installing the span as current makes `TraceContextInterceptor`
+            // inject its context instead of the caller's.
+            let outcome = fut.with_context(cx.clone()).await;
+            let span = cx.span();
+            let code = outcome.status_code();
+            span.set_attribute(KeyValue::new("rpc.grpc.status_code", code as i64));
+            if code != Code::Ok {
+                span.set_status(SpanStatus::error(code.description()));
+            }
+            span.end();
+            outcome
+        }
+    };
+
+    #[cfg(feature = "tracing")]
+    let fut = {
+        use tracing::Instrument;
+
+        let span = tracing::info_span!(
+            "dapr.client",
+            otel.name = %format_args!("dapr.proto.runtime.v1.Dapr/{}", info.method),
+            otel.kind = "client",
+            rpc.system = "grpc",
+            rpc.service = "dapr.proto.runtime.v1.Dapr",
+            rpc.method = info.method,
+            rpc.grpc.status_code = tracing::field::Empty,
+            dapr.building_block = info.building_block,
+            dapr.component = info.component,
+        );
+        let record_span = span.clone();
+        async move {
+            let outcome = fut.await;
+            record_span.record("rpc.grpc.status_code", outcome.status_code() as i32);
+            outcome
+        }
+        .instrument(span)
+    };
+
+    #[cfg(feature = "metrics")]
+    {
+        let started = std::time::Instant::now();
+        let outcome = fut.await;
+        super::metrics::record(&super::metrics::CallRecord {
+            building_block: info.building_block,
+            method: info.method,
+            component: info.component,
+            status: outcome.status_code(),
+            latency: started.elapsed(),
+            request_bytes: info.request_bytes,
+            response_bytes: outcome.response_bytes(),
+        });
+        outcome
+    }
+    #[cfg(not(feature = "metrics"))]
+    {
+        let _ = info;
+        fut.await
+    }
+}
+
+/// Run an inbound handler with the sidecar-supplied trace context as the
+/// task's current context, inside a server span.
+pub(crate) async fn server_call<F: Future>(
+    method: &str,
+    trace: Option<TraceContext>,
+    fut: F,
+) -> F::Output {
+    #[cfg(feature = "tracing")]
+    let fut = {
+        use tracing::Instrument;
+
+        let span = tracing::info_span!(
+            "dapr.server",
+            otel.name = method,
+            otel.kind = "server",
+            trace_id = trace.as_ref().map(|t| t.trace_id()),
+            parent_span_id = trace.as_ref().map(|t| t.span_id()),
+        );
+        fut.instrument(span)
+    };
+    #[cfg(not(feature = "tracing"))]
+    let _ = method;
+
+    match trace {
+        #[cfg(feature = "opentelemetry")]
+        Some(trace) => {
+            use opentelemetry::trace::{FutureExt, TraceContextExt};
+
+            let cx = opentelemetry::Context::current()
+                .with_remote_span_context(trace.to_opentelemetry());
+            trace.scope(fut.with_context(cx)).await
+        }
+        #[cfg(not(feature = "opentelemetry"))]
+        Some(trace) => trace.scope(fut).await,
+        None => fut.await,
+    }
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::client::metrics::{self, InMemoryRecorder};
+
+    #[tokio::test]
+    async fn client_call_reports_status_and_sizes() {
+        let recorder = Arc::new(InMemoryRecorder::new());
+        metrics::set_recorder(recorder.clone());
+
+        let request = crate::client::GetStateRequest {
+            store_name: "instrument-test-store".into(),
+            key: "k".into(),
+            ..Default::default()
+        };
+        let info = CallInfo::new("state", "GetState")
+            .component(&request.store_name)
+            .request(std::slice::from_ref(&request));
+        let result: Result<(), Error> =
+            client_call(info, async { Err(Status::not_found("missing").into()) }).await;
+        assert!(result.is_err());
+
+        let (key, stats) = recorder
+            .snapshot()
+            .into_iter()
+            .find(|(k, _)| k.component.as_deref() == Some("instrument-test-store"))
+            .unwrap();
+        assert_eq!(key.status, Code::NotFound);
+        assert_eq!(stats.count, 1);
+        assert_eq!(
+            stats.request_bytes,
+            prost::Message::encoded_len(&request) as u64
+        );
+        assert_eq!(stats.response_bytes, 0);
+    }
+}
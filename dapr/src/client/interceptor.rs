@@ -1,16 +1,27 @@
-//! gRPC interceptors / tower layers for Dapr API tokens.
+//! gRPC interceptors / tower layers for Dapr API tokens and trace context.
 //!
 //! - [`ApiTokenInterceptor`] is an outbound [`tonic::service::Interceptor`]
 //!   that adds the configured `dapr-api-token` metadata to every request the
 //!   client makes. It is wired in automatically by
 //!   [`crate::Client::new`] / [`crate::Client::from_options`].
+//! - [`TraceContextInterceptor`] is an outbound interceptor that propagates
+//!   the current [`TraceContext`] as `traceparent` / `tracestate` /
+//!   `grpc-trace-bin` metadata. It is wired in alongside the token
+//!   interceptor.
 //! - [`AppApiTokenLayer`] is an inbound [`tower::Layer`] that enforces the
 //!   `APP_API_TOKEN` env var on incoming gRPC requests against the
 //!   app-callback server. It is opt-in.
+//!
+//! Both token components accept a [`TokenProvider`] so that tokens can be
+//! rotated without restarting the process.
 
 #![warn(missing_docs)]
 
-use std::task::{Context, Poll};
+use std::{
+    fmt,
+    sync::Arc,
+    task::{Context, Poll},
+};
 
 use tonic::{
     Status,
@@ -19,12 +30,15 @@ use tonic::{
 use tower::{Layer, Service};
 
 use super::config::{API_TOKEN_METADATA_KEY, APP_API_TOKEN_ENV};
+use super::token::{StaticTokenProvider, TokenProvider, constant_time_eq};
+use super::trace_context::TraceContext;
 
 /// Outbound interceptor that adds the Dapr API token metadata to each call.
 ///
-/// Construct via [`ApiTokenInterceptor::new`]. When the token is `None` or
-/// empty, the interceptor is a no-op, so it is safe to install
-/// unconditionally.
+/// Construct via [`ApiTokenInterceptor::new`] for a fixed token, or
+/// [`ApiTokenInterceptor::from_provider`] for a token that can rotate: the
+/// provider is consulted on every call. When no token is available the
+/// interceptor is a no-op, so it is safe to install unconditionally.
 ///
 /// # Examples
 ///
@@ -34,19 +48,17 @@ use super::config::{API_TOKEN_METADATA_KEY, APP_API_TOKEN_ENV};
 /// let interceptor = ApiTokenInterceptor::new(Some("my-token".to_string()));
 /// assert!(interceptor.has_token());
 /// ```
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Default)]
 pub struct ApiTokenInterceptor {
-    token: Option<MetadataValue<Ascii>>,
+    provider: Option<Arc<dyn TokenProvider>>,
 }
 
 impl ApiTokenInterceptor {
     /// Create an interceptor that injects the given token. Passing `None`
     /// (or `Some("")`) yields a no-op interceptor.
     ///
-    /// # Errors
-    ///
-    /// Returns [`crate::error::Error::InvalidMetadata`] when the token
-    /// contains characters that are not valid HTTP/2 ASCII metadata.
+    /// An invalid token also yields a no-op interceptor; use
+    /// [`ApiTokenInterceptor::try_new`] to surface the error.
     pub fn new(token: Option<String>) -> Self {
         Self::try_new(token).unwrap_or_default()
     }
@@ -59,25 +71,104 @@ impl ApiTokenInterceptor {
     /// Returns [`crate::error::Error::InvalidMetadata`] when the token
     /// contains characters that are not valid HTTP/2 ASCII metadata.
     pub fn try_new(token: Option<String>) -> Result<Self, crate::error::Error> {
-        let token = match token {
-            Some(t) if !t.is_empty() => Some(t.parse()?),
-            _ => None,
-        };
-        Ok(Self { token })
+        match token {
+            Some(t) if !t.is_empty() => {
+                t.parse::<MetadataValue<Ascii>>()?;
+                Ok(Self::from_provider(StaticTokenProvider::new(t)))
+            }
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Create an interceptor that asks `provider` for the token on every
+    /// call.
+    pub fn from_provider(provider: impl TokenProvider + 'static) -> Self {
+        Self::from_shared_provider(Arc::new(provider))
+    }
+
+    /// Like [`ApiTokenInterceptor::from_provider`], for a provider that is
+    /// shared with other components.
+    pub fn from_shared_provider(provider: Arc<dyn TokenProvider>) -> Self {
+        Self {
+            provider: Some(provider),
+        }
     }
 
-    /// Returns `true` when a non-empty token has been configured.
+    /// Returns `true` when a non-empty token is currently available.
     pub fn has_token(&self) -> bool {
-        self.token.is_some()
+        self.current_token().is_some()
+    }
+
+    fn current_token(&self) -> Option<String> {
+        self.provider
+            .as_ref()
+            .and_then(|p| p.token())
+            .filter(|t| !t.is_empty())
+    }
+}
+
+impl fmt::Debug for ApiTokenInterceptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiTokenInterceptor")
+            .field("has_provider", &self.provider.is_some())
+            .finish()
     }
 }
 
 impl tonic::service::Interceptor for ApiTokenInterceptor {
     fn call(&mut self, mut request: tonic::Request<()>) -> Result<tonic::Request<()>, Status> {
-        if let Some(token) = &self.token {
-            request
-                .metadata_mut()
-                .insert(API_TOKEN_METADATA_KEY, token.clone());
+        if let Some(token) = self.current_token() {
+            let token: MetadataValue<Ascii> = token.parse().map_err(|_| {
+                Status::unauthenticated("configured dapr-api-token is not valid metadata")
+            })?;
+            request.metadata_mut().insert(API_TOKEN_METADATA_KEY, token);
+        }
+        Ok(request)
+    }
+}
+
+/// Outbound interceptor that propagates the current [`TraceContext`].
+///
+/// On every call the interceptor looks up [`TraceContext::current`] and, if
+/// one is available, writes it as `traceparent`, `tracestate` and
+/// `grpc-trace-bin` metadata so the sidecar continues the caller's trace.
+/// Metadata already present on the request (e.g. set explicitly by the
+/// caller) is left untouched. Without a current context the interceptor is a
+/// no-op.
+///
+/// # Examples
+///
+/// ```
+/// use dapr::client::{TraceContext, TraceContextInterceptor};
+/// use tonic::service::Interceptor;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let ctx = TraceContext::from_traceparent(
+///     "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+///     None,
+/// )
+/// .unwrap();
+/// let req = ctx
+///     .scope(async { TraceContextInterceptor.call(tonic::Request::new(())) })
+///     .await
+///     .unwrap();
+/// assert!(req.metadata().get("traceparent").is_some());
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TraceContextInterceptor;
+
+impl tonic::service::Interceptor for TraceContextInterceptor {
+    fn call(&mut self, mut request: tonic::Request<()>) -> Result<tonic::Request<()>, Status> {
+        if request
+            .metadata()
+            .contains_key(super::trace_context::TRACEPARENT_HEADER)
+        {
+            return Ok(request);
+        }
+        if let Some(ctx) = TraceContext::current() {
+            ctx.inject(request.metadata_mut());
         }
         Ok(request)
     }
@@ -95,6 +186,11 @@ impl tonic::service::Interceptor for ApiTokenInterceptor {
 /// `APP_API_TOKEN` env var is unset or empty, the layer is permissive — every
 /// request passes. This makes it safe to install unconditionally.
 ///
+/// [`AppApiTokenLayer::from_provider`] checks requests against a
+/// [`TokenProvider`] instead, so the expected token can rotate while the
+/// server runs. Such a layer always enforces: while the provider has no token,
+/// every request is rejected. Tokens are compared in constant time.
+///
 /// # Examples
 ///
 /// ```
@@ -107,20 +203,33 @@ impl tonic::service::Interceptor for ApiTokenInterceptor {
 /// let strict = AppApiTokenLayer::new(Some("expected".to_string()));
 /// assert!(strict.is_enforcing());
 /// ```
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Default)]
 pub struct AppApiTokenLayer {
-    expected: Option<String>,
+    expected: Option<Arc<dyn TokenProvider>>,
 }
 
 impl AppApiTokenLayer {
     /// Construct a layer that enforces the given token. `None` (or an empty
     /// string) yields a permissive layer.
     pub fn new(expected: Option<String>) -> Self {
-        let expected = match expected {
-            Some(s) if !s.is_empty() => Some(s),
-            _ => None,
-        };
-        Self { expected }
+        match expected {
+            Some(s) if !s.is_empty() => Self::from_provider(StaticTokenProvider::new(s)),
+            _ => Self::default(),
+        }
+    }
+
+    /// Construct a layer that enforces whatever token `provider` currently
+    /// returns, rejecting every request while it returns none.
+    pub fn from_provider(provider: impl TokenProvider + 'static) -> Self {
+        Self::from_shared_provider(Arc::new(provider))
+    }
+
+    /// Like [`AppApiTokenLayer::from_provider`], for a provider that is
+    /// shared with other components.
+    pub fn from_shared_provider(provider: Arc<dyn TokenProvider>) -> Self {
+        Self {
+            expected: Some(provider),
+        }
     }
 
     /// Construct a layer that reads the expected token from `APP_API_TOKEN`.
@@ -136,6 +245,14 @@ impl AppApiTokenLayer {
     }
 }
 
+impl fmt::Debug for AppApiTokenLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AppApiTokenLayer")
+            .field("enforcing", &self.is_enforcing())
+            .finish()
+    }
+}
+
 impl<S> Layer<S> for AppApiTokenLayer {
     type Service = AppApiTokenService<S>;
 
@@ -148,10 +265,19 @@ impl<S> Layer<S> for AppApiTokenLayer {
 }
 
 /// Tower service produced by [`AppApiTokenLayer`].
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct AppApiTokenService<S> {
     inner: S,
-    expected: Option<String>,
+    expected: Option<Arc<dyn TokenProvider>>,
+}
+
+impl<S: fmt::Debug> fmt::Debug for AppApiTokenService<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AppApiTokenService")
+            .field("inner", &self.inner)
+            .field("enforcing", &self.expected.is_some())
+            .finish()
+    }
 }
 
 impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for AppApiTokenService<S>
@@ -175,12 +301,15 @@ where
         let expected = self.expected.clone();
 
         Box::pin(async move {
-            if let Some(expected_token) = expected {
-                let presented = req
-                    .headers()
-                    .get(API_TOKEN_METADATA_KEY)
-                    .and_then(|v| v.to_str().ok());
-                if presented != Some(expected_token.as_str()) {
+            if let Some(provider) = expected {
+                let presented = req.headers().get(API_TOKEN_METADATA_KEY);
+                let authorized = match (provider.token(), presented) {
+                    (Some(expected), Some(presented)) if !expected.is_empty() => {
+                        constant_time_eq(expected.as_bytes(), presented.as_bytes())
+                    }
+                    _ => false,
+                };
+                if !authorized {
                     let response = http::Response::builder()
                         .status(http::StatusCode::UNAUTHORIZED)
                         .header(
@@ -235,6 +364,92 @@ mod tests {
         ));
     }
 
+    #[derive(Default)]
+    struct RotatingProvider(std::sync::Mutex<Option<String>>);
+
+    impl RotatingProvider {
+        fn set(&self, token: Option<&str>) {
+            *self.0.lock().unwrap() = token.map(str::to_string);
+        }
+    }
+
+    impl TokenProvider for RotatingProvider {
+        fn token(&self) -> Option<String> {
+            self.0.lock().unwrap().clone()
+        }
+    }
+
+    #[test]
+    fn interceptor_follows_rotating_provider() {
+        let provider = Arc::new(RotatingProvider::default());
+        let mut interceptor = ApiTokenInterceptor::from_shared_provider(provider.clone());
+        assert!(!interceptor.has_token());
+
+        provider.set(Some("one"));
+        let req = interceptor.call(Request::new(())).unwrap();
+        assert_eq!(req.metadata().get(API_TOKEN_METADATA_KEY).unwrap(), "one");
+
+        provider.set(Some("two"));
+        let req = interceptor.call(Request::new(())).unwrap();
+        assert_eq!(req.metadata().get(API_TOKEN_METADATA_KEY).unwrap(), "two");
+    }
+
+    #[tokio::test]
+    async fn app_layer_follows_rotating_provider() {
+        use axum::{Router, routing::get};
+        use tower::ServiceExt;
+
+        let provider = Arc::new(RotatingProvider::default());
+        let app: Router = Router::new()
+            .route("/secret", get(|| async { "ok" }))
+            .layer(AppApiTokenLayer::from_shared_provider(provider.clone()));
+        let call = |token: &'static str| {
+            app.clone().oneshot(
+                http::Request::builder()
+                    .uri("/secret")
+                    .header(API_TOKEN_METADATA_KEY, token)
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+        };
+
+        // No token available yet → everything is rejected.
+        let resp = call("old").await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::UNAUTHORIZED);
+
+        provider.set(Some("old"));
+        let resp = call("old").await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        provider.set(Some("new"));
+        let resp = call("old").await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::UNAUTHORIZED);
+        let resp = call("new").await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn trace_interceptor_is_noop_without_context() {
+        let req = TraceContextInterceptor.call(Request::new(())).unwrap();
+        assert!(req.metadata().is_empty());
+    }
+
+    #[tokio::test]
+    async fn trace_interceptor_injects_scoped_context() {
+        let ctx = TraceContext::from_traceparent(
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            Some("k=v"),
+        )
+        .unwrap();
+        let req = ctx
+            .clone()
+            .scope(async { TraceContextInterceptor.call(Request::new(())) })
+            .await
+            .unwrap();
+        assert_eq!(TraceContext::from_metadata(req.metadata()), Some(ctx));
+        assert!(req.metadata().get_bin("grpc-trace-bin").is_some());
+    }
+
     #[test]
     fn app_layer_permissive_when_no_token() {
         let layer = AppApiTokenLayer::new(None);
@@ -0,0 +1,251 @@
+//! Client-side metrics for Dapr API calls.
+//!
+//! Every call made through a [`crate::client::DaprInterface`] implementation
+//! is reported to the installed [`MetricsRecorder`] as a [`CallRecord`]:
+//! building block, RPC method, component name (state store, pub/sub,
+//! binding, ...), resulting gRPC status code, latency and payload sizes.
+//!
+//! The SDK does not depend on any particular metrics backend. Implement
+//! [`MetricsRecorder`] to forward records to Prometheus, OpenTelemetry or
+//! anything else, and install it once at start-up with [`set_recorder`].
+//! [`InMemoryRecorder`] is a ready-made aggregating recorder that is handy in
+//! tests and for ad-hoc introspection.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::Arc;
+//! use dapr::client::metrics::{self, CallRecord, MetricsRecorder};
+//!
+//! struct LogRecorder;
+//!
+//! impl MetricsRecorder for LogRecorder {
+//!     fn record_call(&self, record: &CallRecord<'_>) {
+//!         println!(
+//!             "{}/{} component={:?} status={:?} took {:?}",
+//!             record.building_block,
+//!             record.method,
+//!             record.component,
+//!             record.status,
+//!             record.latency,
+//!         );
+//!     }
+//! }
+//!
+//! metrics::set_recorder(Arc::new(LogRecorder));
+//! ```
+
+#![warn(missing_docs)]
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+
+/// A single completed Dapr API call.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct CallRecord<'a> {
+    /// The building block the call belongs to (`state`, `pubsub`,
+    /// `bindings`, `secrets`, `actors`, ...).
+    pub building_block: &'a str,
+    /// The Dapr gRPC method name, e.g. `GetState`.
+    pub method: &'a str,
+    /// The component the call targeted (store, pub/sub or binding name,
+    /// app id for service invocation, actor type for actors), if any.
+    pub component: Option<&'a str>,
+    /// The resulting gRPC status code; [`tonic::Code::Ok`] on success.
+    pub status: tonic::Code,
+    /// Wall-clock time from issuing the request until the response (or
+    /// error) was available.
+    pub latency: Duration,
+    /// Encoded size of the request message(s) in bytes.
+    pub request_bytes: usize,
+    /// Encoded size of the response message(s) in bytes. Zero on error and
+    /// for streaming responses.
+    pub response_bytes: usize,
+}
+
+/// Receives a [`CallRecord`] for every Dapr API call.
+///
+/// Implementations are called inline on the request path and should be
+/// cheap and non-blocking.
+pub trait MetricsRecorder: Send + Sync {
+    /// Record a completed call.
+    fn record_call(&self, record: &CallRecord<'_>);
+}
+
+static RECORDER: RwLock<Option<Arc<dyn MetricsRecorder>>> = RwLock::new(None);
+
+/// Install the process-wide recorder, replacing any previous one.
+pub fn set_recorder(recorder: Arc<dyn MetricsRecorder>) {
+    *RECORDER.write().unwrap_or_else(|e| e.into_inner()) = Some(recorder);
+}
+
+/// Remove the process-wide recorder. Subsequent calls are not recorded.
+pub fn clear_recorder() {
+    *RECORDER.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+pub(crate) fn record(record: &CallRecord<'_>) {
+    let recorder = RECORDER.read().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(recorder) = recorder {
+        recorder.record_call(record);
+    }
+}
+
+/// Upper bounds of the latency histogram buckets used by
+/// [`InMemoryRecorder`]. Observations above the last bound land in an
+/// implicit overflow bucket.
+pub const LATENCY_BUCKETS: [Duration; 10] = [
+    Duration::from_millis(1),
+    Duration::from_millis(5),
+    Duration::from_millis(10),
+    Duration::from_millis(25),
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(250),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_secs(5),
+];
+
+/// Aggregation key used by [`InMemoryRecorder`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CallKey {
+    /// See [`CallRecord::building_block`].
+    pub building_block: String,
+    /// See [`CallRecord::method`].
+    pub method: String,
+    /// See [`CallRecord::component`].
+    pub component: Option<String>,
+    /// See [`CallRecord::status`].
+    pub status: tonic::Code,
+}
+
+/// Aggregated statistics for one [`CallKey`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CallStats {
+    /// Number of calls.
+    pub count: u64,
+    /// Sum of all call latencies.
+    pub total_latency: Duration,
+    /// Per-bucket call counts; index `i` counts calls with latency
+    /// `<= LATENCY_BUCKETS[i]`, the final element counts the overflow.
+    pub latency_buckets: [u64; LATENCY_BUCKETS.len() + 1],
+    /// Sum of request payload sizes in bytes.
+    pub request_bytes: u64,
+    /// Sum of response payload sizes in bytes.
+    pub response_bytes: u64,
+}
+
+/// A [`MetricsRecorder`] that aggregates calls in memory.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use dapr::client::metrics::{self, InMemoryRecorder};
+///
+/// let recorder = Arc::new(InMemoryRecorder::new());
+/// metrics::set_recorder(recorder.clone());
+/// // ... make Dapr calls ...
+/// for (key, stats) in recorder.snapshot() {
+///     println!("{}/{}: {} calls", key.building_block, key.method, stats.count);
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct InMemoryRecorder {
+    calls: Mutex<HashMap<CallKey, CallStats>>,
+}
+
+impl InMemoryRecorder {
+    /// Create an empty recorder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A copy of the statistics aggregated so far.
+    pub fn snapshot(&self) -> HashMap<CallKey, CallStats> {
+        self.calls.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Discard all aggregated statistics.
+    pub fn reset(&self) {
+        self.calls.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+impl MetricsRecorder for InMemoryRecorder {
+    fn record_call(&self, record: &CallRecord<'_>) {
+        let key = CallKey {
+            building_block: record.building_block.to_string(),
+            method: record.method.to_string(),
+            component: record.component.map(str::to_string),
+            status: record.status,
+        };
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|bound| record.latency <= *bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+
+        let mut calls = self.calls.lock().unwrap_or_else(|e| e.into_inner());
+        let stats = calls.entry(key).or_default();
+        stats.count += 1;
+        stats.total_latency += record.latency;
+        stats.latency_buckets[bucket] += 1;
+        stats.request_bytes += record.request_bytes as u64;
+        stats.response_bytes += record.response_bytes as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(latency: Duration, status: tonic::Code) -> CallRecord<'static> {
+        CallRecord {
+            building_block: "state",
+            method: "GetState",
+            component: Some("statestore"),
+            status,
+            latency,
+            request_bytes: 10,
+            response_bytes: 20,
+        }
+    }
+
+    #[test]
+    fn in_memory_recorder_aggregates_by_key() {
+        let recorder = InMemoryRecorder::new();
+        recorder.record_call(&record(Duration::from_micros(500), tonic::Code::Ok));
+        recorder.record_call(&record(Duration::from_millis(30), tonic::Code::Ok));
+        recorder.record_call(&record(Duration::from_secs(10), tonic::Code::NotFound));
+
+        let snapshot = recorder.snapshot();
+        assert_eq!(snapshot.len(), 2);
+
+        let ok = &snapshot[&CallKey {
+            building_block: "state".into(),
+            method: "GetState".into(),
+            component: Some("statestore".into()),
+            status: tonic::Code::Ok,
+        }];
+        assert_eq!(ok.count, 2);
+        assert_eq!(ok.request_bytes, 20);
+        assert_eq!(ok.response_bytes, 40);
+        assert_eq!(ok.latency_buckets[0], 1);
+        assert_eq!(ok.latency_buckets[4], 1);
+
+        let not_found = snapshot
+            .iter()
+            .find(|(k, _)| k.status == tonic::Code::NotFound)
+            .unwrap()
+            .1;
+        assert_eq!(not_found.latency_buckets[LATENCY_BUCKETS.len()], 1);
+
+        recorder.reset();
+        assert!(recorder.snapshot().is_empty());
+    }
+}
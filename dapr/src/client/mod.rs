@@ -2,10 +2,12 @@ use crate::dapr::proto::common::v1::JobFailurePolicyConstant;
 use crate::dapr::proto::common::v1::job_failure_policy::Policy;
 use crate::dapr::proto::{common::v1 as common_v1, runtime::v1 as dapr_v1};
 use crate::error::Error;
+use crate::server::actor::{ActorErrorEnvelope, reentrancy};
 #[cfg(feature = "workflow")]
 use crate::workflow;
 use async_trait::async_trait;
 use futures::StreamExt;
+use instrument::CallInfo;
 use prost_types::Any;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -14,11 +16,21 @@ use std::time::Duration;
 use tokio::io::AsyncRead;
 use tonic::codegen::tokio_stream;
 use tonic::service::interceptor::InterceptedService;
-use tonic::{Request, transport::Channel as TonicChannel};
+use tonic::transport::Channel as TonicChannel;
 use tonic::{Status, Streaming};
 
+pub mod actor_proxy;
+pub mod actor_reminder;
+pub mod api;
+pub mod call_options;
+pub mod codec;
 pub mod config;
+pub(crate) mod instrument;
 pub mod interceptor;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod token;
+pub mod trace_context;
 
 /// Returns `true` when a [`tonic::Status`] indicates the called gRPC method
 /// does not exist on the server.  Dapr ≤ 1.17 routes unknown methods through
@@ -30,15 +42,24 @@ fn is_method_not_found(status: &tonic::Status) -> bool {
             && status.message().contains("failed to proxy request"))
 }
 
+pub use actor_proxy::{ActorInterface, ActorProxy};
+pub use actor_reminder::ActorReminder;
+pub use call_options::{APP_ID_METADATA_KEY, CallOptions};
 pub use config::{
     API_TOKEN_METADATA_KEY, APP_API_TOKEN_ENV, ClientOptions, DAPR_API_TOKEN_ENV,
     DAPR_CLIENT_TIMEOUT_SECONDS_ENV, DAPR_GRPC_ENDPOINT_ENV, DAPR_GRPC_PORT_ENV,
     DEFAULT_CLIENT_TIMEOUT_SECONDS, DEFAULT_DAPR_GRPC_PORT, default_sidecar_address,
 };
-pub use interceptor::{ApiTokenInterceptor, AppApiTokenLayer, AppApiTokenService};
+pub use interceptor::{
+    ApiTokenInterceptor, AppApiTokenLayer, AppApiTokenService, TraceContextInterceptor,
+};
+pub use token::{
+    CallbackTokenProvider, EnvTokenProvider, FileTokenProvider, StaticTokenProvider, TokenProvider,
+};
+pub use trace_context::TraceContext;
 
 #[derive(Clone)]
-pub struct Client<T>(T, String);
+pub struct Client<T>(T, String, CallOptions);
 
 impl<T: DaprInterface> Client<T> {
     /// Connect to a Dapr enabled app.
@@ -62,7 +83,11 @@ impl<T: DaprInterface> Client<T> {
         let port: u16 = std::env::var("DAPR_GRPC_PORT")?.parse()?;
         let address = format!("{addr}:{port}");
 
-        Ok(Client(T::connect(address.clone()).await?, address))
+        Ok(Client(
+            T::connect(address.clone()).await?,
+            address,
+            CallOptions::default(),
+        ))
     }
 
     /// Connect to the Dapr sidecar with a specific port.
@@ -85,7 +110,48 @@ impl<T: DaprInterface> Client<T> {
 
         let address = format!("{addr}:{port}");
 
-        Ok(Client(T::connect(address.clone()).await?, address))
+        Ok(Client(
+            T::connect(address.clone()).await?,
+            address,
+            CallOptions::default(),
+        ))
+    }
+
+    /// Return a client that applies `options` to every call it makes, on top
+    /// of this client's existing defaults.
+    ///
+    /// This is also the per-call form, which unlike [`CallOptions::scope`]
+    /// keeps applying when the call is moved into another task:
+    ///
+    /// ```ignore
+    /// let state = client
+    ///     .with_call_options(CallOptions::new().with_deadline(Duration::from_secs(1)))
+    ///     .get_state("statestore", "key", None)
+    ///     .await?;
+    /// ```
+    ///
+    /// The returned client shares this client's connection. Options scoped
+    /// with [`CallOptions::scope`] take precedence over these defaults.
+    pub fn with_call_options(&self, options: CallOptions) -> Self
+    where
+        T: Clone,
+    {
+        Client(self.0.clone(), self.1.clone(), self.2.merged(&options))
+    }
+
+    /// Return a client that sends the metadata entry `key: value` on every
+    /// call it makes, e.g. a tenant header. Shorthand for
+    /// [`Client::with_call_options`].
+    pub fn with_metadata(&self, key: &str, value: &str) -> Result<Self, Error>
+    where
+        T: Clone,
+    {
+        Ok(self.with_call_options(CallOptions::new().with_metadata(key, value)?))
+    }
+
+    /// The call options applied to every call made through this client.
+    pub fn call_options(&self) -> &CallOptions {
+        &self.2
     }
 
     /// Create a workflow client connected to the same Dapr sidecar.
@@ -111,16 +177,18 @@ impl<T: DaprInterface> Client<T> {
         I: Into<String>,
         M: Into<String>,
     {
-        self.0
-            .invoke_service(InvokeServiceRequest {
-                id: app_id.into(),
-                message: common_v1::InvokeRequest {
-                    method: method_name.into(),
-                    data,
-                    ..Default::default()
-                }
-                .into(),
-            })
+        self.2
+            .scope_defaults(
+                self.0.invoke_service(InvokeServiceRequest {
+                    id: app_id.into(),
+                    message: common_v1::InvokeRequest {
+                        method: method_name.into(),
+                        data,
+                        ..Default::default()
+                    }
+                    .into(),
+                }),
+            )
             .await
     }
 
@@ -142,13 +210,13 @@ impl<T: DaprInterface> Client<T> {
     where
         S: Into<String>,
     {
-        self.0
-            .invoke_binding(InvokeBindingRequest {
+        self.2
+            .scope_defaults(self.0.invoke_binding(InvokeBindingRequest {
                 name: name.into(),
                 data,
                 operation: operation.into(),
                 metadata: metadata.unwrap_or_default(),
-            })
+            }))
             .await
     }
 
@@ -162,12 +230,12 @@ impl<T: DaprInterface> Client<T> {
     where
         S: Into<String>,
     {
-        self.0
-            .invoke_binding(InvokeBindingRequest {
+        self.2
+            .scope_defaults(self.0.invoke_binding(InvokeBindingRequest {
                 name: name.into(),
                 operation: operation.into(),
                 ..Default::default()
-            })
+            }))
             .await
             .map(|_| ())
     }
@@ -193,14 +261,14 @@ impl<T: DaprInterface> Client<T> {
         S: Into<String>,
     {
         let mdata = metadata.unwrap_or_default();
-        self.0
-            .publish_event(PublishEventRequest {
+        self.2
+            .scope_defaults(self.0.publish_event(PublishEventRequest {
                 pubsub_name: pubsub_name.into(),
                 topic: topic.into(),
                 data_content_type: data_content_type.into(),
                 data,
                 metadata: mdata,
-            })
+            }))
             .await
     }
 
@@ -214,12 +282,12 @@ impl<T: DaprInterface> Client<T> {
     where
         S: Into<String>,
     {
-        self.0
-            .get_secret(GetSecretRequest {
+        self.2
+            .scope_defaults(self.0.get_secret(GetSecretRequest {
                 store_name: store_name.into(),
                 key: key.into(),
                 ..Default::default()
-            })
+            }))
             .await
     }
 
@@ -236,11 +304,11 @@ impl<T: DaprInterface> Client<T> {
     where
         S: Into<String>,
     {
-        self.0
-            .get_bulk_secret(GetBulkSecretRequest {
+        self.2
+            .scope_defaults(self.0.get_bulk_secret(GetBulkSecretRequest {
                 store_name: store_name.into(),
                 metadata: metadata.unwrap_or_default(),
-            })
+            }))
             .await
     }
 
@@ -262,13 +330,13 @@ impl<T: DaprInterface> Client<T> {
     {
         let mdata = metadata.unwrap_or_default();
 
-        self.0
-            .get_state(GetStateRequest {
+        self.2
+            .scope_defaults(self.0.get_state(GetStateRequest {
                 store_name: store_name.into(),
                 key: key.into(),
                 metadata: mdata,
                 ..Default::default()
-            })
+            }))
             .await
     }
 
@@ -318,11 +386,11 @@ impl<T: DaprInterface> Client<T> {
         S: Into<String>,
         I: Into<Vec<StateItem>>,
     {
-        self.0
-            .save_state(SaveStateRequest {
+        self.2
+            .scope_defaults(self.0.save_state(SaveStateRequest {
                 store_name: store_name.into(),
                 states: items.into(),
-            })
+            }))
             .await
     }
 
@@ -343,12 +411,12 @@ impl<T: DaprInterface> Client<T> {
     {
         let mdata = metadata.unwrap_or_default();
 
-        self.0
-            .query_state_alpha1(QueryStateRequest {
+        self.2
+            .scope_defaults(self.0.query_state_alpha1(QueryStateRequest {
                 store_name: store_name.into(),
                 query: serde_json::to_string(&query).unwrap(),
                 metadata: mdata,
-            })
+            }))
             .await
     }
 
@@ -363,11 +431,11 @@ impl<T: DaprInterface> Client<T> {
         I: IntoIterator<Item = (K, Vec<u8>)>,
         K: Into<String>,
     {
-        self.0
-            .delete_bulk_state(DeleteBulkStateRequest {
+        self.2
+            .scope_defaults(self.0.delete_bulk_state(DeleteBulkStateRequest {
                 store_name: store_name.into(),
                 states: states.into_iter().map(|pair| pair.into()).collect(),
-            })
+            }))
             .await
     }
 
@@ -388,13 +456,13 @@ impl<T: DaprInterface> Client<T> {
     {
         let mdata = metadata.unwrap_or_default();
 
-        self.0
-            .delete_state(DeleteStateRequest {
+        self.2
+            .scope_defaults(self.0.delete_state(DeleteStateRequest {
                 store_name: store_name.into(),
                 key: key.into(),
                 metadata: mdata,
                 ..Default::default()
-            })
+            }))
             .await
     }
 
@@ -408,28 +476,36 @@ impl<T: DaprInterface> Client<T> {
     where
         S: Into<String>,
     {
-        self.0
-            .set_metadata(SetMetadataRequest {
+        self.2
+            .scope_defaults(self.0.set_metadata(SetMetadataRequest {
                 key: key.into(),
                 value: value.into(),
-            })
+            }))
             .await
     }
 
     /// Set sidecar Metadata
     ///
     pub async fn get_metadata(&mut self) -> Result<GetMetadataResponse, Error> {
-        self.0.get_metadata().await
+        self.2.scope_defaults(self.0.get_metadata()).await
     }
 
     /// Invoke a method in a Dapr actor.
     ///
+    /// The input and output are JSON encoded; see
+    /// [`Client::invoke_actor_with_codec`] for other encodings.
+    ///
     /// # Arguments
     ///
     /// * `actor_type` - Type of the actor.
     /// * `actor_id` - Id of the actor.
     /// * `method_name` - Name of the method to invoke.
     /// * `input` - Required. Data required to invoke service, should be json serializable.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ActorMethodError`] when the actor method fails; decode
+    /// an application error with [`Error::actor_method_error`].
     pub async fn invoke_actor<I, M, TInput, TOutput>(
         &mut self,
         actor_type: I,
@@ -443,31 +519,199 @@ impl<T: DaprInterface> Client<T> {
         M: Into<String>,
         TInput: Serialize,
         TOutput: for<'a> Deserialize<'a>,
+    {
+        self.invoke_actor_with_codec(
+            actor_type,
+            actor_id,
+            method_name,
+            input,
+            codec::Json,
+            metadata,
+        )
+        .await
+    }
+
+    /// Invoke a method in a Dapr actor, passing the input and output through
+    /// as bytes.
+    ///
+    /// The request is sent as `application/octet-stream` unless `metadata`
+    /// sets a `Content-Type`.
+    ///
+    /// # Arguments
+    ///
+    /// * `actor_type` - Type of the actor.
+    /// * `actor_id` - Id of the actor.
+    /// * `method_name` - Name of the method to invoke.
+    /// * `data` - The request body.
+    pub async fn invoke_actor_raw<I, M>(
+        &mut self,
+        actor_type: I,
+        actor_id: I,
+        method_name: M,
+        data: impl Into<Vec<u8>>,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<Vec<u8>, Error>
+    where
+        I: Into<String>,
+        M: Into<String>,
+    {
+        self.invoke_actor_with_codec(
+            actor_type,
+            actor_id,
+            method_name,
+            data.into(),
+            codec::Raw,
+            metadata,
+        )
+        .await
+    }
+
+    /// Invoke a method in a Dapr actor, encoding the input and decoding the
+    /// output with `codec`, e.g. [`codec::Protobuf`].
+    ///
+    /// The request is sent with the codec's content type unless `metadata`
+    /// sets a `Content-Type`.
+    ///
+    /// # Arguments
+    ///
+    /// * `actor_type` - Type of the actor.
+    /// * `actor_id` - Id of the actor.
+    /// * `method_name` - Name of the method to invoke.
+    /// * `input` - Required. Data required to invoke service.
+    /// * `codec` - The encoding of the input and output.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ActorMethodError`] when the actor method fails; decode
+    /// an application error with [`Error::actor_method_error`].
+    pub async fn invoke_actor_with_codec<I, M, C, TInput, TOutput>(
+        &mut self,
+        actor_type: I,
+        actor_id: I,
+        method_name: M,
+        input: TInput,
+        codec: C,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<TOutput, Error>
+    where
+        I: Into<String>,
+        M: Into<String>,
+        C: codec::Encode<TInput> + codec::Decode<TOutput>,
     {
         let mut mdata = metadata.unwrap_or_default();
 
-        mdata.insert("Content-Type".to_string(), "application/json".to_string());
+        if !mdata
+            .keys()
+            .any(|key| key.eq_ignore_ascii_case("content-type"))
+        {
+            mdata.insert("Content-Type".to_string(), C::CONTENT_TYPE.to_string());
+        }
+        if let Some(id) = reentrancy::current_id() {
+            mdata
+                .entry(reentrancy::REENTRANCY_ID_HEADER.to_string())
+                .or_insert(id);
+        }
 
-        let data = match serde_json::to_vec(&input) {
-            Ok(data) => data,
-            Err(_e) => return Err(Error::SerializationError),
-        };
+        let data = codec.encode(&input)?;
 
         let res = self
-            .0
-            .invoke_actor(InvokeActorRequest {
+            .2
+            .scope_defaults(self.0.invoke_actor(InvokeActorRequest {
                 actor_type: actor_type.into(),
                 actor_id: actor_id.into(),
                 method: method_name.into(),
                 data,
                 metadata: mdata,
-            })
+            }))
             .await?;
 
-        match serde_json::from_slice::<TOutput>(&res.data) {
-            Ok(output) => Ok(output),
-            Err(_e) => Err(Error::SerializationError),
+        if let Some(envelope) = actor_error_envelope(&res.data) {
+            return Err(Error::ActorMethodError {
+                status: envelope.error.status,
+                body: serde_json::to_vec(&envelope.error.body)
+                    .map_err(|_| Error::SerializationError)?,
+            });
         }
+
+        codec.decode(&res.data)
+    }
+
+    /// Get a reminder registered on an actor.
+    ///
+    /// # Arguments
+    ///
+    /// * `actor_type` - Type of the actor.
+    /// * `actor_id` - Id of the actor.
+    /// * `name` - Name of the reminder.
+    pub async fn get_actor_reminder<S>(
+        &mut self,
+        actor_type: S,
+        actor_id: S,
+        name: S,
+    ) -> Result<ActorReminder, Error>
+    where
+        S: Into<String>,
+    {
+        let name = name.into();
+        let request = GetActorReminderRequest {
+            actor_type: actor_type.into(),
+            actor_id: actor_id.into(),
+            name: name.clone(),
+        };
+        let response = self
+            .2
+            .scope_defaults(self.0.get_actor_reminder(request))
+            .await?;
+        Ok(ActorReminder::from_response(name, response))
+    }
+
+    /// List the reminders registered on actors of a type.
+    ///
+    /// # Arguments
+    ///
+    /// * `actor_type` - Type of the actors.
+    /// * `actor_id` - Only list the reminders of this actor, if set.
+    pub async fn list_actor_reminders<S>(
+        &mut self,
+        actor_type: S,
+        actor_id: Option<S>,
+    ) -> Result<Vec<ActorReminder>, Error>
+    where
+        S: Into<String>,
+    {
+        let request = ListActorRemindersRequest {
+            actor_type: actor_type.into(),
+            actor_id: actor_id.map(Into::into),
+        };
+        let response = self
+            .2
+            .scope_defaults(self.0.list_actor_reminders(request))
+            .await?;
+        Ok(response.reminders.into_iter().map(Into::into).collect())
+    }
+
+    /// Unregister all reminders of actors of a type.
+    ///
+    /// # Arguments
+    ///
+    /// * `actor_type` - Type of the actors.
+    /// * `actor_id` - Only unregister the reminders of this actor, if set.
+    pub async fn unregister_actor_reminders_by_type<S>(
+        &mut self,
+        actor_type: S,
+        actor_id: Option<S>,
+    ) -> Result<(), Error>
+    where
+        S: Into<String>,
+    {
+        let request = UnregisterActorRemindersByTypeRequest {
+            actor_type: actor_type.into(),
+            actor_id: actor_id.map(Into::into),
+        };
+        self.2
+            .scope_defaults(self.0.unregister_actor_reminders_by_type(request))
+            .await?;
+        Ok(())
     }
 
     /// Get the configuration for a specific key
@@ -491,7 +735,9 @@ impl<T: DaprInterface> Client<T> {
             keys: keys.into_iter().map(|key| key.into()).collect(),
             metadata: metadata.unwrap_or_default(),
         };
-        self.0.get_configuration(request).await
+        self.2
+            .scope_defaults(self.0.get_configuration(request))
+            .await
     }
 
     /// Subscribe to configuration changes
@@ -509,7 +755,9 @@ impl<T: DaprInterface> Client<T> {
             keys: keys.into_iter().map(|key| key.into()).collect(),
             metadata: metadata.unwrap_or_default(),
         };
-        self.0.subscribe_configuration(request).await
+        self.2
+            .scope_defaults(self.0.subscribe_configuration(request))
+            .await
     }
 
     /// Unsubscribe from configuration changes
@@ -525,7 +773,9 @@ impl<T: DaprInterface> Client<T> {
             id: id.into(),
             store_name: store_name.into(),
         };
-        self.0.unsubscribe_configuration(request).await
+        self.2
+            .scope_defaults(self.0.unsubscribe_configuration(request))
+            .await
     }
 
     /// Encrypt binary data using Dapr. returns `Vec<StreamPayload>` to be used in decrypt method
@@ -566,7 +816,7 @@ impl<T: DaprInterface> Client<T> {
                 init
             })
             .await;
-        self.0.encrypt(requested_items).await
+        self.2.scope_defaults(self.0.encrypt(requested_items)).await
     }
 
     /// Decrypt binary data using Dapr. returns `Vec<u8>`.
@@ -597,7 +847,7 @@ impl<T: DaprInterface> Client<T> {
                 }
             })
             .collect();
-        self.0.decrypt(requested_items).await
+        self.2.scope_defaults(self.0.decrypt(requested_items)).await
     }
 
     /// Schedules a job with the Dapr Distributed Scheduler
@@ -615,7 +865,7 @@ impl<T: DaprInterface> Client<T> {
             job: Some(job.clone()),
             overwrite: overwrite.unwrap_or(false),
         };
-        self.0.schedule_job(request).await
+        self.2.scope_defaults(self.0.schedule_job(request)).await
     }
 
     /// Schedules a job with the Dapr Distributed Scheduler
@@ -635,7 +885,9 @@ impl<T: DaprInterface> Client<T> {
             job: Some(job.clone()),
             overwrite: overwrite.unwrap_or(false),
         };
-        self.0.schedule_job_alpha1(request).await
+        self.2
+            .scope_defaults(self.0.schedule_job_alpha1(request))
+            .await
     }
 
     /// Retrieves a scheduled job from the Dapr Distributed Scheduler
@@ -647,7 +899,7 @@ impl<T: DaprInterface> Client<T> {
         let request = GetJobRequest {
             name: name.to_string(),
         };
-        self.0.get_job(request).await
+        self.2.scope_defaults(self.0.get_job(request)).await
     }
 
     /// Retrieves a scheduled job from the Dapr Distributed Scheduler
@@ -661,7 +913,7 @@ impl<T: DaprInterface> Client<T> {
         let request = GetJobRequest {
             name: name.to_string(),
         };
-        self.0.get_job_alpha1(request).await
+        self.2.scope_defaults(self.0.get_job_alpha1(request)).await
     }
 
     /// Deletes a scheduled job from the Dapr Distributed Scheduler
@@ -673,7 +925,7 @@ impl<T: DaprInterface> Client<T> {
         let request = DeleteJobRequest {
             name: name.to_string(),
         };
-        self.0.delete_job(request).await
+        self.2.scope_defaults(self.0.delete_job(request)).await
     }
 
     /// Deletes a scheduled job from the Dapr Distributed Scheduler
@@ -687,7 +939,9 @@ impl<T: DaprInterface> Client<T> {
         let request = DeleteJobRequest {
             name: name.to_string(),
         };
-        self.0.delete_job_alpha1(request).await
+        self.2
+            .scope_defaults(self.0.delete_job_alpha1(request))
+            .await
     }
 
     /// Deletes all jobs whose name starts with the given prefix.
@@ -703,12 +957,16 @@ impl<T: DaprInterface> Client<T> {
         let request = DeleteJobsByPrefixRequest {
             name_prefix: prefix.map(|p| p.to_string()),
         };
-        self.0.delete_jobs_by_prefix(request).await
+        self.2
+            .scope_defaults(self.0.delete_jobs_by_prefix(request))
+            .await
     }
 
     /// Lists all scheduled jobs
     pub async fn list_jobs(&mut self) -> Result<ListJobsResponse, Error> {
-        self.0.list_jobs(ListJobsRequest {}).await
+        self.2
+            .scope_defaults(self.0.list_jobs(ListJobsRequest {}))
+            .await
     }
 
     /// Converse with an LLM
@@ -720,7 +978,7 @@ impl<T: DaprInterface> Client<T> {
         &mut self,
         request: ConversationRequest,
     ) -> Result<ConversationResponse, Error> {
-        self.0.converse_alpha1(request).await
+        self.2.scope_defaults(self.0.converse_alpha1(request)).await
     }
 
     /// Converse with an LLM using alpha2
@@ -732,7 +990,7 @@ impl<T: DaprInterface> Client<T> {
         &mut self,
         request: ConversationRequestAlpha2,
     ) -> Result<ConversationResponseAlpha2, Error> {
-        self.0.converse_alpha2(request).await
+        self.2.scope_defaults(self.0.converse_alpha2(request)).await
     }
 }
 
@@ -767,6 +1025,18 @@ pub trait DaprInterface: Sized + Send {
         &mut self,
         request: InvokeActorRequest,
     ) -> Result<InvokeActorResponse, Error>;
+    async fn get_actor_reminder(
+        &mut self,
+        request: GetActorReminderRequest,
+    ) -> Result<GetActorReminderResponse, Error>;
+    async fn list_actor_reminders(
+        &mut self,
+        request: ListActorRemindersRequest,
+    ) -> Result<ListActorRemindersResponse, Error>;
+    async fn unregister_actor_reminders_by_type(
+        &mut self,
+        request: UnregisterActorRemindersByTypeRequest,
+    ) -> Result<UnregisterActorRemindersByTypeResponse, Error>;
     async fn get_configuration(
         &mut self,
         request: GetConfigurationRequest,
@@ -839,12 +1109,7 @@ async fn connect_plain(
     Ok(dapr_v1::dapr_client::DaprClient::connect(addr).await?)
 }
 
-async fn connect_intercepted(
-    addr: String,
-) -> Result<
-    dapr_v1::dapr_client::DaprClient<InterceptedService<TonicChannel, ApiTokenInterceptor>>,
-    Error,
-> {
+async fn connect_intercepted(addr: String) -> Result<TonicClientWithAuth, Error> {
     // The intercepted variant cannot be constructed from just an address
     // because the interceptor needs to be supplied. Use [`Client::new`] /
     // [`Client::from_options`] / [`Client::connect_with_address`] instead,
@@ -854,6 +1119,39 @@ async fn connect_intercepted(
     ))
 }
 
+/// Connect an intercepted tonic client as described by `opts`: the api
+/// token (or token provider), the connect timeout, TLS via the address
+/// scheme and the trace-context interceptor.
+///
+/// Shared by [`Client::from_options`] and the actor runtime, so that calls
+/// made from inside an actor authenticate like the main client.
+pub(crate) async fn connect_with_options(
+    opts: &ClientOptions,
+) -> Result<TonicClientWithAuth, Error> {
+    let address = opts.address().to_string();
+    let interceptor = match opts.token_provider() {
+        Some(provider) => ApiTokenInterceptor::from_shared_provider(provider.clone()),
+        None => ApiTokenInterceptor::try_new(opts.api_token().map(|s| s.to_string()))?,
+    };
+
+    let sanitized_address = crate::error::sanitize_endpoint_for_diagnostics(&address);
+    let endpoint = tonic::transport::Endpoint::from_shared(address)
+        .map_err(|_| Error::InvalidEndpoint(sanitized_address))?
+        .connect_timeout(opts.timeout());
+
+    let channel = match tokio::time::timeout(opts.timeout(), endpoint.connect()).await {
+        Ok(Ok(c)) => c,
+        Ok(Err(e)) => return Err(Error::from(e)),
+        Err(_) => return Err(Error::ConnectTimeout),
+    };
+
+    let channel = InterceptedService::new(
+        InterceptedService::new(channel, TraceContextInterceptor),
+        interceptor,
+    );
+    Ok(dapr_v1::dapr_client::DaprClient::new(channel))
+}
+
 macro_rules! impl_dapr_interface_for {
     ($type:ty, $connect_fn:path) => {
         #[async_trait]
@@ -863,214 +1161,429 @@ macro_rules! impl_dapr_interface_for {
             }
 
             async fn publish_event(&mut self, request: PublishEventRequest) -> Result<(), Error> {
-                self.publish_event(Request::new(request))
-                    .await?
-                    .into_inner();
-                Ok(())
+                let pubsub_name = request.pubsub_name.clone();
+                let info = CallInfo::new("pubsub", "PublishEvent")
+                    .component(&pubsub_name)
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    self.publish_event(CallOptions::request(request))
+                        .await?
+                        .into_inner();
+                    Ok(())
+                })
+                .await
             }
 
             async fn invoke_service(
                 &mut self,
                 request: InvokeServiceRequest,
             ) -> Result<InvokeServiceResponse, Error> {
-                Ok(self
-                    .invoke_service(Request::new(request))
-                    .await?
-                    .into_inner())
+                let app_id = request.id.clone();
+                let info = CallInfo::new("invoke", "InvokeService")
+                    .component(&app_id)
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    Ok(self
+                        .invoke_service(CallOptions::request(request))
+                        .await?
+                        .into_inner())
+                })
+                .await
             }
 
             async fn invoke_binding(
                 &mut self,
                 request: InvokeBindingRequest,
             ) -> Result<InvokeBindingResponse, Error> {
-                Ok(self
-                    .invoke_binding(Request::new(request))
-                    .await?
-                    .into_inner())
+                let name = request.name.clone();
+                let info = CallInfo::new("bindings", "InvokeBinding")
+                    .component(&name)
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    Ok(self
+                        .invoke_binding(CallOptions::request(request))
+                        .await?
+                        .into_inner())
+                })
+                .await
             }
 
             async fn get_secret(
                 &mut self,
                 request: GetSecretRequest,
             ) -> Result<GetSecretResponse, Error> {
-                Ok(self.get_secret(Request::new(request)).await?.into_inner())
+                let store_name = request.store_name.clone();
+                let info = CallInfo::new("secrets", "GetSecret")
+                    .component(&store_name)
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    Ok(self
+                        .get_secret(CallOptions::request(request))
+                        .await?
+                        .into_inner())
+                })
+                .await
             }
 
             async fn get_bulk_secret(
                 &mut self,
                 request: GetBulkSecretRequest,
             ) -> Result<GetBulkSecretResponse, Error> {
-                Ok(self
-                    .get_bulk_secret(Request::new(request))
-                    .await?
-                    .into_inner())
+                let store_name = request.store_name.clone();
+                let info = CallInfo::new("secrets", "GetBulkSecret")
+                    .component(&store_name)
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    Ok(self
+                        .get_bulk_secret(CallOptions::request(request))
+                        .await?
+                        .into_inner())
+                })
+                .await
             }
 
             async fn get_state(
                 &mut self,
                 request: GetStateRequest,
             ) -> Result<GetStateResponse, Error> {
-                Ok(self.get_state(Request::new(request)).await?.into_inner())
+                let store_name = request.store_name.clone();
+                let info = CallInfo::new("state", "GetState")
+                    .component(&store_name)
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    Ok(self
+                        .get_state(CallOptions::request(request))
+                        .await?
+                        .into_inner())
+                })
+                .await
             }
 
             async fn save_state(&mut self, request: SaveStateRequest) -> Result<(), Error> {
-                self.save_state(Request::new(request)).await?.into_inner();
-                Ok(())
+                let store_name = request.store_name.clone();
+                let info = CallInfo::new("state", "SaveState")
+                    .component(&store_name)
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    self.save_state(CallOptions::request(request))
+                        .await?
+                        .into_inner();
+                    Ok(())
+                })
+                .await
             }
 
             async fn query_state_alpha1(
                 &mut self,
                 request: QueryStateRequest,
             ) -> Result<QueryStateResponse, Error> {
-                Ok(self
-                    .query_state_alpha1(Request::new(request))
-                    .await?
-                    .into_inner())
+                let store_name = request.store_name.clone();
+                let info = CallInfo::new("state", "QueryStateAlpha1")
+                    .component(&store_name)
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    Ok(self
+                        .query_state_alpha1(CallOptions::request(request))
+                        .await?
+                        .into_inner())
+                })
+                .await
             }
 
             async fn delete_state(&mut self, request: DeleteStateRequest) -> Result<(), Error> {
-                self.delete_state(Request::new(request)).await?.into_inner();
-                Ok(())
+                let store_name = request.store_name.clone();
+                let info = CallInfo::new("state", "DeleteState")
+                    .component(&store_name)
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    self.delete_state(CallOptions::request(request))
+                        .await?
+                        .into_inner();
+                    Ok(())
+                })
+                .await
             }
 
             async fn delete_bulk_state(
                 &mut self,
                 request: DeleteBulkStateRequest,
             ) -> Result<(), Error> {
-                self.delete_bulk_state(Request::new(request))
-                    .await?
-                    .into_inner();
-                Ok(())
+                let store_name = request.store_name.clone();
+                let info = CallInfo::new("state", "DeleteBulkState")
+                    .component(&store_name)
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    self.delete_bulk_state(CallOptions::request(request))
+                        .await?
+                        .into_inner();
+                    Ok(())
+                })
+                .await
             }
 
             async fn set_metadata(&mut self, request: SetMetadataRequest) -> Result<(), Error> {
-                self.set_metadata(Request::new(request)).await?.into_inner();
-                Ok(())
+                let info = CallInfo::new("metadata", "SetMetadata")
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    self.set_metadata(CallOptions::request(request))
+                        .await?
+                        .into_inner();
+                    Ok(())
+                })
+                .await
             }
 
             async fn get_metadata(&mut self) -> Result<GetMetadataResponse, Error> {
-                Ok(self.get_metadata(GetMetadataRequest {}).await?.into_inner())
+                let info = CallInfo::new("metadata", "GetMetadata");
+                instrument::client_call(info, async {
+                    Ok(self
+                        .get_metadata(CallOptions::request(GetMetadataRequest {}))
+                        .await?
+                        .into_inner())
+                })
+                .await
             }
 
             async fn invoke_actor(
                 &mut self,
                 request: InvokeActorRequest,
             ) -> Result<InvokeActorResponse, Error> {
-                Ok(self.invoke_actor(Request::new(request)).await?.into_inner())
+                let actor_type = request.actor_type.clone();
+                let info = CallInfo::new("actors", "InvokeActor")
+                    .component(&actor_type)
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    Ok(self
+                        .invoke_actor(CallOptions::request(request))
+                        .await?
+                        .into_inner())
+                })
+                .await
+            }
+
+            async fn get_actor_reminder(
+                &mut self,
+                request: GetActorReminderRequest,
+            ) -> Result<GetActorReminderResponse, Error> {
+                let actor_type = request.actor_type.clone();
+                let info = CallInfo::new("actors", "GetActorReminder")
+                    .component(&actor_type)
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    Ok(self
+                        .get_actor_reminder(CallOptions::request(request))
+                        .await?
+                        .into_inner())
+                })
+                .await
+            }
+
+            async fn list_actor_reminders(
+                &mut self,
+                request: ListActorRemindersRequest,
+            ) -> Result<ListActorRemindersResponse, Error> {
+                let actor_type = request.actor_type.clone();
+                let info = CallInfo::new("actors", "ListActorReminders")
+                    .component(&actor_type)
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    Ok(self
+                        .list_actor_reminders(CallOptions::request(request))
+                        .await?
+                        .into_inner())
+                })
+                .await
+            }
+
+            async fn unregister_actor_reminders_by_type(
+                &mut self,
+                request: UnregisterActorRemindersByTypeRequest,
+            ) -> Result<UnregisterActorRemindersByTypeResponse, Error> {
+                let actor_type = request.actor_type.clone();
+                let info = CallInfo::new("actors", "UnregisterActorRemindersByType")
+                    .component(&actor_type)
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    Ok(self
+                        .unregister_actor_reminders_by_type(CallOptions::request(request))
+                        .await?
+                        .into_inner())
+                })
+                .await
             }
 
             async fn get_configuration(
                 &mut self,
                 request: GetConfigurationRequest,
             ) -> Result<GetConfigurationResponse, Error> {
-                Ok(self
-                    .get_configuration(Request::new(request))
-                    .await?
-                    .into_inner())
+                let store_name = request.store_name.clone();
+                let info = CallInfo::new("configuration", "GetConfiguration")
+                    .component(&store_name)
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    Ok(self
+                        .get_configuration(CallOptions::request(request))
+                        .await?
+                        .into_inner())
+                })
+                .await
             }
 
             async fn subscribe_configuration(
                 &mut self,
                 request: SubscribeConfigurationRequest,
             ) -> Result<Streaming<SubscribeConfigurationResponse>, Error> {
-                Ok(self
-                    .subscribe_configuration(Request::new(request))
-                    .await?
-                    .into_inner())
+                let store_name = request.store_name.clone();
+                let info = CallInfo::new("configuration", "SubscribeConfiguration")
+                    .component(&store_name)
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    Ok(self
+                        .subscribe_configuration(CallOptions::request(request))
+                        .await?
+                        .into_inner())
+                })
+                .await
             }
 
             async fn unsubscribe_configuration(
                 &mut self,
                 request: UnsubscribeConfigurationRequest,
             ) -> Result<UnsubscribeConfigurationResponse, Error> {
-                Ok(self
-                    .unsubscribe_configuration(Request::new(request))
-                    .await?
-                    .into_inner())
+                let store_name = request.store_name.clone();
+                let info = CallInfo::new("configuration", "UnsubscribeConfiguration")
+                    .component(&store_name)
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    Ok(self
+                        .unsubscribe_configuration(CallOptions::request(request))
+                        .await?
+                        .into_inner())
+                })
+                .await
             }
 
             async fn encrypt(
                 &mut self,
                 request: Vec<EncryptRequest>,
             ) -> Result<Vec<StreamPayload>, Status> {
-                let request = Request::new(tokio_stream::iter(request));
-                let stream = self.encrypt_alpha1(request).await?;
-                let mut stream = stream.into_inner();
-                let mut return_data = vec![];
-                while let Some(resp) = stream.next().await {
-                    if let Ok(resp) = resp
-                        && let Some(data) = resp.payload
-                    {
-                        return_data.push(data)
+                let component = request
+                    .first()
+                    .and_then(|r| r.options.as_ref())
+                    .map(|o| o.component_name.clone())
+                    .unwrap_or_default();
+                let info = CallInfo::new("crypto", "EncryptAlpha1")
+                    .component(&component)
+                    .request(&request);
+                instrument::client_call(info, async {
+                    let request = CallOptions::request(tokio_stream::iter(request));
+                    let stream = self.encrypt_alpha1(request).await?;
+                    let mut stream = stream.into_inner();
+                    let mut return_data = vec![];
+                    while let Some(resp) = stream.next().await {
+                        if let Ok(resp) = resp
+                            && let Some(data) = resp.payload
+                        {
+                            return_data.push(data)
+                        }
                     }
-                }
-                Ok(return_data)
+                    Ok(return_data)
+                })
+                .await
             }
 
             async fn decrypt(&mut self, request: Vec<DecryptRequest>) -> Result<Vec<u8>, Status> {
-                let request = Request::new(tokio_stream::iter(request));
-                let stream = self.decrypt_alpha1(request).await?;
-                let mut stream = stream.into_inner();
-                let mut data = vec![];
-                while let Some(resp) = stream.next().await {
-                    if let Ok(resp) = resp
-                        && let Some(mut payload) = resp.payload
-                    {
-                        data.append(payload.data.as_mut())
+                let component = request
+                    .first()
+                    .and_then(|r| r.options.as_ref())
+                    .map(|o| o.component_name.clone())
+                    .unwrap_or_default();
+                let info = CallInfo::new("crypto", "DecryptAlpha1")
+                    .component(&component)
+                    .request(&request);
+                instrument::client_call(info, async {
+                    let request = CallOptions::request(tokio_stream::iter(request));
+                    let stream = self.decrypt_alpha1(request).await?;
+                    let mut stream = stream.into_inner();
+                    let mut data = vec![];
+                    while let Some(resp) = stream.next().await {
+                        if let Ok(resp) = resp
+                            && let Some(mut payload) = resp.payload
+                        {
+                            data.append(payload.data.as_mut())
+                        }
                     }
-                }
-                Ok(data)
+                    Ok(data)
+                })
+                .await
             }
 
             async fn schedule_job(
                 &mut self,
                 request: ScheduleJobRequest,
             ) -> Result<ScheduleJobResponse, Error> {
-                let fallback = request.clone();
-                match self.schedule_job(request).await {
-                    Ok(resp) => Ok(resp.into_inner()),
-                    Err(status) if is_method_not_found(&status) =>
-                    {
-                        #[allow(deprecated)]
-                        Ok(self.schedule_job_alpha1(fallback).await?.into_inner())
+                let info =
+                    CallInfo::new("jobs", "ScheduleJob").request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    let fallback = request.clone();
+                    match self.schedule_job(CallOptions::request(request)).await {
+                        Ok(resp) => Ok(resp.into_inner()),
+                        Err(status) if is_method_not_found(&status) =>
+                        {
+                            #[allow(deprecated)]
+                            Ok(self
+                                .schedule_job_alpha1(CallOptions::request(fallback))
+                                .await?
+                                .into_inner())
+                        }
+                        Err(status) => Err(status.into()),
                     }
-                    Err(status) => Err(status.into()),
-                }
+                })
+                .await
             }
 
             async fn get_job(&mut self, request: GetJobRequest) -> Result<GetJobResponse, Error> {
-                let fallback = request.clone();
-                match self.get_job(Request::new(request)).await {
-                    Ok(resp) => Ok(resp.into_inner()),
-                    Err(status) if is_method_not_found(&status) =>
-                    {
-                        #[allow(deprecated)]
-                        Ok(self
-                            .get_job_alpha1(Request::new(fallback))
-                            .await?
-                            .into_inner())
+                let info = CallInfo::new("jobs", "GetJob").request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    let fallback = request.clone();
+                    match self.get_job(CallOptions::request(request)).await {
+                        Ok(resp) => Ok(resp.into_inner()),
+                        Err(status) if is_method_not_found(&status) =>
+                        {
+                            #[allow(deprecated)]
+                            Ok(self
+                                .get_job_alpha1(CallOptions::request(fallback))
+                                .await?
+                                .into_inner())
+                        }
+                        Err(status) => Err(status.into()),
                     }
-                    Err(status) => Err(status.into()),
-                }
+                })
+                .await
             }
 
             async fn delete_job(
                 &mut self,
                 request: DeleteJobRequest,
             ) -> Result<DeleteJobResponse, Error> {
-                let fallback = request.clone();
-                match self.delete_job(Request::new(request)).await {
-                    Ok(resp) => Ok(resp.into_inner()),
-                    Err(status) if is_method_not_found(&status) =>
-                    {
-                        #[allow(deprecated)]
-                        Ok(self
-                            .delete_job_alpha1(Request::new(fallback))
-                            .await?
-                            .into_inner())
+                let info =
+                    CallInfo::new("jobs", "DeleteJob").request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    let fallback = request.clone();
+                    match self.delete_job(CallOptions::request(request)).await {
+                        Ok(resp) => Ok(resp.into_inner()),
+                        Err(status) if is_method_not_found(&status) =>
+                        {
+                            #[allow(deprecated)]
+                            Ok(self
+                                .delete_job_alpha1(CallOptions::request(fallback))
+                                .await?
+                                .into_inner())
+                        }
+                        Err(status) => Err(status.into()),
                     }
-                    Err(status) => Err(status.into()),
-                }
+                })
+                .await
             }
 
             #[allow(deprecated)]
@@ -1078,7 +1591,15 @@ macro_rules! impl_dapr_interface_for {
                 &mut self,
                 request: ScheduleJobRequest,
             ) -> Result<ScheduleJobResponse, Error> {
-                Ok(self.schedule_job_alpha1(request).await?.into_inner())
+                let info = CallInfo::new("jobs", "ScheduleJobAlpha1")
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    Ok(self
+                        .schedule_job_alpha1(CallOptions::request(request))
+                        .await?
+                        .into_inner())
+                })
+                .await
             }
 
             #[allow(deprecated)]
@@ -1086,10 +1607,15 @@ macro_rules! impl_dapr_interface_for {
                 &mut self,
                 request: GetJobRequest,
             ) -> Result<GetJobResponse, Error> {
-                Ok(self
-                    .get_job_alpha1(Request::new(request))
-                    .await?
-                    .into_inner())
+                let info =
+                    CallInfo::new("jobs", "GetJobAlpha1").request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    Ok(self
+                        .get_job_alpha1(CallOptions::request(request))
+                        .await?
+                        .into_inner())
+                })
+                .await
             }
 
             #[allow(deprecated)]
@@ -1097,47 +1623,79 @@ macro_rules! impl_dapr_interface_for {
                 &mut self,
                 request: DeleteJobRequest,
             ) -> Result<DeleteJobResponse, Error> {
-                Ok(self
-                    .delete_job_alpha1(Request::new(request))
-                    .await?
-                    .into_inner())
+                let info = CallInfo::new("jobs", "DeleteJobAlpha1")
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    Ok(self
+                        .delete_job_alpha1(CallOptions::request(request))
+                        .await?
+                        .into_inner())
+                })
+                .await
             }
 
             async fn delete_jobs_by_prefix(
                 &mut self,
                 request: DeleteJobsByPrefixRequest,
             ) -> Result<DeleteJobsByPrefixResponse, Error> {
-                Ok(self
-                    .delete_jobs_by_prefix(Request::new(request))
-                    .await?
-                    .into_inner())
+                let info = CallInfo::new("jobs", "DeleteJobsByPrefix")
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    Ok(self
+                        .delete_jobs_by_prefix(CallOptions::request(request))
+                        .await?
+                        .into_inner())
+                })
+                .await
             }
 
             async fn list_jobs(
                 &mut self,
                 request: ListJobsRequest,
             ) -> Result<ListJobsResponse, Error> {
-                Ok(self.list_jobs(Request::new(request)).await?.into_inner())
+                let info =
+                    CallInfo::new("jobs", "ListJobs").request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    Ok(self
+                        .list_jobs(CallOptions::request(request))
+                        .await?
+                        .into_inner())
+                })
+                .await
             }
 
             async fn converse_alpha1(
                 &mut self,
                 request: ConversationRequest,
             ) -> Result<ConversationResponse, Error> {
-                Ok(self
-                    .converse_alpha1(Request::new(request))
-                    .await?
-                    .into_inner())
+                let name = request.name.clone();
+                let info = CallInfo::new("conversation", "ConverseAlpha1")
+                    .component(&name)
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    Ok(self
+                        .converse_alpha1(CallOptions::request(request))
+                        .await?
+                        .into_inner())
+                })
+                .await
             }
 
             async fn converse_alpha2(
                 &mut self,
                 request: ConversationRequestAlpha2,
             ) -> Result<ConversationResponseAlpha2, Error> {
-                Ok(self
-                    .converse_alpha2(Request::new(request))
-                    .await?
-                    .into_inner())
+                let name = request.name.clone();
+                let info = CallInfo::new("conversation", "ConverseAlpha2")
+                    .component(&name)
+                    .request(std::slice::from_ref(&request));
+                instrument::client_call(info, async {
+                    Ok(self
+                        .converse_alpha2(CallOptions::request(request))
+                        .await?
+                        .into_inner())
+                })
+                .await
             }
         }
     };
@@ -1148,10 +1706,7 @@ impl_dapr_interface_for!(
     connect_plain
 );
 
-impl_dapr_interface_for!(
-    dapr_v1::dapr_client::DaprClient<InterceptedService<TonicChannel, ApiTokenInterceptor>>,
-    connect_intercepted
-);
+impl_dapr_interface_for!(TonicClientWithAuth, connect_intercepted);
 
 /// A request from invoking a service
 pub type InvokeServiceRequest = dapr_v1::InvokeServiceRequest;
@@ -1224,6 +1779,27 @@ pub type InvokeActorRequest = dapr_v1::InvokeActorRequest;
 
 /// A response from invoking an actor
 pub type InvokeActorResponse = dapr_v1::InvokeActorResponse;
+
+/// A request for getting a reminder registered on an actor
+pub type GetActorReminderRequest = dapr_v1::GetActorReminderRequest;
+
+/// A response from getting a reminder registered on an actor
+pub type GetActorReminderResponse = dapr_v1::GetActorReminderResponse;
+
+/// A request for listing the reminders registered on actors of a type
+pub type ListActorRemindersRequest = dapr_v1::ListActorRemindersRequest;
+
+/// A response from listing the reminders registered on actors of a type
+pub type ListActorRemindersResponse = dapr_v1::ListActorRemindersResponse;
+
+/// A reminder in a [ListActorRemindersResponse]
+pub type NamedActorReminder = dapr_v1::NamedActorReminder;
+
+/// A request for unregistering all reminders of actors of a type
+pub type UnregisterActorRemindersByTypeRequest = dapr_v1::UnregisterActorRemindersByTypeRequest;
+
+/// A response from unregistering all reminders of actors of a type
+pub type UnregisterActorRemindersByTypeResponse = dapr_v1::UnregisterActorRemindersByTypeResponse;
 /// A request for getting configuration
 pub type GetConfigurationRequest = dapr_v1::GetConfigurationRequest;
 
@@ -1246,14 +1822,20 @@ pub type UnsubscribeConfigurationRequest = dapr_v1::UnsubscribeConfigurationRequ
 pub type TonicClient = dapr_v1::dapr_client::DaprClient<TonicChannel>;
 
 /// A tonic based gRPC client wrapped with an [`ApiTokenInterceptor`] that
-/// injects the configured `dapr-api-token` metadata on every outgoing call.
+/// injects the configured `dapr-api-token` metadata on every outgoing call,
+/// and a [`TraceContextInterceptor`] that propagates the current
+/// [`TraceContext`].
 ///
 /// This is the type returned by [`Client::new`], [`Client::from_options`],
-/// and [`Client::connect_with_address`]. When the configured token is empty,
-/// the interceptor is a no-op, so it is safe to use this type even when no
-/// token is required.
-pub type TonicClientWithAuth =
-    dapr_v1::dapr_client::DaprClient<InterceptedService<TonicChannel, ApiTokenInterceptor>>;
+/// and [`Client::connect_with_address`]. When the configured token is empty
+/// and no trace context is active, both interceptors are no-ops, so it is
+/// safe to use this type even when neither is required.
+pub type TonicClientWithAuth = dapr_v1::dapr_client::DaprClient<
+    InterceptedService<
+        InterceptedService<TonicChannel, TraceContextInterceptor>,
+        ApiTokenInterceptor,
+    >,
+>;
 
 impl Client<TonicClientWithAuth> {
     /// Create a new Dapr client using configuration drawn entirely from the
@@ -1316,22 +1898,44 @@ impl Client<TonicClientWithAuth> {
     /// # }
     /// ```
     pub async fn from_options(opts: ClientOptions) -> Result<Self, Error> {
-        let address = opts.address().to_string();
-        let interceptor = ApiTokenInterceptor::try_new(opts.api_token().map(|s| s.to_string()))?;
-
-        let sanitized_address = crate::error::sanitize_endpoint_for_diagnostics(&address);
-        let endpoint = tonic::transport::Endpoint::from_shared(address.clone())
-            .map_err(|_| Error::InvalidEndpoint(sanitized_address.clone()))?
-            .connect_timeout(opts.timeout());
-
-        let channel = match tokio::time::timeout(opts.timeout(), endpoint.connect()).await {
-            Ok(Ok(c)) => c,
-            Ok(Err(e)) => return Err(Error::from(e)),
-            Err(_) => return Err(Error::ConnectTimeout),
-        };
+        let grpc = connect_with_options(&opts).await?;
+        Ok(Client(
+            grpc,
+            opts.address().to_string(),
+            CallOptions::default(),
+        ))
+    }
 
-        let grpc = dapr_v1::dapr_client::DaprClient::with_interceptor(channel, interceptor);
-        Ok(Client(grpc, address))
+    /// Create a Dapr client connected in-memory to `sidecar`, for tests
+    /// that should not need a running `daprd`.
+    ///
+    /// Must be called from within a tokio runtime.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), dapr::error::Error> {
+    /// let sidecar = dapr::testing::MockSidecar::new();
+    /// let mut client = dapr::Client::for_testing(&sidecar);
+    /// client
+    ///     .save_state("store", "key", b"value".to_vec(), None, None, None)
+    ///     .await?;
+    /// assert_eq!(sidecar.state("store", "key"), Some(b"value".to_vec()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "testing")]
+    pub fn for_testing(sidecar: &crate::testing::MockSidecar) -> Self {
+        let channel = InterceptedService::new(
+            InterceptedService::new(sidecar.channel(), TraceContextInterceptor),
+            ApiTokenInterceptor::default(),
+        );
+        Client(
+            dapr_v1::dapr_client::DaprClient::new(channel),
+            "http://mock-sidecar".to_string(),
+            CallOptions::default(),
+        )
     }
 
     /// Create a new Dapr client connected to an explicit address. All other
@@ -1765,10 +2369,32 @@ impl From<ConversationMessageOfTool> for ConversationMessage {
     }
 }
 
+/// Parses an actor method response as an [`ActorErrorEnvelope`]. Only a
+/// JSON object can be one, so other responses are not parsed at all.
+fn actor_error_envelope(data: &[u8]) -> Option<ActorErrorEnvelope> {
+    let first = data.iter().find(|b| !b.is_ascii_whitespace())?;
+    if *first != b'{' {
+        return None;
+    }
+    serde_json::from_slice(data).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn actor_error_envelope_is_only_parsed_from_objects() {
+        let envelope = actor_error_envelope(br#" {"actorError":{"status":409,"body":"no"}}"#)
+            .expect("an error envelope");
+        assert_eq!(envelope.error.status, 409);
+
+        assert!(actor_error_envelope(br#"{"actorError":{"status":409}}"#).is_none());
+        assert!(actor_error_envelope(br#"[{"actorError":{"status":409,"body":"no"}}]"#).is_none());
+        assert!(actor_error_envelope(b"\x00\x01binary").is_none());
+        assert!(actor_error_envelope(b"").is_none());
+    }
+
     #[tokio::test]
     #[allow(deprecated)]
     async fn connect_with_port_returns_parse_error_for_invalid_port() {
@@ -1780,4 +2406,82 @@ mod tests {
             Ok(_) => panic!("invalid port should return an error"),
         }
     }
+
+    type RecordedCalls = std::sync::Arc<std::sync::Mutex<Vec<(String, axum::http::HeaderMap)>>>;
+
+    /// Starts a sidecar that records the path and headers of every gRPC call
+    /// and answers `Unimplemented`, returning its address.
+    async fn spawn_recording_sidecar() -> (String, RecordedCalls) {
+        use axum::http::{HeaderMap, Uri};
+
+        let calls = RecordedCalls::default();
+        let seen = calls.clone();
+        let sidecar =
+            axum::Router::new().fallback(move |uri: Uri, headers: HeaderMap| async move {
+                seen.lock().unwrap().push((uri.path().to_string(), headers));
+                (
+                    [("content-type", "application/grpc"), ("grpc-status", "12")],
+                    "",
+                )
+            });
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, sidecar).await });
+        (address, calls)
+    }
+
+    #[tokio::test]
+    async fn invoke_actor_sends_call_option_defaults() {
+        let (address, calls) = spawn_recording_sidecar().await;
+
+        let client = Client::connect_with_address(address).await.unwrap();
+        let mut client = client
+            .with_metadata("x-tenant-id", "acme")
+            .unwrap()
+            .with_call_options(CallOptions::new().with_deadline(Duration::from_secs(5)));
+        let result = client
+            .invoke_actor::<_, _, (), ()>("MyActor", "a1", "method", (), None)
+            .await;
+        assert!(result.is_err());
+
+        let calls = calls.lock().unwrap();
+        let (path, headers) = &calls[0];
+        assert_eq!(path, "/dapr.proto.runtime.v1.Dapr/InvokeActor");
+        assert_eq!(headers["x-tenant-id"], "acme");
+        assert!(headers.contains_key("grpc-timeout"));
+    }
+
+    #[tokio::test]
+    async fn per_call_options_reach_the_sidecar_from_spawned_tasks() {
+        let (address, calls) = spawn_recording_sidecar().await;
+        let client = Client::connect_with_address(address).await.unwrap();
+
+        let options = CallOptions::new()
+            .with_metadata("x-request-id", "42")
+            .unwrap()
+            .with_deadline(Duration::from_secs(5));
+        let per_call = client.with_call_options(options);
+        tokio::spawn(async move {
+            let mut client = per_call;
+            client.get_state("store", "key", None).await
+        })
+        .await
+        .unwrap()
+        .unwrap_err();
+        // The options apply to the returned client only.
+        client
+            .clone()
+            .get_state("store", "key", None)
+            .await
+            .unwrap_err();
+
+        let calls = calls.lock().unwrap();
+        let (path, headers) = &calls[0];
+        assert_eq!(path, "/dapr.proto.runtime.v1.Dapr/GetState");
+        assert_eq!(headers["x-request-id"], "42");
+        assert!(headers.contains_key("grpc-timeout"));
+        let (_, headers) = &calls[1];
+        assert!(!headers.contains_key("x-request-id"));
+        assert!(!headers.contains_key("grpc-timeout"));
+    }
 }
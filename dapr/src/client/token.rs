@@ -0,0 +1,351 @@
+//! Sources of Dapr API tokens that can change while the process runs.
+//!
+//! A [`TokenProvider`] is consulted on every call by
+//! [`super::ApiTokenInterceptor`] (outbound `DAPR_API_TOKEN`) and on every
+//! request by [`super::AppApiTokenLayer`] (inbound `APP_API_TOKEN`), so a
+//! rotated token takes effect without rebuilding the client or server.
+//!
+//! The SDK ships with providers for the common cases:
+//!
+//! - [`StaticTokenProvider`] — a fixed token.
+//! - [`EnvTokenProvider`] — re-reads an environment variable.
+//! - [`FileTokenProvider`] — re-reads a (mounted) file when it changes.
+//! - [`CallbackTokenProvider`] — periodically refreshes the token from an
+//!   async callback, e.g. a secret manager.
+
+#![warn(missing_docs)]
+
+use std::{
+    fmt,
+    future::Future,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Supplies the current API token.
+///
+/// `token` is called inline on the request path, so implementations should
+/// return a cached value rather than perform I/O on every call. Returning
+/// `None` (or an empty string) means no token is currently available.
+pub trait TokenProvider: Send + Sync {
+    /// The current token, if any.
+    fn token(&self) -> Option<String>;
+}
+
+/// A token that never changes.
+///
+/// # Examples
+///
+/// ```
+/// use dapr::client::{StaticTokenProvider, TokenProvider};
+///
+/// let provider = StaticTokenProvider::new("my-token");
+/// assert_eq!(provider.token().as_deref(), Some("my-token"));
+/// ```
+#[derive(Clone)]
+pub struct StaticTokenProvider {
+    token: Option<String>,
+}
+
+impl StaticTokenProvider {
+    /// Provide `token`. An empty string provides no token.
+    pub fn new(token: impl Into<String>) -> Self {
+        let token = token.into();
+        Self {
+            token: (!token.is_empty()).then_some(token),
+        }
+    }
+}
+
+impl TokenProvider for StaticTokenProvider {
+    fn token(&self) -> Option<String> {
+        self.token.clone()
+    }
+}
+
+impl fmt::Debug for StaticTokenProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticTokenProvider")
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+/// Reads the token from an environment variable on every call.
+///
+/// # Examples
+///
+/// ```
+/// use dapr::client::EnvTokenProvider;
+/// use dapr::client::DAPR_API_TOKEN_ENV;
+///
+/// let provider = EnvTokenProvider::new(DAPR_API_TOKEN_ENV);
+/// ```
+#[derive(Clone, Debug)]
+pub struct EnvTokenProvider {
+    var: String,
+}
+
+impl EnvTokenProvider {
+    /// Read the token from the environment variable `var`.
+    pub fn new(var: impl Into<String>) -> Self {
+        Self { var: var.into() }
+    }
+}
+
+impl TokenProvider for EnvTokenProvider {
+    fn token(&self) -> Option<String> {
+        std::env::var(&self.var).ok().filter(|t| !t.is_empty())
+    }
+}
+
+/// Reads the token from a file and picks up changes to it.
+///
+/// The file's modification time is checked at most once per
+/// [`FileTokenProvider::with_check_interval`] (one second by default) and its
+/// contents re-read when it changed. Surrounding whitespace, such as a
+/// trailing newline, is trimmed. If the file cannot be read the last token
+/// read successfully keeps being used.
+///
+/// # Examples
+///
+/// ```no_run
+/// use dapr::client::{ClientOptions, FileTokenProvider};
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let opts = ClientOptions::new()
+///     .with_token_provider(FileTokenProvider::new("/var/run/secrets/dapr/token"));
+/// let client = dapr::Client::from_options(opts).await?;
+/// # Ok(()) }
+/// ```
+pub struct FileTokenProvider {
+    path: PathBuf,
+    check_interval: Duration,
+    state: Mutex<FileState>,
+}
+
+struct FileState {
+    checked_at: Option<Instant>,
+    modified: Option<SystemTime>,
+    token: Option<String>,
+}
+
+impl FileTokenProvider {
+    /// Read the token from the file at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            check_interval: Duration::from_secs(1),
+            state: Mutex::new(FileState {
+                checked_at: None,
+                modified: None,
+                token: None,
+            }),
+        }
+    }
+
+    /// How often to check the file for changes. `Duration::ZERO` checks on
+    /// every call.
+    pub fn with_check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    /// The watched file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn refresh(&self, state: &mut FileState) {
+        let modified = match std::fs::metadata(&self.path).and_then(|m| m.modified()) {
+            Ok(modified) => modified,
+            Err(e) => {
+                log::warn!("failed to stat token file {}: {e}", self.path.display());
+                return;
+            }
+        };
+        if state.modified == Some(modified) {
+            return;
+        }
+        match std::fs::read_to_string(&self.path) {
+            Ok(contents) => {
+                let token = contents.trim();
+                state.token = (!token.is_empty()).then(|| token.to_string());
+                state.modified = Some(modified);
+            }
+            Err(e) => log::warn!("failed to read token file {}: {e}", self.path.display()),
+        }
+    }
+}
+
+impl TokenProvider for FileTokenProvider {
+    fn token(&self) -> Option<String> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let due = state
+            .checked_at
+            .is_none_or(|at| at.elapsed() >= self.check_interval);
+        if due {
+            self.refresh(&mut state);
+            state.checked_at = Some(Instant::now());
+        }
+        state.token.clone()
+    }
+}
+
+impl fmt::Debug for FileTokenProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileTokenProvider")
+            .field("path", &self.path)
+            .field("check_interval", &self.check_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Refreshes the token from an async callback in the background.
+///
+/// The callback runs once when the provider is created and then every
+/// refresh interval on a tokio task, which stops when the provider is
+/// dropped. A failed refresh is logged and the previous token kept.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use dapr::client::{CallbackTokenProvider, ClientOptions};
+///
+/// # async fn fetch_token_from_vault() -> Result<String, std::io::Error> { todo!() }
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let provider =
+///     CallbackTokenProvider::new(Duration::from_secs(300), fetch_token_from_vault).await;
+/// let opts = ClientOptions::new().with_token_provider(provider);
+/// let client = dapr::Client::from_options(opts).await?;
+/// # Ok(()) }
+/// ```
+#[derive(Clone)]
+pub struct CallbackTokenProvider {
+    token: Arc<RwLock<Option<String>>>,
+}
+
+impl CallbackTokenProvider {
+    /// Fetch the initial token with `callback`, then refresh it every
+    /// `refresh_interval`. Must be called within a tokio runtime.
+    pub async fn new<F, Fut, E>(refresh_interval: Duration, callback: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String, E>> + Send + 'static,
+        E: fmt::Display,
+    {
+        let token = Arc::new(RwLock::new(None));
+        refresh(&token, &callback).await;
+
+        let weak = Arc::downgrade(&token);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(refresh_interval).await;
+                let Some(token) = weak.upgrade() else {
+                    return;
+                };
+                refresh(&token, &callback).await;
+            }
+        });
+
+        Self { token }
+    }
+}
+
+async fn refresh<F, Fut, E>(token: &RwLock<Option<String>>, callback: &F)
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<String, E>>,
+    E: fmt::Display,
+{
+    match callback().await {
+        Ok(fresh) => {
+            *token.write().unwrap_or_else(|e| e.into_inner()) =
+                (!fresh.is_empty()).then_some(fresh);
+        }
+        Err(e) => log::warn!("failed to refresh Dapr API token: {e}"),
+    }
+}
+
+impl TokenProvider for CallbackTokenProvider {
+    fn token(&self) -> Option<String> {
+        self.token.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+impl fmt::Debug for CallbackTokenProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackTokenProvider")
+            .finish_non_exhaustive()
+    }
+}
+
+/// Compare two byte strings in time independent of where they first differ.
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn static_provider_treats_empty_as_none() {
+        assert_eq!(StaticTokenProvider::new("").token(), None);
+        assert_eq!(StaticTokenProvider::new("t").token().as_deref(), Some("t"));
+    }
+
+    #[test]
+    fn file_provider_picks_up_rotated_token() {
+        let path = std::env::temp_dir().join(format!("dapr-token-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "first\n").unwrap();
+        let provider = FileTokenProvider::new(&path).with_check_interval(Duration::ZERO);
+        assert_eq!(provider.token().as_deref(), Some("first"));
+
+        // Make sure the modification time moves even on coarse filesystems.
+        let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+        std::fs::write(&path, "second").unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(provider.token().as_deref(), Some("second"));
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(provider.token().as_deref(), Some("second"));
+    }
+
+    #[tokio::test]
+    async fn callback_provider_refreshes_in_background() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let provider = CallbackTokenProvider::new(Duration::from_millis(20), move || {
+            let n = counter.fetch_add(1, Ordering::SeqCst);
+            async move { Ok::<_, std::io::Error>(format!("token-{n}")) }
+        })
+        .await;
+        assert_eq!(provider.token().as_deref(), Some("token-0"));
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_ne!(provider.token().as_deref(), Some("token-0"));
+
+        // The refresh task stops once the provider is gone.
+        drop(provider);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let after_drop = calls.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), after_drop);
+    }
+
+    #[test]
+    fn constant_time_eq_compares_contents() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+    }
+}
@@ -0,0 +1,379 @@
+//! W3C trace-context propagation between the app and the Dapr sidecar.
+//!
+//! [`TraceContext`] models a single `traceparent` (plus optional
+//! `tracestate`) and knows how to read and write it in both the textual W3C
+//! form used by Dapr's HTTP channel and the `grpc-trace-bin` binary form used
+//! by its gRPC channel.
+//!
+//! The "current" context for outbound calls is resolved, in order, from:
+//!
+//! 1. the active OpenTelemetry span (with the `opentelemetry` feature), and
+//! 2. the context scoped onto the current task with [`TraceContext::scope`].
+//!
+//! The inbound handlers in [`crate::appcallback`] and
+//! [`crate::server::DaprHttpServer`] scope the context they receive from the
+//! sidecar automatically, so calls made from inside a handler continue the
+//! caller's trace without any extra wiring.
+
+#![warn(missing_docs)]
+
+use std::{fmt, future::Future};
+
+use tonic::metadata::{AsciiMetadataValue, BinaryMetadataValue, MetadataMap};
+
+/// Header / metadata key carrying the W3C `traceparent`.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Header / metadata key carrying the W3C `tracestate`.
+pub const TRACESTATE_HEADER: &str = "tracestate";
+
+/// Binary gRPC metadata key carrying the OpenCensus-encoded span context.
+pub const GRPC_TRACE_BIN_HEADER: &str = "grpc-trace-bin";
+
+const TRACEPARENT_VERSION: u8 = 0;
+const FLAG_SAMPLED: u8 = 0x01;
+const GRPC_TRACE_BIN_LEN: usize = 29;
+
+tokio::task_local! {
+    static CURRENT: TraceContext;
+}
+
+/// A W3C trace context: trace id, parent span id, trace flags and the
+/// optional vendor-specific `tracestate`.
+///
+/// # Examples
+///
+/// ```
+/// use dapr::client::TraceContext;
+///
+/// let ctx = TraceContext::from_traceparent(
+///     "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+///     Some("congo=t61rcWkgMzE"),
+/// )
+/// .unwrap();
+/// assert!(ctx.is_sampled());
+/// assert_eq!(ctx.trace_id(), "4bf92f3577b34da6a3ce929d0e0e4736");
+/// assert_eq!(ctx.tracestate(), Some("congo=t61rcWkgMzE"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceContext {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    flags: u8,
+    tracestate: Option<String>,
+}
+
+impl TraceContext {
+    /// Build a context from raw ids. Returns `None` if either id is all
+    /// zeros, which the W3C specification reserves as invalid.
+    pub fn new(trace_id: [u8; 16], span_id: [u8; 8], sampled: bool) -> Option<Self> {
+        if trace_id == [0; 16] || span_id == [0; 8] {
+            return None;
+        }
+        Some(Self {
+            trace_id,
+            span_id,
+            flags: if sampled { FLAG_SAMPLED } else { 0 },
+            tracestate: None,
+        })
+    }
+
+    /// Attach a `tracestate` value. An empty string clears it.
+    pub fn with_tracestate(mut self, tracestate: impl Into<String>) -> Self {
+        let tracestate = tracestate.into();
+        self.tracestate = if tracestate.is_empty() {
+            None
+        } else {
+            Some(tracestate)
+        };
+        self
+    }
+
+    /// Parse a `traceparent` header (and optional `tracestate`).
+    ///
+    /// Returns `None` when the header is malformed, uses the forbidden
+    /// version `ff`, or carries an all-zero trace or span id.
+    pub fn from_traceparent(traceparent: &str, tracestate: Option<&str>) -> Option<Self> {
+        let mut parts = traceparent.trim().split('-');
+        let version = parse_hex::<1>(parts.next()?)?[0];
+        if version == 0xff {
+            return None;
+        }
+        let trace_id = parse_hex::<16>(parts.next()?)?;
+        let span_id = parse_hex::<8>(parts.next()?)?;
+        let flags = parse_hex::<1>(parts.next()?)?[0];
+        // Version 00 has exactly four fields; future versions may append more.
+        if version == TRACEPARENT_VERSION && parts.next().is_some() {
+            return None;
+        }
+
+        let mut ctx = Self::new(trace_id, span_id, false)?;
+        ctx.flags = flags;
+        Some(ctx.with_tracestate(tracestate.map(str::trim).unwrap_or_default()))
+    }
+
+    /// Decode an OpenCensus `grpc-trace-bin` value.
+    pub fn from_grpc_trace_bin(bytes: &[u8]) -> Option<Self> {
+        // version(0) | 0 trace_id[16] | 1 span_id[8] | 2 options[1]
+        if bytes.len() < GRPC_TRACE_BIN_LEN || bytes[0] != 0 {
+            return None;
+        }
+        if bytes[1] != 0 || bytes[18] != 1 || bytes[27] != 2 {
+            return None;
+        }
+        let trace_id: [u8; 16] = bytes[2..18].try_into().ok()?;
+        let span_id: [u8; 8] = bytes[19..27].try_into().ok()?;
+        Self::new(trace_id, span_id, bytes[28] & FLAG_SAMPLED != 0)
+    }
+
+    /// Read the context from incoming gRPC metadata, preferring
+    /// `traceparent` and falling back to `grpc-trace-bin`.
+    pub fn from_metadata(metadata: &MetadataMap) -> Option<Self> {
+        let tracestate = metadata
+            .get(TRACESTATE_HEADER)
+            .and_then(|v| v.to_str().ok());
+        if let Some(ctx) = metadata
+            .get(TRACEPARENT_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|tp| Self::from_traceparent(tp, tracestate))
+        {
+            return Some(ctx);
+        }
+        let bin = metadata.get_bin(GRPC_TRACE_BIN_HEADER)?.to_bytes().ok()?;
+        let ctx = Self::from_grpc_trace_bin(&bin)?;
+        Some(ctx.with_tracestate(tracestate.unwrap_or_default()))
+    }
+
+    /// Read the context from incoming HTTP headers.
+    pub fn from_headers(headers: &http::HeaderMap) -> Option<Self> {
+        let traceparent = headers.get(TRACEPARENT_HEADER)?.to_str().ok()?;
+        let tracestate = headers.get(TRACESTATE_HEADER).and_then(|v| v.to_str().ok());
+        Self::from_traceparent(traceparent, tracestate)
+    }
+
+    /// Write `traceparent`, `tracestate` and `grpc-trace-bin` into outgoing
+    /// gRPC metadata, replacing any existing values.
+    pub fn inject(&self, metadata: &mut MetadataMap) {
+        if let Ok(value) = AsciiMetadataValue::try_from(self.traceparent()) {
+            metadata.insert(TRACEPARENT_HEADER, value);
+        }
+        match self
+            .tracestate
+            .as_deref()
+            .and_then(|ts| AsciiMetadataValue::try_from(ts).ok())
+        {
+            Some(value) => {
+                metadata.insert(TRACESTATE_HEADER, value);
+            }
+            None => {
+                metadata.remove(TRACESTATE_HEADER);
+            }
+        }
+        metadata.insert_bin(
+            GRPC_TRACE_BIN_HEADER,
+            BinaryMetadataValue::from_bytes(&self.to_grpc_trace_bin()),
+        );
+    }
+
+    /// The `traceparent` header value (`00-<trace-id>-<span-id>-<flags>`).
+    pub fn traceparent(&self) -> String {
+        format!(
+            "{TRACEPARENT_VERSION:02x}-{}-{}-{:02x}",
+            self.trace_id(),
+            self.span_id(),
+            self.flags
+        )
+    }
+
+    /// The `tracestate` header value, if any.
+    pub fn tracestate(&self) -> Option<&str> {
+        self.tracestate.as_deref()
+    }
+
+    /// The OpenCensus `grpc-trace-bin` encoding of this context.
+    pub fn to_grpc_trace_bin(&self) -> [u8; GRPC_TRACE_BIN_LEN] {
+        let mut out = [0u8; GRPC_TRACE_BIN_LEN];
+        out[2..18].copy_from_slice(&self.trace_id);
+        out[18] = 1;
+        out[19..27].copy_from_slice(&self.span_id);
+        out[27] = 2;
+        out[28] = self.flags & FLAG_SAMPLED;
+        out
+    }
+
+    /// The trace id as 32 lowercase hex characters.
+    pub fn trace_id(&self) -> String {
+        to_hex(&self.trace_id)
+    }
+
+    /// The parent span id as 16 lowercase hex characters.
+    pub fn span_id(&self) -> String {
+        to_hex(&self.span_id)
+    }
+
+    /// The raw trace id bytes.
+    pub fn trace_id_bytes(&self) -> [u8; 16] {
+        self.trace_id
+    }
+
+    /// The raw parent span id bytes.
+    pub fn span_id_bytes(&self) -> [u8; 8] {
+        self.span_id
+    }
+
+    /// Whether the caller sampled this trace.
+    pub fn is_sampled(&self) -> bool {
+        self.flags & FLAG_SAMPLED != 0
+    }
+
+    /// The context that outbound Dapr calls made from the current task will
+    /// propagate, if any. See the [module docs](self) for the lookup order.
+    pub fn current() -> Option<Self> {
+        #[cfg(feature = "opentelemetry")]
+        if let Some(ctx) = Self::from_opentelemetry(&opentelemetry::Context::current()) {
+            return Some(ctx);
+        }
+        CURRENT.try_with(Clone::clone).ok()
+    }
+
+    /// Run `fut` with this context as the task's current trace context, so
+    /// that Dapr calls made inside it continue this trace.
+    pub async fn scope<F: Future>(self, fut: F) -> F::Output {
+        CURRENT.scope(self, fut).await
+    }
+
+    /// Convert an OpenTelemetry context's active span into a
+    /// [`TraceContext`]. Returns `None` when there is no valid span.
+    #[cfg(feature = "opentelemetry")]
+    pub fn from_opentelemetry(cx: &opentelemetry::Context) -> Option<Self> {
+        use opentelemetry::trace::TraceContextExt;
+
+        let span = cx.span();
+        let sc = span.span_context();
+        if !sc.is_valid() {
+            return None;
+        }
+        let mut ctx = Self::new(
+            sc.trace_id().to_bytes(),
+            sc.span_id().to_bytes(),
+            sc.is_sampled(),
+        )?;
+        ctx.flags = sc.trace_flags().to_u8();
+        Some(ctx.with_tracestate(sc.trace_state().header()))
+    }
+
+    /// Convert this context into a remote OpenTelemetry span context.
+    #[cfg(feature = "opentelemetry")]
+    pub fn to_opentelemetry(&self) -> opentelemetry::trace::SpanContext {
+        use opentelemetry::trace::{SpanContext, SpanId, TraceFlags, TraceId, TraceState};
+
+        let trace_state = self
+            .tracestate
+            .as_deref()
+            .and_then(|ts| ts.parse::<TraceState>().ok())
+            .unwrap_or_default();
+        SpanContext::new(
+            TraceId::from_bytes(self.trace_id),
+            SpanId::from_bytes(self.span_id),
+            TraceFlags::new(self.flags),
+            true,
+            trace_state,
+        )
+    }
+}
+
+impl fmt::Display for TraceContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.traceparent())
+    }
+}
+
+fn parse_hex<const N: usize>(s: &str) -> Option<[u8; N]> {
+    if s.len() != N * 2 {
+        return None;
+    }
+    let mut out = [0u8; N];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(s.get(i * 2..i * 2 + 2)?, 16).ok()?;
+    }
+    Some(out)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn parses_and_formats_traceparent() {
+        let ctx = TraceContext::from_traceparent(TRACEPARENT, None).unwrap();
+        assert_eq!(ctx.trace_id(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(ctx.span_id(), "00f067aa0ba902b7");
+        assert!(ctx.is_sampled());
+        assert_eq!(ctx.traceparent(), TRACEPARENT);
+        assert_eq!(ctx.tracestate(), None);
+    }
+
+    #[test]
+    fn rejects_invalid_traceparent() {
+        for bad in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e473-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "00-zzf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        ] {
+            assert!(
+                TraceContext::from_traceparent(bad, None).is_none(),
+                "{bad:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn grpc_trace_bin_round_trips() {
+        let ctx = TraceContext::from_traceparent(TRACEPARENT, None).unwrap();
+        let bin = ctx.to_grpc_trace_bin();
+        assert_eq!(TraceContext::from_grpc_trace_bin(&bin), Some(ctx));
+        assert!(TraceContext::from_grpc_trace_bin(&bin[..20]).is_none());
+    }
+
+    #[test]
+    fn metadata_prefers_traceparent_and_falls_back_to_bin() {
+        let ctx = TraceContext::from_traceparent(TRACEPARENT, Some("k=v")).unwrap();
+        let mut md = MetadataMap::new();
+        ctx.inject(&mut md);
+        assert_eq!(md.get(TRACEPARENT_HEADER).unwrap(), TRACEPARENT);
+        assert_eq!(md.get(TRACESTATE_HEADER).unwrap(), "k=v");
+        assert_eq!(TraceContext::from_metadata(&md), Some(ctx.clone()));
+
+        md.remove(TRACEPARENT_HEADER);
+        assert_eq!(TraceContext::from_metadata(&md), Some(ctx));
+    }
+
+    #[test]
+    fn headers_are_parsed() {
+        let mut headers = http::HeaderMap::new();
+        headers.insert(TRACEPARENT_HEADER, TRACEPARENT.parse().unwrap());
+        headers.insert(TRACESTATE_HEADER, "k=v".parse().unwrap());
+        let ctx = TraceContext::from_headers(&headers).unwrap();
+        assert_eq!(ctx.tracestate(), Some("k=v"));
+    }
+
+    #[tokio::test]
+    async fn scope_sets_current_context() {
+        assert!(TraceContext::current().is_none());
+        let ctx = TraceContext::from_traceparent(TRACEPARENT, None).unwrap();
+        let seen = ctx.clone().scope(async { TraceContext::current() }).await;
+        assert_eq!(seen, Some(ctx));
+        assert!(TraceContext::current().is_none());
+    }
+}
@@ -38,6 +38,38 @@ pub enum Error {
     /// A value supplied as gRPC metadata (e.g. the `dapr-api-token` header)
     /// contained characters that are not legal in HTTP/2 metadata.
     InvalidMetadata,
+    /// A duration or schedule cannot be represented in a format the sidecar
+    /// accepts, e.g. because it is longer than Go's `time.Duration` allows.
+    InvalidDuration(String),
+    /// An actor method returned an error, see
+    /// [`crate::server::actor::ActorError::application`]. `status` is the
+    /// HTTP status the method reported and `body` its JSON encoded error;
+    /// decode it with [`Error::actor_method_error`].
+    ActorMethodError {
+        /// The HTTP status reported by the actor method.
+        status: u16,
+        /// The JSON encoded error body.
+        body: Vec<u8>,
+    },
+    /// An encoded `FileDescriptorSet` passed for gRPC server reflection could
+    /// not be decoded. The wrapped string describes why.
+    InvalidDescriptorSet(String),
+    /// A route was registered more than once with the app server, or clashes
+    /// with one the SDK serves itself such as `/dapr/config`. The wrapped
+    /// string is the route.
+    DuplicateRoute(String),
+}
+
+impl Error {
+    /// Decodes the body of an [`Error::ActorMethodError`] into the caller's
+    /// error type. Returns `None` for other errors or when the body is not a
+    /// `E`.
+    pub fn actor_method_error<E: serde::de::DeserializeOwned>(&self) -> Option<E> {
+        match self {
+            Error::ActorMethodError { body, .. } => serde_json::from_slice(body).ok(),
+            _ => None,
+        }
+    }
 }
 
 impl Display for Error {
@@ -112,6 +144,18 @@ pub struct GrpcError {
     _status: TonicStatus,
 }
 
+impl GrpcError {
+    /// The underlying gRPC status returned by the sidecar.
+    pub fn status(&self) -> &TonicStatus {
+        &self._status
+    }
+
+    /// The gRPC status code returned by the sidecar.
+    pub fn code(&self) -> tonic::Code {
+        self._status.code()
+    }
+}
+
 impl Display for GrpcError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{self:?}")
@@ -31,11 +31,18 @@ pub mod dapr {
             }
         }
     }
+
+    /// Encoded `FileDescriptorSet` of the Dapr protos, e.g. for gRPC
+    /// reflection.
+    pub const FILE_DESCRIPTOR_SET: &[u8] = include_bytes!("dapr/types.bin");
 }
 /// Module defining the error implementations.
 pub mod error;
 /// Module containing the 'Server' implementation.
 pub mod server;
+/// Module containing an in-memory Dapr sidecar for tests.
+#[cfg(feature = "testing")]
+pub mod testing;
 /// Module containing the Dapr Workflow client and worker interface.
 #[cfg(feature = "workflow")]
 pub mod workflow;
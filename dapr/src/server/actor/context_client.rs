@@ -1,9 +1,14 @@
-use crate::client::TonicClient;
+use super::schedule::{ReminderSpec, TimerSpec};
+#[cfg(feature = "testing")]
+use super::testing::InMemoryActors;
+use crate::client::{ActorReminder, TonicClientWithAuth};
 use crate::dapr::proto::common::v1::JobFailurePolicy;
 use crate::dapr::proto::runtime::v1 as dapr_v1;
 use crate::error::Error as DaprError;
 use prost_types::Any;
 use std::collections::HashMap;
+#[cfg(feature = "testing")]
+use std::sync::Arc;
 use std::time::Duration;
 use tonic::transport::Channel as TonicChannel;
 
@@ -36,20 +41,34 @@ impl From<ActorStateOperation> for TransactionalActorStateOperation {
     }
 }
 
+/// Where an [ActorContextClient] sends its calls.
+#[derive(Clone)]
+pub(crate) enum ActorBackend {
+    /// The Dapr sidecar.
+    Sidecar(TonicClientWithAuth),
+    /// The in-memory store of an [`super::testing::ActorTestHarness`].
+    #[cfg(feature = "testing")]
+    InMemory(Arc<InMemoryActors>),
+}
+
 /// A client for interacting with the Dapr runtime within the scope of an actor.
 ///
 /// Hosts methods for interacting with the Dapr sidecar specific to the actor instance.
 #[derive(Clone)]
 pub struct ActorContextClient {
-    client: TonicClient,
+    backend: ActorBackend,
     actor_type: String,
     actor_id: String,
 }
 
 impl ActorContextClient {
-    pub fn new(client: TonicClient, actor_type: &str, actor_id: &str) -> Self {
+    pub fn new(client: TonicClientWithAuth, actor_type: &str, actor_id: &str) -> Self {
+        Self::with_backend(ActorBackend::Sidecar(client), actor_type, actor_id)
+    }
+
+    pub(crate) fn with_backend(backend: ActorBackend, actor_type: &str, actor_id: &str) -> Self {
         ActorContextClient {
-            client,
+            backend,
             actor_type: actor_type.to_string(),
             actor_id: actor_id.to_string(),
         }
@@ -63,15 +82,18 @@ impl ActorContextClient {
     where
         K: Into<String>,
     {
-        Ok(self
-            .client
-            .get_actor_state(GetActorStateRequest {
-                actor_type: self.actor_type.to_string(),
-                actor_id: self.actor_id.to_string(),
-                key: key.into(),
-            })
-            .await?
-            .into_inner())
+        let request = GetActorStateRequest {
+            actor_type: self.actor_type.to_string(),
+            actor_id: self.actor_id.to_string(),
+            key: key.into(),
+        };
+        match &mut self.backend {
+            ActorBackend::Sidecar(client) => {
+                Ok(client.get_actor_state(request).await?.into_inner())
+            }
+            #[cfg(feature = "testing")]
+            ActorBackend::InMemory(actors) => Ok(actors.get_actor_state(request)),
+        }
     }
 
     /// Saves a state value within the scope of this instance of the actor.
@@ -82,14 +104,54 @@ impl ActorContextClient {
         &mut self,
         operations: Vec<ActorStateOperation>,
     ) -> Result<(), DaprError> {
-        self.client
-            .execute_actor_state_transaction(ExecuteActorStateTransactionRequest {
-                actor_type: self.actor_type.to_string(),
-                actor_id: self.actor_id.to_string(),
-                operations: operations.into_iter().map(|o| o.into()).collect(),
-            })
-            .await?
-            .into_inner();
+        self.execute_transaction(operations.into_iter().map(|o| o.into()).collect())
+            .await
+    }
+
+    pub(crate) async fn execute_transaction(
+        &mut self,
+        operations: Vec<TransactionalActorStateOperation>,
+    ) -> Result<(), DaprError> {
+        let request = ExecuteActorStateTransactionRequest {
+            actor_type: self.actor_type.to_string(),
+            actor_id: self.actor_id.to_string(),
+            operations,
+        };
+        match &mut self.backend {
+            ActorBackend::Sidecar(client) => {
+                client.execute_actor_state_transaction(request).await?;
+            }
+            #[cfg(feature = "testing")]
+            ActorBackend::InMemory(actors) => actors.execute_actor_state_transaction(request),
+        }
+        Ok(())
+    }
+
+    /// Registers a reminder on this actor, see [ReminderSpec].
+    ///
+    /// Fails with [DaprError::InvalidDuration] if the schedule cannot be
+    /// expressed in a format the sidecar accepts.
+    pub async fn register_reminder(&mut self, spec: ReminderSpec) -> Result<(), DaprError> {
+        let schedule = spec.encode_schedule()?;
+        let request = RegisterActorReminderRequest {
+            actor_type: self.actor_type.to_string(),
+            actor_id: self.actor_id.to_string(),
+            name: spec.name,
+            due_time: schedule.due_time,
+            period: schedule.period,
+            data: spec.data,
+            ttl: schedule.ttl,
+            // Defaults to true if not set, but we want to be explicit about it here.
+            overwrite: Option::from(spec.overwrite.unwrap_or(true)),
+            failure_policy: spec.failure_policy,
+        };
+        match &mut self.backend {
+            ActorBackend::Sidecar(client) => {
+                client.register_actor_reminder(request).await?;
+            }
+            #[cfg(feature = "testing")]
+            ActorBackend::InMemory(actors) => actors.register_actor_reminder(request),
+        }
         Ok(())
     }
 
@@ -103,6 +165,7 @@ impl ActorContextClient {
     /// * `ttl` - The time to live for the reminder.
     /// * `overwrite` - Whether to overwrite an existing reminder with the same name. Defaults to true if not set.
     /// * `failure_policy` - The job failure policy to apply to this reminder. If not set, the reminder will use the default job failure policy configured in Dapr. Use the client::JobFailurePolicyBuilder to construct a JobFailurePolicy with the desired settings.
+    #[deprecated(note = "use `register_reminder` with a `ReminderSpec`")]
     #[allow(clippy::too_many_arguments)]
     pub async fn register_actor_reminder<I>(
         &mut self,
@@ -117,31 +180,23 @@ impl ActorContextClient {
     where
         I: Into<String>,
     {
-        self.client
-            .register_actor_reminder(RegisterActorReminderRequest {
-                actor_type: self.actor_type.to_string(),
-                actor_id: self.actor_id.to_string(),
-                name: name.into(),
-                due_time: match due_time {
-                    None => "".to_string(),
-                    Some(t) => chrono::Duration::from_std(t).unwrap().to_string(),
-                },
-                period: match period {
-                    None => "".to_string(),
-                    Some(t) => chrono::Duration::from_std(t).unwrap().to_string(),
-                },
-                data,
-                ttl: match ttl {
-                    None => "".to_string(),
-                    Some(t) => chrono::Duration::from_std(t).unwrap().to_string(),
-                },
-                // Defaults to true if not set, but we want to be explicit about it here.
-                overwrite: Option::from(overwrite.unwrap_or(true)),
-                failure_policy,
-            })
-            .await?
-            .into_inner();
-        Ok(())
+        let mut spec = ReminderSpec::new(name).with_raw_data(data);
+        if let Some(due_time) = due_time {
+            spec = spec.with_due_time(due_time);
+        }
+        if let Some(period) = period {
+            spec = spec.with_period(period);
+        }
+        if let Some(ttl) = ttl {
+            spec = spec.with_ttl(ttl);
+        }
+        if let Some(overwrite) = overwrite {
+            spec = spec.with_overwrite(overwrite);
+        }
+        if let Some(failure_policy) = failure_policy {
+            spec = spec.with_failure_policy(failure_policy);
+        }
+        self.register_reminder(spec).await
     }
 
     /// Unregisters a reminder with the Dapr runtime.
@@ -152,14 +207,100 @@ impl ActorContextClient {
     where
         I: Into<String>,
     {
-        self.client
-            .unregister_actor_reminder(UnregisterActorReminderRequest {
-                actor_type: self.actor_type.to_string(),
-                actor_id: self.actor_id.to_string(),
-                name: name.into(),
-            })
-            .await?
-            .into_inner();
+        let request = UnregisterActorReminderRequest {
+            actor_type: self.actor_type.to_string(),
+            actor_id: self.actor_id.to_string(),
+            name: name.into(),
+        };
+        match &mut self.backend {
+            ActorBackend::Sidecar(client) => {
+                client.unregister_actor_reminder(request).await?;
+            }
+            #[cfg(feature = "testing")]
+            ActorBackend::InMemory(actors) => actors.unregister_actor_reminder(request),
+        }
+        Ok(())
+    }
+
+    /// Retrieves a reminder registered on this actor.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the reminder.
+    pub async fn get_reminder<I>(&mut self, name: I) -> Result<ActorReminder, DaprError>
+    where
+        I: Into<String>,
+    {
+        let name = name.into();
+        let request = GetActorReminderRequest {
+            actor_type: self.actor_type.to_string(),
+            actor_id: self.actor_id.to_string(),
+            name: name.clone(),
+        };
+        match &mut self.backend {
+            ActorBackend::Sidecar(client) => {
+                let response = client.get_actor_reminder(request).await?.into_inner();
+                Ok(ActorReminder::from_response(name, response))
+            }
+            #[cfg(feature = "testing")]
+            ActorBackend::InMemory(actors) => actors.get_actor_reminder(request),
+        }
+    }
+
+    /// Lists the reminders registered on this actor.
+    pub async fn list_reminders(&mut self) -> Result<Vec<ActorReminder>, DaprError> {
+        let request = ListActorRemindersRequest {
+            actor_type: self.actor_type.to_string(),
+            actor_id: Some(self.actor_id.to_string()),
+        };
+        match &mut self.backend {
+            ActorBackend::Sidecar(client) => {
+                let response = client.list_actor_reminders(request).await?.into_inner();
+                Ok(response.reminders.into_iter().map(Into::into).collect())
+            }
+            #[cfg(feature = "testing")]
+            ActorBackend::InMemory(actors) => Ok(actors.list_actor_reminders(request)),
+        }
+    }
+
+    /// Unregisters all reminders registered on this actor.
+    pub async fn unregister_all_reminders(&mut self) -> Result<(), DaprError> {
+        let request = UnregisterActorRemindersByTypeRequest {
+            actor_type: self.actor_type.to_string(),
+            actor_id: Some(self.actor_id.to_string()),
+        };
+        match &mut self.backend {
+            ActorBackend::Sidecar(client) => {
+                client.unregister_actor_reminders_by_type(request).await?;
+            }
+            #[cfg(feature = "testing")]
+            ActorBackend::InMemory(actors) => actors.unregister_actor_reminders_by_type(request),
+        }
+        Ok(())
+    }
+
+    /// Registers a timer on this actor, see [TimerSpec].
+    ///
+    /// Fails with [DaprError::InvalidDuration] if the schedule cannot be
+    /// expressed in a format the sidecar accepts.
+    pub async fn register_timer(&mut self, spec: TimerSpec) -> Result<(), DaprError> {
+        let schedule = spec.encode_schedule()?;
+        let request = RegisterActorTimerRequest {
+            actor_type: self.actor_type.to_string(),
+            actor_id: self.actor_id.to_string(),
+            name: spec.name,
+            due_time: schedule.due_time,
+            period: schedule.period,
+            data: spec.data,
+            callback: spec.callback.unwrap_or_default(),
+            ttl: schedule.ttl,
+        };
+        match &mut self.backend {
+            ActorBackend::Sidecar(client) => {
+                client.register_actor_timer(request).await?;
+            }
+            #[cfg(feature = "testing")]
+            ActorBackend::InMemory(actors) => actors.register_actor_timer(request),
+        }
         Ok(())
     }
 
@@ -172,6 +313,7 @@ impl ActorContextClient {
     /// * `data` - The data to pass to the timer when it is invoked.
     /// * `callback` - The callback name to include in the invocation.
     /// * `ttl` - The time to live for the timer.
+    #[deprecated(note = "use `register_timer` with a `TimerSpec`")]
     pub async fn register_actor_timer<I>(
         &mut self,
         name: I,
@@ -184,29 +326,20 @@ impl ActorContextClient {
     where
         I: Into<String>,
     {
-        self.client
-            .register_actor_timer(RegisterActorTimerRequest {
-                actor_type: self.actor_type.to_string(),
-                actor_id: self.actor_id.to_string(),
-                name: name.into(),
-                due_time: match due_time {
-                    None => "".to_string(),
-                    Some(t) => chrono::Duration::from_std(t).unwrap().to_string(),
-                },
-                period: match period {
-                    None => "".to_string(),
-                    Some(t) => chrono::Duration::from_std(t).unwrap().to_string(),
-                },
-                data,
-                callback: callback.unwrap_or_default(),
-                ttl: match ttl {
-                    None => "".to_string(),
-                    Some(t) => chrono::Duration::from_std(t).unwrap().to_string(),
-                },
-            })
-            .await?
-            .into_inner();
-        Ok(())
+        let mut spec = TimerSpec::new(name).with_raw_data(data);
+        if let Some(due_time) = due_time {
+            spec = spec.with_due_time(due_time);
+        }
+        if let Some(period) = period {
+            spec = spec.with_period(period);
+        }
+        if let Some(callback) = callback {
+            spec = spec.with_callback(callback);
+        }
+        if let Some(ttl) = ttl {
+            spec = spec.with_ttl(ttl);
+        }
+        self.register_timer(spec).await
     }
 
     /// Unregisters a timer with the Dapr runtime.
@@ -217,14 +350,18 @@ impl ActorContextClient {
     where
         I: Into<String>,
     {
-        self.client
-            .unregister_actor_timer(UnregisterActorTimerRequest {
-                actor_type: self.actor_type.to_string(),
-                actor_id: self.actor_id.to_string(),
-                name: name.into(),
-            })
-            .await?
-            .into_inner();
+        let request = UnregisterActorTimerRequest {
+            actor_type: self.actor_type.to_string(),
+            actor_id: self.actor_id.to_string(),
+            name: name.into(),
+        };
+        match &mut self.backend {
+            ActorBackend::Sidecar(client) => {
+                client.unregister_actor_timer(request).await?;
+            }
+            #[cfg(feature = "testing")]
+            ActorBackend::InMemory(actors) => actors.unregister_actor_timer(request),
+        }
         Ok(())
     }
 }
@@ -244,3 +381,9 @@ pub type RegisterActorReminderRequest = dapr_v1::RegisterActorReminderRequest;
 pub type UnregisterActorTimerRequest = dapr_v1::UnregisterActorTimerRequest;
 
 pub type UnregisterActorReminderRequest = dapr_v1::UnregisterActorReminderRequest;
+
+pub type GetActorReminderRequest = dapr_v1::GetActorReminderRequest;
+
+pub type ListActorRemindersRequest = dapr_v1::ListActorRemindersRequest;
+
+pub type UnregisterActorRemindersByTypeRequest = dapr_v1::UnregisterActorRemindersByTypeRequest;
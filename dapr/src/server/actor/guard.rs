@@ -0,0 +1,209 @@
+use std::{
+    future::Future,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    panic::AssertUnwindSafe,
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use axum::{
+    extract::{FromRequestParts, Path, Request, State},
+    http::request::Parts,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use futures::FutureExt;
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+use super::{
+    ACTOR_ERROR_HEADER, Actor, ActorPath, ActorRejection,
+    reentrancy::REENTRANCY_ID_HEADER,
+    runtime::{ActorState, ActorTurn, discard_state, save_state},
+};
+
+/// The turn started for a method invocation, shared between
+/// [`run_method_turn`] and the handler's [`ActorGuard`].
+#[derive(Clone)]
+struct MethodTurn(Arc<Mutex<ActorTurn>>);
+
+/// Extracts the actor a method invocation is addressed to.
+///
+/// The guard holds the actor's turn: no other method, timer, reminder or
+/// deactivation runs on the same actor until the handler returns, which is
+/// what gives the handler mutable access through [`DerefMut`]. Extraction
+/// fails if the actor the route was invoked on is not an `A`, e.g. because
+/// the registration's factory builds a different type.
+///
+/// # Example:
+/// ```ignore
+/// impl MyActor {
+///     async fn increment(mut actor: ActorGuard<Self>) -> Json<u64> {
+///         actor.count += 1;
+///         Json(actor.count)
+///     }
+/// }
+/// ```
+pub struct ActorGuard<A> {
+    turn: OwnedMutexGuard<ActorTurn>,
+    _actor: PhantomData<A>,
+}
+
+impl<A> ActorGuard<A> {
+    /// Awaits `fut`, typically a call to another actor, while letting calls
+    /// from the same reentrant call chain run on this actor.
+    ///
+    /// Only needed when reentrancy is enabled; see
+    /// [`super::reentrancy`]. The actor is not accessible while `fut` runs.
+    ///
+    /// If the returned future is dropped before completing, e.g. on a
+    /// timeout, a nested call may still hold the actor. Call
+    /// [`ActorGuard::reacquire`] before using the actor again:
+    ///
+    /// ```ignore
+    /// let call = actor.allow_reentry(other_actor.ping());
+    /// let result = tokio::time::timeout(Duration::from_secs(1), call).await;
+    /// actor.reacquire().await;
+    /// actor.pings += 1;
+    /// ```
+    pub async fn allow_reentry<F: Future>(&mut self, fut: F) -> F::Output {
+        self.turn.allow_reentry(fut).await
+    }
+
+    /// Waits until nested calls let in by an interrupted
+    /// [`ActorGuard::allow_reentry`] have handed the actor back. Does nothing
+    /// if the guard already has it.
+    pub async fn reacquire(&mut self) {
+        self.turn.reacquire().await
+    }
+}
+
+impl<A: Actor> ActorGuard<A> {
+    /// The actor, or `None` while an interrupted
+    /// [`ActorGuard::allow_reentry`] has it lent out. Dereferencing the guard
+    /// panics in that case.
+    pub fn try_get(&self) -> Option<&A> {
+        self.turn.downcast_ref()
+    }
+
+    /// The actor, or `None` while an interrupted
+    /// [`ActorGuard::allow_reentry`] has it lent out. Dereferencing the guard
+    /// panics in that case.
+    pub fn try_get_mut(&mut self) -> Option<&mut A> {
+        self.turn.downcast_mut()
+    }
+}
+
+impl<A: Actor> Deref for ActorGuard<A> {
+    type Target = A;
+
+    fn deref(&self) -> &A {
+        // The type was checked when extracting the guard.
+        self.try_get()
+            .expect("actor used after an interrupted `allow_reentry`; `reacquire` it first")
+    }
+}
+
+impl<A: Actor> DerefMut for ActorGuard<A> {
+    fn deref_mut(&mut self) -> &mut A {
+        self.try_get_mut()
+            .expect("actor used after an interrupted `allow_reentry`; `reacquire` it first")
+    }
+}
+
+#[async_trait]
+impl<A: Actor> FromRequestParts<ActorState> for ActorGuard<A> {
+    type Rejection = ActorRejection;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &ActorState,
+    ) -> Result<Self, Self::Rejection> {
+        let turn = match parts.extensions.get::<MethodTurn>() {
+            Some(MethodTurn(turn)) => turn.clone().lock_owned().await,
+            None => {
+                return Err(ActorRejection::ActorError(
+                    "ActorGuard used outside of an actor method route".to_string(),
+                ));
+            }
+        };
+        if turn.downcast_ref::<A>().is_none() {
+            return Err(ActorRejection::ActorError(format!(
+                "actor is not a {}",
+                std::any::type_name::<A>()
+            )));
+        }
+        Ok(ActorGuard {
+            turn,
+            _actor: PhantomData,
+        })
+    }
+}
+
+/// Runs an actor method invocation as one turn of the addressed actor.
+///
+/// The turn is started before the handler runs and ends after it returned,
+/// once the actor's state changes have been saved. A handler that panics
+/// gets the instance discarded and the caller an [`ActorError::Panicked`];
+/// the connection and other actors are unaffected.
+///
+/// [`ActorError::Panicked`]: super::ActorError::Panicked
+pub(crate) async fn run_method_turn(
+    State(state): State<ActorState>,
+    path: Result<Path<ActorPath>, axum::extract::rejection::PathRejection>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let path = match path {
+        Ok(path) => path,
+        Err(e) => {
+            log::error!("Error getting path: {e}");
+            return ActorRejection::Path(e).into_response();
+        }
+    };
+    let actor_type = &state.actor_type;
+    let actor_id = &path.actor_id;
+    log::info!("Request for actor_type: {actor_type}, actor_id: {actor_id}");
+    let reentrancy_id = req
+        .headers()
+        .get(REENTRANCY_ID_HEADER)
+        .and_then(|v| v.to_str().ok());
+    let turn = match state
+        .runtime
+        .get_or_create_actor_reentrant(actor_type, actor_id, reentrancy_id)
+        .await
+    {
+        Ok(turn) => Arc::new(Mutex::new(turn)),
+        Err(e) => {
+            log::error!("Error getting actor: {e}");
+            return e.into_response();
+        }
+    };
+
+    req.extensions_mut().insert(MethodTurn(turn.clone()));
+    let response = match AssertUnwindSafe(next.run(req)).catch_unwind().await {
+        Ok(response) => response,
+        Err(panic) => {
+            let mut turn = turn.lock_owned().await;
+            return state
+                .runtime
+                .discard_panicked(actor_type, actor_id, &mut turn, panic)
+                .await
+                .into_response();
+        }
+    };
+
+    // The handler has dropped its guard by now.
+    let mut turn = turn.lock_owned().await;
+    if !response.status().is_success() || response.headers().contains_key(ACTOR_ERROR_HEADER) {
+        discard_state(&mut turn).await;
+        return response;
+    }
+    match save_state(&mut turn).await {
+        Ok(()) => response,
+        Err(e) => {
+            log::error!("Error saving actor state: {e}");
+            e.into_response()
+        }
+    }
+}
@@ -1,33 +1,124 @@
 use async_trait::async_trait;
 use axum::{extract::rejection::PathRejection, http::StatusCode, response::IntoResponse};
 use serde::{Deserialize, Serialize};
-use std::{error::Error, fmt::Display, sync::Arc};
+use serde_json::Value;
+use std::{any::Any, error::Error, fmt::Display, time::Duration};
 
-use self::context_client::ActorContextClient;
+use self::{context_client::ActorContextClient, state_manager::ActorStateManager};
 
 pub use axum;
+pub use guard::ActorGuard;
 
 pub mod context_client;
+mod guard;
+pub mod reentrancy;
 pub mod runtime;
+pub mod schedule;
+pub mod state_manager;
+#[cfg(feature = "testing")]
+pub mod testing;
 
-pub type ActorFactory = Box<dyn Fn(&str, &str, ActorContextClient) -> Arc<dyn Actor> + Send + Sync>;
+pub type ActorFactory = Box<dyn Fn(&str, &str, ActorContextClient) -> Box<dyn Actor> + Send + Sync>;
 
+/// An actor implementation hosted by the [runtime::ActorRuntime].
+///
+/// Calls on an actor instance are turn-based: method invocations, timers,
+/// reminders and deactivation of the same actor never run concurrently, so
+/// handlers get exclusive (`&mut self`) access to the actor's fields.
+///
+/// Instances are stored as `dyn Actor` and recovered as their concrete type
+/// through [`Any`], see [`ActorGuard`] and [`runtime::ActorTurn::downcast_mut`].
 #[async_trait]
-pub trait Actor: Send + Sync {
-    async fn on_activate(&self) -> Result<(), ActorError>;
-    async fn on_deactivate(&self) -> Result<(), ActorError>;
-    async fn on_reminder(&self, _reminder_name: &str, _data: Vec<u8>) -> Result<(), ActorError>;
-    async fn on_timer(&self, _timer_name: &str, _data: Vec<u8>) -> Result<(), ActorError>;
+pub trait Actor: Any + Send + Sync {
+    async fn on_activate(&mut self) -> Result<(), ActorError>;
+    async fn on_deactivate(&mut self) -> Result<(), ActorError>;
+    async fn on_reminder(&mut self, _reminder_name: &str, _data: Vec<u8>)
+    -> Result<(), ActorError>;
+    async fn on_timer(&mut self, _timer_name: &str, _data: Vec<u8>) -> Result<(), ActorError>;
+
+    /// The actor's [ActorStateManager], if it keeps one. The runtime saves
+    /// its pending changes at the end of each successful turn.
+    fn state_manager(&mut self) -> Option<&mut ActorStateManager> {
+        None
+    }
 }
 
+/// Marks a method response as an actor error. The sidecar passes the body of
+/// such a response through to the caller instead of failing the call, see
+/// [`ActorError`]'s `IntoResponse` implementation.
+pub const ACTOR_ERROR_HEADER: &str = "X-DaprErrorResponseHeader";
+
 #[derive(Debug)]
 pub enum ActorError {
     NotRegistered,
     CorruptedState,
     MethodNotFound,
     ActorNotFound,
-    MethodError(Box<dyn Error>),
+    MethodError(Box<dyn Error + Send + Sync>),
     SerializationError(),
+    MaxStackDepthExceeded,
+    StateError(crate::error::Error),
+    /// An error defined by the application, returned to the caller of the
+    /// method with its status and JSON body. See [ActorError::application].
+    Application {
+        status: StatusCode,
+        body: Value,
+    },
+    /// The actor panicked during a turn, and the instance was discarded.
+    Panicked(String),
+    /// The actor is not activated after repeated activation failures:
+    /// `retry_after` is how long until the next attempt, or `None` if the
+    /// actor is poisoned, see
+    /// [runtime::ActorRuntimeConfig::with_max_activation_failures].
+    ActivationFailed {
+        attempts: u32,
+        retry_after: Option<Duration>,
+    },
+}
+
+impl ActorError {
+    /// An application error for the caller of an actor method, who receives
+    /// it as [`crate::error::Error::ActorMethodError`] and can decode `error`
+    /// back with [`crate::error::Error::actor_method_error`].
+    ///
+    /// The error travels as a successful response holding exactly
+    /// `{"actorError":{"status":…,"body":…}}`. A method whose regular result
+    /// serializes to that same shape is reported to the caller as an error
+    /// too, so avoid returning such values.
+    ///
+    /// # Example:
+    /// ```ignore
+    /// #[derive(Serialize, Deserialize)]
+    /// struct InsufficientFunds { balance: u64 }
+    ///
+    /// return Err(ActorError::application(
+    ///     StatusCode::CONFLICT,
+    ///     &InsufficientFunds { balance },
+    /// ));
+    /// ```
+    pub fn application<E: Serialize>(status: StatusCode, error: &E) -> Self {
+        match serde_json::to_value(error) {
+            Ok(body) => ActorError::Application { status, body },
+            Err(_) => ActorError::SerializationError(),
+        }
+    }
+
+    /// The status reported to the caller for this error.
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ActorError::NotRegistered | ActorError::MethodNotFound | ActorError::ActorNotFound => {
+                StatusCode::NOT_FOUND
+            }
+            ActorError::SerializationError() => StatusCode::BAD_REQUEST,
+            ActorError::Application { status, .. } => *status,
+            ActorError::ActivationFailed { .. } => StatusCode::SERVICE_UNAVAILABLE,
+            ActorError::Panicked(_)
+            | ActorError::CorruptedState
+            | ActorError::MethodError(_)
+            | ActorError::MaxStackDepthExceeded
+            | ActorError::StateError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
 }
 
 impl Display for ActorError {
@@ -39,15 +130,68 @@ impl Display for ActorError {
             ActorError::ActorNotFound => write!(f, "Actor not found"),
             ActorError::MethodError(e) => write!(f, "Method error: {e}"),
             ActorError::SerializationError() => write!(f, "Serialization error"),
+            ActorError::MaxStackDepthExceeded => {
+                write!(f, "Maximum reentrancy stack depth exceeded")
+            }
+            ActorError::StateError(e) => write!(f, "State error: {e}"),
+            ActorError::Application { status, body } => {
+                write!(f, "Application error ({status}): {body}")
+            }
+            ActorError::Panicked(message) => write!(f, "Actor panicked: {message}"),
+            ActorError::ActivationFailed {
+                attempts,
+                retry_after: Some(after),
+            } => write!(
+                f,
+                "Actor activation failed {attempts} times, retrying in {after:?}"
+            ),
+            ActorError::ActivationFailed {
+                attempts,
+                retry_after: None,
+            } => write!(f, "Actor activation failed {attempts} times, giving up"),
         }
     }
 }
 
+impl From<crate::error::Error> for ActorError {
+    fn from(e: crate::error::Error) -> Self {
+        ActorError::StateError(e)
+    }
+}
+
+/// The body of a method response for an [ActorError]. Application errors
+/// keep their body; the others carry their message as a JSON string.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ActorErrorEnvelope {
+    #[serde(rename = "actorError")]
+    pub(crate) error: ActorErrorBody,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ActorErrorBody {
+    pub(crate) status: u16,
+    pub(crate) body: Value,
+}
+
+/// Responds with `200 OK`, the [ACTOR_ERROR_HEADER] and an envelope holding
+/// the error's status and body: the sidecar only forwards the body of a
+/// failed actor call when marked this way, and reports any other status as
+/// an opaque internal error.
 impl IntoResponse for ActorError {
     fn into_response(self) -> axum::response::Response {
+        let status = self.status().as_u16();
+        let body = match self {
+            ActorError::Application { body, .. } => body,
+            other => Value::String(other.to_string()),
+        };
         (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            axum::Json(self.to_string()),
+            StatusCode::OK,
+            [(ACTOR_ERROR_HEADER, "true")],
+            axum::Json(ActorErrorEnvelope {
+                error: ActorErrorBody { status, body },
+            }),
         )
             .into_response()
     }
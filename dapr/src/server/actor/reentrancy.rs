@@ -0,0 +1,96 @@
+//! Actor reentrancy.
+//!
+//! With reentrancy enabled, the Dapr sidecar tags every actor call with a
+//! `Dapr-Reentrancy-Id` that is shared by all calls of one logical call
+//! chain. Calls carrying the id of the chain that currently holds an actor's
+//! turn may enter that actor again, so an A → B → A chain completes instead
+//! of deadlocking.
+//!
+//! **Reentry is opt-in per call.** The handler awaiting the outbound call
+//! has to lend the actor out with [`super::ActorGuard::allow_reentry`] for
+//! the nested call to get in. A handler that awaits the call directly keeps
+//! the actor's turn, so an A → B → A chain deadlocks until the sidecar times
+//! the call out, even with reentrancy enabled:
+//!
+//! ```ignore
+//! // Deadlocks if B calls back into this actor:
+//! let reply = b.ping().await;
+//! // Lets B's call back in:
+//! let reply = actor.allow_reentry(b.ping()).await;
+//! ```
+//!
+//! If the future returned by `allow_reentry` is dropped early, e.g. on a
+//! timeout, call [`super::ActorGuard::reacquire`] before using the actor
+//! again.
+
+use std::future::Future;
+
+use serde::{Deserialize, Serialize};
+
+/// Header (and actor invocation metadata key) carrying the reentrancy id.
+pub const REENTRANCY_ID_HEADER: &str = "Dapr-Reentrancy-Id";
+
+/// Default maximum number of nested calls into the same actor, matching the
+/// Dapr runtime's default.
+pub const DEFAULT_MAX_STACK_DEPTH: u32 = 32;
+
+tokio::task_local! {
+    static CURRENT: String;
+}
+
+/// Reentrancy settings advertised to the sidecar in `/dapr/config`.
+///
+/// # Example:
+/// ```no_run
+/// use dapr::server::actor::reentrancy::ReentrancyConfig;
+///
+/// # async fn run() {
+/// let dapr_server = dapr::server::DaprHttpServer::new()
+///     .await
+///     .with_actor_reentrancy(ReentrancyConfig::enabled().with_max_stack_depth(8));
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReentrancyConfig {
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_stack_depth: Option<u32>,
+}
+
+impl ReentrancyConfig {
+    /// Reentrancy enabled with the default maximum stack depth.
+    pub fn enabled() -> Self {
+        ReentrancyConfig {
+            enabled: true,
+            max_stack_depth: None,
+        }
+    }
+
+    /// Limit the number of nested calls into the same actor.
+    pub fn with_max_stack_depth(mut self, max_stack_depth: u32) -> Self {
+        self.max_stack_depth = Some(max_stack_depth);
+        self
+    }
+
+    /// The effective maximum stack depth.
+    pub fn max_stack_depth(&self) -> u32 {
+        self.max_stack_depth.unwrap_or(DEFAULT_MAX_STACK_DEPTH)
+    }
+}
+
+/// The reentrancy id of the actor call being handled by the current task.
+///
+/// Actor invocations made through [`crate::Client::invoke_actor`] while
+/// handling the call forward it, so the sidecar can recognise the chain.
+pub fn current_id() -> Option<String> {
+    CURRENT.try_with(Clone::clone).ok()
+}
+
+/// Run `fut` with `id` as the current reentrancy id.
+pub(crate) async fn scope<F: Future>(id: Option<String>, fut: F) -> F::Output {
+    match id {
+        Some(id) => CURRENT.scope(id, fut).await,
+        None => fut.await,
+    }
+}
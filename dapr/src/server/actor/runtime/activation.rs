@@ -0,0 +1,160 @@
+use std::{
+    any::Any,
+    collections::HashMap,
+    sync::{Arc, Mutex as SyncMutex},
+    time::{Duration, Instant},
+};
+
+use crate::server::actor::ActorError;
+
+/// A failed activation of an actor, passed to the hook registered with
+/// [`super::ActorTypeRegistration::on_activation_failed`].
+#[derive(Clone, Debug)]
+pub struct ActivationFailure {
+    /// The type of the actor.
+    pub actor_type: String,
+    /// The id of the actor.
+    pub actor_id: String,
+    /// Why the factory or `on_activate` failed.
+    pub error: String,
+    /// How many activations of the actor have failed in a row.
+    pub attempts: u32,
+    /// How long calls to the actor fail fast before activation is retried,
+    /// or `None` once the actor is poisoned.
+    pub retry_after: Option<Duration>,
+}
+
+pub(super) type ActivationFailedHook = Arc<dyn Fn(&ActivationFailure) + Send + Sync>;
+
+struct FailureRecord {
+    attempts: u32,
+    retry_at: Option<Instant>,
+}
+
+/// Consecutive activation failures per actor, which hold back further
+/// activations with exponential backoff.
+#[derive(Default)]
+pub(super) struct ActivationFailures {
+    records: SyncMutex<HashMap<(String, String), FailureRecord>>,
+}
+
+/// The backoff policy applied to failing activations.
+#[derive(Clone, Copy)]
+pub(super) struct Backoff {
+    pub(super) initial: Duration,
+    pub(super) max: Duration,
+    pub(super) max_failures: Option<u32>,
+}
+
+impl ActivationFailures {
+    /// Fails fast while the actor is backing off or poisoned.
+    pub(super) fn check(&self, key: &(String, String)) -> Result<(), ActorError> {
+        let records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(record) = records.get(key) else {
+            return Ok(());
+        };
+        match record.retry_at {
+            None => Err(ActorError::ActivationFailed {
+                attempts: record.attempts,
+                retry_after: None,
+            }),
+            Some(retry_at) => match retry_at.checked_duration_since(Instant::now()) {
+                Some(remaining) if !remaining.is_zero() => Err(ActorError::ActivationFailed {
+                    attempts: record.attempts,
+                    retry_after: Some(remaining),
+                }),
+                _ => Ok(()),
+            },
+        }
+    }
+
+    /// Records a failed activation, returning how it is reported.
+    pub(super) fn record(
+        &self,
+        key: &(String, String),
+        error: &ActorError,
+        backoff: Backoff,
+    ) -> ActivationFailure {
+        let mut records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        let record = records.entry(key.clone()).or_insert(FailureRecord {
+            attempts: 0,
+            retry_at: None,
+        });
+        record.attempts += 1;
+        let poisoned = backoff
+            .max_failures
+            .is_some_and(|max| record.attempts >= max);
+        let retry_after = (!poisoned).then(|| {
+            let factor = 2u32.saturating_pow(record.attempts - 1);
+            backoff.initial.saturating_mul(factor).min(backoff.max)
+        });
+        record.retry_at = retry_after.map(|after| Instant::now() + after);
+
+        ActivationFailure {
+            actor_type: key.0.clone(),
+            actor_id: key.1.clone(),
+            error: error.to_string(),
+            attempts: record.attempts,
+            retry_after,
+        }
+    }
+
+    /// Forgets the failures of an actor, after it activated or to lift its
+    /// poisoning.
+    pub(super) fn clear(&self, key: &(String, String)) -> bool {
+        let mut records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        records.remove(key).is_some()
+    }
+}
+
+/// The message of a caught panic.
+pub(super) fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "non-string panic payload".to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backs_off_then_poisons() {
+        let failures = ActivationFailures::default();
+        let key = ("MyActor".to_string(), "1".to_string());
+        let backoff = Backoff {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(3),
+            max_failures: Some(4),
+        };
+        let error = ActorError::CorruptedState;
+
+        assert!(failures.check(&key).is_ok());
+        let retries: Vec<_> = (0..4)
+            .map(|_| failures.record(&key, &error, backoff).retry_after)
+            .collect();
+        assert_eq!(
+            retries,
+            [
+                Some(Duration::from_secs(1)),
+                Some(Duration::from_secs(2)),
+                Some(Duration::from_secs(3)),
+                None
+            ]
+        );
+        assert!(matches!(
+            failures.check(&key),
+            Err(ActorError::ActivationFailed {
+                attempts: 4,
+                retry_after: None
+            })
+        ));
+
+        assert!(failures.clear(&key));
+        assert!(failures.check(&key).is_ok());
+    }
+}
@@ -0,0 +1,244 @@
+use std::time::Duration;
+
+use crate::server::actor::reentrancy::ReentrancyConfig;
+
+/// The idle timeout the Dapr sidecar applies when none is configured.
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// The idle scan interval the Dapr sidecar applies when none is configured.
+pub const DEFAULT_SCAN_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The drain timeout the Dapr sidecar applies when none is configured, also
+/// used to bound the drain when the app shuts down.
+pub const DEFAULT_DRAIN_ONGOING_CALL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How many actors are deactivated at once when the app shuts down, unless
+/// configured with [`ActorRuntimeConfig::with_drain_concurrency`].
+pub const DEFAULT_DRAIN_CONCURRENCY: usize = 16;
+
+/// How long calls to an actor fail fast after its first failed activation.
+/// The delay doubles with each further failure.
+pub const DEFAULT_ACTIVATION_BACKOFF: Duration = Duration::from_secs(1);
+
+/// The longest calls to an actor fail fast between activation attempts.
+pub const DEFAULT_MAX_ACTIVATION_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Actor runtime settings reported to the Dapr sidecar in `/dapr/config`.
+///
+/// Settings left unset are omitted, so the sidecar applies its own defaults.
+/// The idle timeout and scan interval are also used by the
+/// [`super::ActorRuntime`] itself to deactivate idle actors, falling back to
+/// [`DEFAULT_IDLE_TIMEOUT`] and [`DEFAULT_SCAN_INTERVAL`].
+///
+/// # Example:
+/// ```no_run
+/// use std::time::Duration;
+/// use dapr::server::actor::runtime::ActorRuntimeConfig;
+///
+/// # async fn run() {
+/// let dapr_server = dapr::server::DaprHttpServer::new().await.with_actor_config(
+///     ActorRuntimeConfig::new()
+///         .with_idle_timeout(Duration::from_secs(600))
+///         .with_drain_ongoing_call_timeout(Duration::from_secs(30))
+///         .with_drain_rebalanced_actors(true),
+/// );
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ActorRuntimeConfig {
+    idle_timeout: Option<Duration>,
+    scan_interval: Option<Duration>,
+    drain_ongoing_call_timeout: Option<Duration>,
+    drain_rebalanced_actors: Option<bool>,
+    reentrancy: Option<ReentrancyConfig>,
+    reminders_storage_partitions: Option<u32>,
+    max_active_actors: Option<usize>,
+    drain_concurrency: Option<usize>,
+    activation_backoff: Option<(Duration, Duration)>,
+    max_activation_failures: Option<u32>,
+}
+
+impl ActorRuntimeConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How long an actor may go without calls before it is deactivated.
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// How often to look for idle actors to deactivate.
+    pub fn with_scan_interval(mut self, interval: Duration) -> Self {
+        self.scan_interval = Some(interval);
+        self
+    }
+
+    /// How long to wait for in-flight calls when actors are moved to
+    /// another host, and when the app shuts down, see
+    /// [`super::ActorRuntime::deactivate_all`].
+    pub fn with_drain_ongoing_call_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_ongoing_call_timeout = Some(timeout);
+        self
+    }
+
+    /// Whether to wait for in-flight calls (up to the drain timeout) before
+    /// deactivating actors moved to another host.
+    pub fn with_drain_rebalanced_actors(mut self, drain: bool) -> Self {
+        self.drain_rebalanced_actors = Some(drain);
+        self
+    }
+
+    /// Reentrancy settings, see [`crate::server::actor::reentrancy`].
+    pub fn with_reentrancy(mut self, reentrancy: ReentrancyConfig) -> Self {
+        self.reentrancy = Some(reentrancy);
+        self
+    }
+
+    /// Number of partitions the sidecar splits each actor type's reminders
+    /// into in the state store.
+    pub fn with_reminders_storage_partitions(mut self, partitions: u32) -> Self {
+        self.reminders_storage_partitions = Some(partitions);
+        self
+    }
+
+    /// The most actors kept active at once across all types. When a new
+    /// actor would exceed it, the least recently used idle actor is
+    /// deactivated first. Enforced locally, not reported to the sidecar.
+    pub fn with_max_active_actors(mut self, max: usize) -> Self {
+        self.max_active_actors = Some(max);
+        self
+    }
+
+    /// How many actors are deactivated at once when the app shuts down.
+    /// Enforced locally, not reported to the sidecar.
+    pub fn with_drain_concurrency(mut self, concurrency: usize) -> Self {
+        self.drain_concurrency = Some(concurrency.max(1));
+        self
+    }
+
+    /// How long calls to an actor fail fast after a failed activation,
+    /// doubling from `initial` with each consecutive failure up to `max`.
+    /// Enforced locally, not reported to the sidecar.
+    pub fn with_activation_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.activation_backoff = Some((initial, max.max(initial)));
+        self
+    }
+
+    /// Poisons an actor after `failures` consecutive failed activations:
+    /// calls to it fail until
+    /// [`super::ActorRuntime::clear_activation_failures`] is called. By
+    /// default activation is retried indefinitely, with backoff.
+    pub fn with_max_activation_failures(mut self, failures: u32) -> Self {
+        self.max_activation_failures = Some(failures.max(1));
+        self
+    }
+
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout
+    }
+
+    pub fn scan_interval(&self) -> Option<Duration> {
+        self.scan_interval
+    }
+
+    pub fn drain_ongoing_call_timeout(&self) -> Option<Duration> {
+        self.drain_ongoing_call_timeout
+    }
+
+    pub fn drain_rebalanced_actors(&self) -> Option<bool> {
+        self.drain_rebalanced_actors
+    }
+
+    pub fn reentrancy(&self) -> Option<&ReentrancyConfig> {
+        self.reentrancy.as_ref()
+    }
+
+    pub fn reminders_storage_partitions(&self) -> Option<u32> {
+        self.reminders_storage_partitions
+    }
+
+    pub fn max_active_actors(&self) -> Option<usize> {
+        self.max_active_actors
+    }
+
+    pub fn drain_concurrency(&self) -> Option<usize> {
+        self.drain_concurrency
+    }
+
+    pub fn activation_backoff(&self) -> Option<(Duration, Duration)> {
+        self.activation_backoff
+    }
+
+    pub fn max_activation_failures(&self) -> Option<u32> {
+        self.max_activation_failures
+    }
+}
+
+/// Per actor type overrides of the [`ActorRuntimeConfig`], set with
+/// [`super::ActorTypeRegistration::with_config`].
+#[derive(Clone, Debug, Default)]
+pub struct ActorTypeConfig {
+    idle_timeout: Option<Duration>,
+    drain_ongoing_call_timeout: Option<Duration>,
+    drain_rebalanced_actors: Option<bool>,
+    reentrancy: Option<ReentrancyConfig>,
+    reminders_storage_partitions: Option<u32>,
+}
+
+impl ActorTypeConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// See [`ActorRuntimeConfig::with_idle_timeout`].
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// See [`ActorRuntimeConfig::with_drain_ongoing_call_timeout`].
+    pub fn with_drain_ongoing_call_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_ongoing_call_timeout = Some(timeout);
+        self
+    }
+
+    /// See [`ActorRuntimeConfig::with_drain_rebalanced_actors`].
+    pub fn with_drain_rebalanced_actors(mut self, drain: bool) -> Self {
+        self.drain_rebalanced_actors = Some(drain);
+        self
+    }
+
+    /// See [`ActorRuntimeConfig::with_reentrancy`].
+    pub fn with_reentrancy(mut self, reentrancy: ReentrancyConfig) -> Self {
+        self.reentrancy = Some(reentrancy);
+        self
+    }
+
+    /// See [`ActorRuntimeConfig::with_reminders_storage_partitions`].
+    pub fn with_reminders_storage_partitions(mut self, partitions: u32) -> Self {
+        self.reminders_storage_partitions = Some(partitions);
+        self
+    }
+
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout
+    }
+
+    pub fn drain_ongoing_call_timeout(&self) -> Option<Duration> {
+        self.drain_ongoing_call_timeout
+    }
+
+    pub fn drain_rebalanced_actors(&self) -> Option<bool> {
+        self.drain_rebalanced_actors
+    }
+
+    pub fn reentrancy(&self) -> Option<&ReentrancyConfig> {
+        self.reentrancy.as_ref()
+    }
+
+    pub fn reminders_storage_partitions(&self) -> Option<u32> {
+        self.reminders_storage_partitions
+    }
+}
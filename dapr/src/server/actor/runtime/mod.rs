@@ -1,10 +1,38 @@
-use axum::{Router, handler::Handler, routing::put};
-use std::{collections::HashMap, sync::Arc};
-use tokio::sync::RwLock;
+use axum::{Router, handler::Handler, middleware, routing::put};
+use futures::{FutureExt, StreamExt, future::BoxFuture};
+use serde::de::DeserializeOwned;
+use std::{
+    any::Any,
+    collections::HashMap,
+    fmt,
+    panic::AssertUnwindSafe,
+    sync::{Arc, RwLock as SyncRwLock},
+    time::{Duration, Instant},
+};
+use tokio::{sync::RwLock, task::JoinHandle};
 
-use crate::client::TonicClient;
+use crate::client::TonicClientWithAuth;
 
-use super::{Actor, ActorError, ActorFactory, context_client::ActorContextClient};
+use self::activation::{ActivationFailedHook, ActivationFailures, Backoff, panic_message};
+use self::turn::{ActorCell, Reentrancy};
+use super::{
+    Actor, ActorError, ActorFactory,
+    context_client::{ActorBackend, ActorContextClient},
+    guard::run_method_turn,
+    reentrancy::ReentrancyConfig,
+};
+
+pub use self::activation::ActivationFailure;
+pub use self::config::{
+    ActorRuntimeConfig, ActorTypeConfig, DEFAULT_ACTIVATION_BACKOFF, DEFAULT_DRAIN_CONCURRENCY,
+    DEFAULT_DRAIN_ONGOING_CALL_TIMEOUT, DEFAULT_IDLE_TIMEOUT, DEFAULT_MAX_ACTIVATION_BACKOFF,
+    DEFAULT_SCAN_INTERVAL,
+};
+pub use self::turn::ActorTurn;
+
+mod activation;
+mod config;
+mod turn;
 
 #[derive(Clone)]
 pub struct ActorState {
@@ -15,11 +43,16 @@ pub struct ActorState {
 type MethodRegistrationMap =
     HashMap<String, Box<dyn (FnOnce(Router, Arc<ActorRuntime>) -> Router) + Send + Sync>>;
 
+type TimerCallback = Arc<
+    dyn for<'a> Fn(&'a mut dyn Actor, Vec<u8>) -> BoxFuture<'a, Result<(), ActorError>>
+        + Send
+        + Sync,
+>;
+
 /// Describes the registration of an actor type, including the methods that can be invoked on it and the factory to create instances of it.
 /// # Example:
 /// ```ignore
-/// # use std::sync::Arc;
-/// # use dapr::server::actor::{context_client::ActorContextClient, Actor, ActorError, ActorFactory, runtime::ActorTypeRegistration};
+/// # use dapr::server::actor::{context_client::ActorContextClient, Actor, ActorError, ActorFactory, ActorGuard, runtime::ActorTypeRegistration};
 /// # use dapr::server::utils::DaprJson;
 /// # use dapr::actor;
 /// # use axum::{Json, Router};
@@ -41,10 +74,10 @@ type MethodRegistrationMap =
 /// # }
 /// #
 /// # impl MyActor {
-/// #     async fn do_stuff(&self, DaprJson(req): DaprJson<MyRequest>) -> Json<MyResponse> {
+/// #     async fn do_stuff(_actor: ActorGuard<Self>, DaprJson(req): DaprJson<MyRequest>) -> Json<MyResponse> {
 /// #         todo!()
 /// #     }
-/// #     async fn do_other_stuff(&self, DaprJson(req): DaprJson<MyRequest>) -> Json<MyResponse> {
+/// #     async fn do_other_stuff(_actor: ActorGuard<Self>, DaprJson(req): DaprJson<MyRequest>) -> Json<MyResponse> {
 /// #         todo!()
 /// #     }
 /// # }
@@ -52,16 +85,16 @@ type MethodRegistrationMap =
 /// #
 /// # #[async_trait::async_trait]
 /// # impl Actor for MyActor {
-/// #    async fn on_activate(&self) -> Result<(), ActorError> {
+/// #    async fn on_activate(&mut self) -> Result<(), ActorError> {
 /// #        todo!()
 /// #    }
-/// #    async fn on_deactivate(&self) -> Result<(), ActorError> {
+/// #    async fn on_deactivate(&mut self) -> Result<(), ActorError> {
 /// #         todo!()
 /// #    }
-/// #    async fn on_reminder(&self, reminder_name: &str, data: Vec<u8>) -> Result<(), ActorError> {
+/// #    async fn on_reminder(&mut self, reminder_name: &str, data: Vec<u8>) -> Result<(), ActorError> {
 /// #         todo!()
 /// #    }
-/// #    async fn on_timer(&self, timer_name: &str, data: Vec<u8>) -> Result<(), ActorError> {
+/// #    async fn on_timer(&mut self, timer_name: &str, data: Vec<u8>) -> Result<(), ActorError> {
 /// #         todo!()
 /// #    }
 /// # }
@@ -70,7 +103,7 @@ type MethodRegistrationMap =
 /// let mut dapr_server = dapr::server::DaprHttpServer::new().await;
 ///
 /// dapr_server.register_actor(ActorTypeRegistration::new::<MyActor>("MyActor", Box::new(|_actor_type, actor_id, context| {
-///    Arc::new(MyActor {
+///    Box::new(MyActor {
 ///        id: actor_id.to_string(),
 ///        client: context,
 ///    })}))
@@ -83,6 +116,9 @@ pub struct ActorTypeRegistration {
     name: String,
     factory: ActorFactory,
     method_registrations: MethodRegistrationMap,
+    timer_callbacks: HashMap<String, TimerCallback>,
+    config: Option<ActorTypeConfig>,
+    activation_failed: Option<ActivationFailedHook>,
 }
 
 impl ActorTypeRegistration {
@@ -94,9 +130,48 @@ impl ActorTypeRegistration {
             name: name.to_string(),
             factory,
             method_registrations: HashMap::new(),
+            timer_callbacks: HashMap::new(),
+            config: None,
+            activation_failed: None,
         }
     }
 
+    /// Overrides the runtime-wide [`ActorRuntimeConfig`] for this actor type.
+    pub fn with_config(mut self, config: ActorTypeConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Calls `hook` each time the factory or [`Actor::on_activate`] of an
+    /// actor of this type fails or panics, e.g. to alert on an actor id that
+    /// keeps failing. See [`ActorRuntimeConfig::with_activation_backoff`].
+    pub fn on_activation_failed<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&ActivationFailure) + Send + Sync + 'static,
+    {
+        self.activation_failed = Some(Arc::new(hook));
+        self
+    }
+
+    /// Registers every method of the actor interface `I`, declared with
+    /// `#[dapr_macros::actor_interface]` and implemented by `TActor`.
+    ///
+    /// # Example:
+    /// ```ignore
+    /// dapr_server
+    ///     .register_actor(
+    ///         ActorTypeRegistration::new::<CounterActor>("Counter", factory)
+    ///             .register_interface::<dyn Counter, CounterActor>(),
+    ///     )
+    ///     .await;
+    /// ```
+    pub fn register_interface<I, TActor>(self) -> Self
+    where
+        I: ActorMethods<TActor> + ?Sized,
+    {
+        I::register_methods(self)
+    }
+
     /// Registers a method on the actor type to be exposed to actor clients.
     ///
     /// # Arguments:
@@ -107,8 +182,7 @@ impl ActorTypeRegistration {
     ///   Use the `DaprJson` extractor to deserialize the request from Json coming from a Dapr sidecar.
     /// # Example:
     /// ```ignore
-    /// # use std::sync::Arc;
-    /// # use dapr::server::actor::{context_client::ActorContextClient, Actor, ActorError, ActorFactory, runtime::ActorTypeRegistration};
+    /// # use dapr::server::actor::{context_client::ActorContextClient, Actor, ActorError, ActorFactory, ActorGuard, runtime::ActorTypeRegistration};
     /// # use dapr::server::utils::DaprJson;
     /// # use dapr::actor;
     /// # use axum::{Json, Router};
@@ -121,16 +195,16 @@ impl ActorTypeRegistration {
     /// #
     /// # #[async_trait::async_trait]
     /// # impl Actor for MyActor {
-    /// #    async fn on_activate(&self) -> Result<(), ActorError> {
+    /// #    async fn on_activate(&mut self) -> Result<(), ActorError> {
     /// #        todo!()
     /// #    }
-    /// #    async fn on_deactivate(&self) -> Result<(), ActorError> {
+    /// #    async fn on_deactivate(&mut self) -> Result<(), ActorError> {
     /// #         todo!()
     /// #    }
-    /// #    async fn on_reminder(&self, reminder_name: &str, data: Vec<u8>) -> Result<(), ActorError> {
+    /// #    async fn on_reminder(&mut self, reminder_name: &str, data: Vec<u8>) -> Result<(), ActorError> {
     /// #         todo!()
     /// #    }
-    /// #    async fn on_timer(&self, timer_name: &str, data: Vec<u8>) -> Result<(), ActorError> {
+    /// #    async fn on_timer(&mut self, timer_name: &str, data: Vec<u8>) -> Result<(), ActorError> {
     /// #         todo!()
     /// #    }
     /// # }
@@ -145,7 +219,7 @@ impl ActorTypeRegistration {
     ///}   
     ///
     ///impl MyActor {
-    ///    fn do_stuff(&self, DaprJson(data): DaprJson<MyRequest>) -> Json<MyResponse> {        
+    ///    async fn do_stuff(_actor: ActorGuard<Self>, DaprJson(data): DaprJson<MyRequest>) -> Json<MyResponse> {        
     ///        println!("doing stuff with {}", data.name);        
     ///        Json(MyResponse {
     ///            available: true
@@ -157,7 +231,7 @@ impl ActorTypeRegistration {
     /// let mut dapr_server = dapr::server::DaprHttpServer::new().await;
     ///
     /// dapr_server.register_actor(ActorTypeRegistration::new::<MyActor>("MyActor", Box::new(|_actor_type, actor_id, context| {
-    ///    Arc::new(MyActor {
+    ///    Box::new(MyActor {
     ///        id: actor_id.to_string(),
     ///        client: context,
     ///    })}))
@@ -177,12 +251,15 @@ impl ActorTypeRegistration {
         let method_path = format!("/actors/{actor_type}/:actor_id/method/{method_name}");
 
         let reg_func = move |router: Router, runtime: Arc<ActorRuntime>| {
+            let state = ActorState {
+                actor_type,
+                runtime,
+            };
             router.route(
                 &method_path,
-                put(handler).with_state(ActorState {
-                    actor_type,
-                    runtime,
-                }),
+                put(handler)
+                    .with_state(state.clone())
+                    .route_layer(middleware::from_fn_with_state(state, run_method_turn)),
             )
         };
 
@@ -191,32 +268,172 @@ impl ActorTypeRegistration {
         self
     }
 
-    fn create_actor(&self, actor_id: &str, client: TonicClient) -> Arc<dyn Actor> {
-        let client = ActorContextClient::new(client, &self.name, actor_id);
+    /// Registers a callback for timers registered with
+    /// [`TimerSpec::with_callback`](crate::server::actor::schedule::TimerSpec::with_callback).
+    ///
+    /// The callback runs as a turn of the actor, like
+    /// [`Actor::on_timer`], and receives the timer's data deserialized from
+    /// JSON.
+    ///
+    /// # Example:
+    /// ```ignore
+    /// ActorTypeRegistration::new::<MyActor>("MyActor", factory)
+    ///     .register_timer_callback("tick", |actor: &mut MyActor, by: u32| {
+    ///         Box::pin(async move {
+    ///             actor.count += by;
+    ///             Ok(())
+    ///         })
+    ///     })
+    /// ```
+    pub fn register_timer_callback<TActor, T, F>(mut self, callback_name: &str, callback: F) -> Self
+    where
+        TActor: Actor,
+        T: DeserializeOwned + Send + 'static,
+        F: for<'a> Fn(&'a mut TActor, T) -> BoxFuture<'a, Result<(), ActorError>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let callback: TimerCallback = Arc::new(move |actor: &mut dyn Actor, data: Vec<u8>| {
+            let actor: &mut dyn Any = actor;
+            let Some(actor) = actor.downcast_mut::<TActor>() else {
+                return Box::pin(async { Err(ActorError::NotRegistered) });
+            };
+            match crate::server::actor::schedule::from_data(&data) {
+                Ok(data) => callback(actor, data),
+                Err(_) => Box::pin(async { Err(ActorError::SerializationError()) }),
+            }
+        });
+        self.timer_callbacks
+            .insert(callback_name.to_string(), callback);
+        self
+    }
+
+    /// The name of the actor type.
+    #[cfg(feature = "testing")]
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn create_actor(&self, actor_id: &str, backend: ActorBackend) -> Box<dyn Actor> {
+        let client = ActorContextClient::with_backend(backend, &self.name, actor_id);
 
-        (self.factory)(&self.name, actor_id, client) as _
+        (self.factory)(&self.name, actor_id, client)
     }
 }
 
-type ActiveActorMap = Arc<RwLock<HashMap<(String, String), Arc<dyn Actor>>>>;
+/// Registers the methods of an actor interface for the actor type `A`.
+///
+/// Implemented for `dyn Trait` by `#[dapr_macros::actor_interface]`; see
+/// [`ActorTypeRegistration::register_interface`].
+pub trait ActorMethods<A> {
+    fn register_methods(registration: ActorTypeRegistration) -> ActorTypeRegistration;
+}
+
+type ActiveActorMap = Arc<RwLock<HashMap<(String, String), Arc<ActorCell>>>>;
 type ActorRegistrationMap = Arc<RwLock<HashMap<String, ActorTypeRegistration>>>;
 
+/// The outcome of [ActorRuntime::deactivate_all].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DrainSummary {
+    /// Actors deactivated.
+    pub deactivated: usize,
+    /// Actors whose `on_deactivate` or final state save failed.
+    pub failed: usize,
+    /// Actors still in a turn when the drain timeout expired.
+    pub timed_out: usize,
+    /// How long the drain took.
+    pub elapsed: Duration,
+}
+
+impl fmt::Display for DrainSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} actors deactivated, {} failed, {} timed out in {:?}",
+            self.deactivated, self.failed, self.timed_out, self.elapsed
+        )
+    }
+}
+
 pub struct ActorRuntime {
-    dapr_client: TonicClient,
+    backend: ActorBackend,
 
     registered_actors_types: ActorRegistrationMap,
     active_actors: ActiveActorMap,
+    activation_failures: ActivationFailures,
+    config: SyncRwLock<ActorRuntimeConfig>,
 }
 
 impl ActorRuntime {
-    pub fn new(dapr_client: TonicClient) -> Self {
+    pub fn new(dapr_client: TonicClientWithAuth) -> Self {
+        Self::with_backend(ActorBackend::Sidecar(dapr_client))
+    }
+
+    /// A runtime whose actors' [ActorContextClient]s talk to `backend`.
+    pub(crate) fn with_backend(backend: ActorBackend) -> Self {
         ActorRuntime {
-            dapr_client,
+            backend,
             registered_actors_types: Arc::new(RwLock::new(HashMap::new())),
             active_actors: Arc::new(RwLock::new(HashMap::new())),
+            activation_failures: ActivationFailures::default(),
+            config: SyncRwLock::new(ActorRuntimeConfig::default()),
         }
     }
 
+    /// Sets the configuration applied to all hosted actor types.
+    pub fn set_config(&self, config: ActorRuntimeConfig) {
+        *self.config.write().unwrap_or_else(|e| e.into_inner()) = config;
+    }
+
+    /// Updates the configuration applied to all hosted actor types.
+    pub fn update_config(&self, update: impl FnOnce(ActorRuntimeConfig) -> ActorRuntimeConfig) {
+        let mut config = self.config.write().unwrap_or_else(|e| e.into_inner());
+        *config = update(std::mem::take(&mut *config));
+    }
+
+    pub fn config(&self) -> ActorRuntimeConfig {
+        self.config
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// The per-type overrides of the registered actor types that have any.
+    pub async fn list_actor_type_configs(&self) -> Vec<(String, ActorTypeConfig)> {
+        let types = self.registered_actors_types.read().await;
+
+        types
+            .values()
+            .filter_map(|r| Some((r.name.clone(), r.config.clone()?)))
+            .collect()
+    }
+
+    /// The reentrancy settings in effect for `actor_type`.
+    pub async fn reentrancy(&self, actor_type: &str) -> ReentrancyConfig {
+        let types = self.registered_actors_types.read().await;
+        let type_config = types
+            .get(actor_type)
+            .and_then(|r| r.config.as_ref())
+            .and_then(|c| c.reentrancy());
+        match type_config {
+            Some(reentrancy) => reentrancy.clone(),
+            None => self.config().reentrancy().cloned().unwrap_or_default(),
+        }
+    }
+
+    /// How long actors of `actor_type` may stay idle before they are
+    /// deactivated.
+    pub async fn idle_timeout(&self, actor_type: &str) -> Duration {
+        let types = self.registered_actors_types.read().await;
+        types
+            .get(actor_type)
+            .and_then(|r| r.config.as_ref())
+            .and_then(|c| c.idle_timeout())
+            .or(self.config().idle_timeout())
+            .unwrap_or(DEFAULT_IDLE_TIMEOUT)
+    }
+
     /// Registers an actor type to be exposed to actor clients.
     /// # Arguments:
     /// * `registration` - The [ActorTypeRegistration] that describes the actor implementation.
@@ -243,26 +460,173 @@ impl ActorRuntime {
         router
     }
 
+    /// Deactivates an actor, waiting for its current turn (if any) to
+    /// finish before calling [`Actor::on_deactivate`].
     pub async fn deactivate_actor(&self, name: &str, id: &str) -> Result<(), ActorError> {
-        let mut actors = self.active_actors.write().await;
-
-        let actor = match actors.remove(&(name.to_string(), id.to_string())) {
-            Some(actor_ref) => actor_ref,
+        let key = (name.to_string(), id.to_string());
+        let cell = match self.active_actors.read().await.get(&key) {
+            Some(cell) => cell.clone(),
             None => return Err(ActorError::ActorNotFound),
         };
-        drop(actors);
-        actor.on_deactivate().await?;
-        drop(actor);
-        Ok(())
+        cell.start_deactivating();
+        deactivate_cell(&self.active_actors, &key, cell).await
     }
 
-    pub async fn deactivate_all(&self) {
-        let mut actors = self.active_actors.write().await;
+    /// Deactivates all active actors, e.g. when the app shuts down.
+    ///
+    /// Each actor is deactivated once its in-flight method, timer or
+    /// reminder turn has finished, up to the
+    /// [drain timeout](ActorRuntimeConfig::with_drain_ongoing_call_timeout)
+    /// of its type (see [ActorTypeConfig::with_drain_ongoing_call_timeout],
+    /// default [DEFAULT_DRAIN_ONGOING_CALL_TIMEOUT]) from the start of the
+    /// drain. At most [ActorRuntimeConfig::with_drain_concurrency] actors are
+    /// deactivated at once. Actors still busy at the deadline are left
+    /// without `on_deactivate`; failures are logged and counted in the
+    /// returned summary.
+    pub async fn deactivate_all(&self) -> DrainSummary {
+        let started = Instant::now();
+        let drain_started = tokio::time::Instant::now();
+        let config = self.config();
+        let default_timeout = config
+            .drain_ongoing_call_timeout()
+            .unwrap_or(DEFAULT_DRAIN_ONGOING_CALL_TIMEOUT);
+        let concurrency = config
+            .drain_concurrency()
+            .unwrap_or(DEFAULT_DRAIN_CONCURRENCY);
+        let type_timeouts: HashMap<String, Duration> = self
+            .list_actor_type_configs()
+            .await
+            .into_iter()
+            .filter_map(|(name, c)| Some((name, c.drain_ongoing_call_timeout()?)))
+            .collect();
+
+        let cells: Vec<((String, String), Arc<ActorCell>)> = {
+            let actors = self.active_actors.read().await;
+            actors
+                .iter()
+                .filter(|(_, cell)| cell.start_deactivating())
+                .map(|(key, cell)| (key.clone(), cell.clone()))
+                .collect()
+        };
 
-        for actor in actors.values() {
-            _ = actor.on_deactivate().await;
+        let mut summary = DrainSummary::default();
+        let mut outcomes = futures::stream::iter(cells)
+            .map(|((actor_type, id), cell)| {
+                let timeout = type_timeouts
+                    .get(&actor_type)
+                    .copied()
+                    .unwrap_or(default_timeout);
+                let actors = &self.active_actors;
+                async move {
+                    let key = (actor_type, id);
+                    let deadline = drain_started + timeout;
+                    let deactivation = deactivate_cell(actors, &key, cell.clone());
+                    let outcome = tokio::time::timeout_at(deadline, deactivation).await;
+                    if outcome.is_err() {
+                        remove_cell(actors, &key, &cell).await;
+                    }
+                    let (actor_type, id) = key;
+                    (actor_type, id, timeout, outcome)
+                }
+            })
+            .buffer_unordered(concurrency);
+        while let Some((actor_type, id, timeout, outcome)) = outcomes.next().await {
+            match outcome {
+                Ok(Ok(())) => summary.deactivated += 1,
+                Ok(Err(e)) => {
+                    log::error!("failed to deactivate actor {actor_type}/{id}: {e}");
+                    summary.failed += 1;
+                }
+                Err(_) => {
+                    log::warn!(
+                        "actor {actor_type}/{id} still busy after the {timeout:?} drain timeout"
+                    );
+                    summary.timed_out += 1;
+                }
+            }
         }
-        actors.clear();
+        summary.elapsed = started.elapsed();
+
+        log::info!("actor runtime drained: {summary}");
+        summary
+    }
+
+    /// Deactivates every actor that has gone without a turn for longer than
+    /// its [idle timeout](ActorRuntime::idle_timeout), returning how many
+    /// were deactivated.
+    pub async fn deactivate_idle_actors(&self) -> usize {
+        let default_timeout = self.config().idle_timeout().unwrap_or(DEFAULT_IDLE_TIMEOUT);
+        let type_timeouts: HashMap<String, Duration> = self
+            .list_actor_type_configs()
+            .await
+            .into_iter()
+            .filter_map(|(name, c)| Some((name, c.idle_timeout()?)))
+            .collect();
+
+        let idle: Vec<((String, String), Arc<ActorCell>)> = {
+            let actors = self.active_actors.read().await;
+            actors
+                .iter()
+                .filter(|((actor_type, _), cell)| {
+                    let timeout = type_timeouts
+                        .get(actor_type)
+                        .copied()
+                        .unwrap_or(default_timeout);
+                    cell.idle_for().is_some_and(|idle| idle >= timeout) && cell.start_deactivating()
+                })
+                .map(|(key, cell)| (key.clone(), cell.clone()))
+                .collect()
+        };
+
+        let count = idle.len();
+        for (key, cell) in idle {
+            let (actor_type, id) = &key;
+            log::info!("deactivating idle actor {actor_type}/{id}");
+            if let Err(e) = deactivate_cell(&self.active_actors, &key, cell).await {
+                log::error!("Error deactivating idle actor {actor_type}/{id}: {e}");
+            }
+        }
+        count
+    }
+
+    /// Spawns a task that calls [`ActorRuntime::deactivate_idle_actors`]
+    /// every [scan interval](ActorRuntimeConfig::with_scan_interval).
+    ///
+    /// The task ends once the runtime is dropped, or when the returned
+    /// handle is aborted.
+    pub fn spawn_idle_scanner(self: &Arc<Self>) -> JoinHandle<()> {
+        let runtime = Arc::downgrade(self);
+        tokio::spawn(async move {
+            loop {
+                let interval = match runtime.upgrade() {
+                    Some(rt) => rt.config().scan_interval(),
+                    None => return,
+                };
+                tokio::time::sleep(interval.unwrap_or(DEFAULT_SCAN_INTERVAL)).await;
+                match runtime.upgrade() {
+                    Some(rt) => _ = rt.deactivate_idle_actors().await,
+                    None => return,
+                }
+            }
+        })
+    }
+
+    /// The ids of the active actors, by actor type.
+    pub async fn list_active_actors(&self) -> HashMap<String, Vec<String>> {
+        let actors = self.active_actors.read().await;
+        let mut by_type: HashMap<String, Vec<String>> = HashMap::new();
+        for (actor_type, id) in actors.keys() {
+            by_type
+                .entry(actor_type.clone())
+                .or_default()
+                .push(id.clone());
+        }
+        by_type
+    }
+
+    /// The number of active actors across all types.
+    pub async fn active_actor_count(&self) -> usize {
+        self.active_actors.read().await.len()
     }
 
     pub async fn invoke_reminder(
@@ -272,9 +636,11 @@ impl ActorRuntime {
         reminder_name: &str,
         data: Vec<u8>,
     ) -> Result<(), ActorError> {
-        let actor = self.get_or_create_actor(name, id).await?;
-        actor.on_reminder(reminder_name, data).await?;
-        Ok(())
+        let mut actor = self.get_or_create_actor(name, id).await?;
+        let outcome = AssertUnwindSafe(actor.on_reminder(reminder_name, data))
+            .catch_unwind()
+            .await;
+        self.end_turn(name, id, actor, outcome).await
     }
 
     pub async fn invoke_timer(
@@ -284,9 +650,134 @@ impl ActorRuntime {
         timer_name: &str,
         data: Vec<u8>,
     ) -> Result<(), ActorError> {
-        let actor = self.get_or_create_actor(name, id).await?;
-        actor.on_timer(timer_name, data).await?;
-        Ok(())
+        let mut actor = self.get_or_create_actor(name, id).await?;
+        let outcome = AssertUnwindSafe(actor.on_timer(timer_name, data))
+            .catch_unwind()
+            .await;
+        self.end_turn(name, id, actor, outcome).await
+    }
+
+    /// Delivers a timer registered with a callback to the callback
+    /// registered under that name with
+    /// [`ActorTypeRegistration::register_timer_callback`].
+    ///
+    /// Fails with [`ActorError::MethodNotFound`] if there is no such
+    /// callback.
+    pub async fn invoke_timer_callback(
+        &self,
+        name: &str,
+        id: &str,
+        callback_name: &str,
+        data: Vec<u8>,
+    ) -> Result<(), ActorError> {
+        let callback = {
+            let types = self.registered_actors_types.read().await;
+            let registration = types.get(name).ok_or(ActorError::NotRegistered)?;
+            registration
+                .timer_callbacks
+                .get(callback_name)
+                .cloned()
+                .ok_or(ActorError::MethodNotFound)?
+        };
+        let mut actor = self.get_or_create_actor(name, id).await?;
+        let outcome = AssertUnwindSafe(callback(&mut *actor, data))
+            .catch_unwind()
+            .await;
+        self.end_turn(name, id, actor, outcome).await
+    }
+
+    /// Ends a timer or reminder turn, saving the actor's state changes if
+    /// it succeeded.
+    async fn end_turn(
+        &self,
+        name: &str,
+        id: &str,
+        mut actor: ActorTurn,
+        outcome: std::thread::Result<Result<(), ActorError>>,
+    ) -> Result<(), ActorError> {
+        match outcome {
+            Ok(Ok(())) => save_state(&mut actor).await,
+            Ok(Err(e)) => {
+                discard_state(&mut actor).await;
+                Err(e)
+            }
+            Err(panic) => Err(self.discard_panicked(name, id, &mut actor, panic).await),
+        }
+    }
+
+    /// Discards an actor instance that panicked during `turn`, without
+    /// calling [`Actor::on_deactivate`] or saving its state. The next call
+    /// to the actor activates a fresh instance.
+    pub(crate) async fn discard_panicked(
+        &self,
+        actor_type: &str,
+        id: &str,
+        turn: &mut ActorTurn,
+        panic: Box<dyn Any + Send>,
+    ) -> ActorError {
+        let message = panic_message(panic);
+        log::error!("actor {actor_type}/{id} panicked, discarding it: {message}");
+        self.discard_instance(&(actor_type.to_string(), id.to_string()), turn)
+            .await;
+        ActorError::Panicked(message)
+    }
+
+    /// Marks the instance `turn` is on as discarded and removes it from the
+    /// active actors, unless it was already replaced.
+    async fn discard_instance(&self, key: &(String, String), turn: &mut ActorTurn) {
+        turn.discard().await;
+        let mut actors = self.active_actors.write().await;
+        if actors.get(key).is_some_and(|cell| turn.is_on(cell)) {
+            actors.remove(key);
+        }
+    }
+
+    /// Records a failed activation, reports it to the actor type's
+    /// [`ActorTypeRegistration::on_activation_failed`] hook and returns the
+    /// error for the call that triggered it.
+    async fn activation_failed(&self, key: &(String, String), error: ActorError) -> ActorError {
+        let config = self.config();
+        let (initial, max) = config
+            .activation_backoff()
+            .unwrap_or((DEFAULT_ACTIVATION_BACKOFF, DEFAULT_MAX_ACTIVATION_BACKOFF));
+        let backoff = Backoff {
+            initial,
+            max,
+            max_failures: config.max_activation_failures(),
+        };
+        let failure = self.activation_failures.record(key, &error, backoff);
+        match failure.retry_after {
+            Some(after) => log::warn!(
+                "activation of actor {}/{} failed ({} in a row), retrying in {after:?}: {error}",
+                key.0,
+                key.1,
+                failure.attempts
+            ),
+            None => log::error!(
+                "activation of actor {}/{} failed {} times, giving up: {error}",
+                key.0,
+                key.1,
+                failure.attempts
+            ),
+        }
+
+        let hook = {
+            let types = self.registered_actors_types.read().await;
+            types.get(&key.0).and_then(|r| r.activation_failed.clone())
+        };
+        if let Some(hook) = hook {
+            hook(&failure);
+        }
+        error
+    }
+
+    /// Lets calls activate the actor again right away after failed
+    /// activations, including once it is poisoned, see
+    /// [`ActorRuntimeConfig::with_max_activation_failures`]. Returns whether
+    /// the actor had failed activations.
+    pub fn clear_activation_failures(&self, actor_type: &str, id: &str) -> bool {
+        self.activation_failures
+            .clear(&(actor_type.to_string(), id.to_string()))
     }
 
     pub async fn list_registered_actors(&self) -> Vec<String> {
@@ -295,38 +786,230 @@ impl ActorRuntime {
         types.keys().map(|k| k.to_string()).collect()
     }
 
+    /// Starts a turn on the actor, activating it first if needed.
+    ///
+    /// Turns on the same actor are serialized: this waits until any other
+    /// turn (method call, timer, reminder or deactivation) on the actor has
+    /// finished, and the returned [`ActorTurn`] keeps other turns waiting
+    /// until it is dropped.
+    ///
+    /// If the actor's factory or [`Actor::on_activate`] fails or panics,
+    /// calls to the actor fail fast with [`ActorError::ActivationFailed`]
+    /// for a backoff period before activation is retried, see
+    /// [`ActorRuntimeConfig::with_activation_backoff`].
     pub async fn get_or_create_actor(
         &self,
         actor_type: &str,
         id: &str,
-    ) -> Result<Arc<dyn Actor>, ActorError> {
-        let actors = self.active_actors.read().await;
-        match actors.get(&(actor_type.to_string(), id.to_string())) {
-            Some(actor_ref) => Ok(actor_ref.clone()),
-            None => {
-                drop(actors);
-                self.activate_actor(actor_type, id).await
+    ) -> Result<ActorTurn, ActorError> {
+        self.get_or_create_actor_reentrant(actor_type, id, None)
+            .await
+    }
+
+    /// Like [`ActorRuntime::get_or_create_actor`], but when reentrancy is
+    /// enabled a call carrying the reentrancy id of the call chain holding
+    /// the actor's turn joins that turn instead of waiting for it.
+    ///
+    /// Fails with [`ActorError::MaxStackDepthExceeded`] when the chain is
+    /// already nested [`ReentrancyConfig::max_stack_depth`] deep.
+    pub async fn get_or_create_actor_reentrant(
+        &self,
+        actor_type: &str,
+        id: &str,
+        reentrancy_id: Option<&str>,
+    ) -> Result<ActorTurn, ActorError> {
+        let config = self.reentrancy(actor_type).await;
+        let reentrancy = reentrancy_id
+            .filter(|_| config.enabled)
+            .map(|id| Reentrancy {
+                id,
+                max_stack_depth: config.max_stack_depth(),
+            });
+
+        let actor_key = (actor_type.to_string(), id.to_string());
+        loop {
+            self.activation_failures.check(&actor_key)?;
+            let cell = self.get_or_insert_cell(actor_type, id).await?;
+            let mut turn = cell.begin_turn(reentrancy).await?;
+            let slot = turn.slot();
+            if slot.deactivated {
+                // Deactivated while we were waiting for the turn; the next
+                // lookup creates a fresh instance.
+                continue;
+            }
+            if !slot.activated {
+                let error = match AssertUnwindSafe(slot.actor.on_activate())
+                    .catch_unwind()
+                    .await
+                {
+                    Ok(Ok(())) => None,
+                    Ok(Err(e)) => Some(e),
+                    Err(panic) => Some(ActorError::Panicked(panic_message(panic))),
+                };
+                if let Some(e) = error {
+                    // Calls waiting for the turn look the actor up again,
+                    // and then back off.
+                    self.discard_instance(&actor_key, &mut turn).await;
+                    drop(turn);
+                    return Err(self.activation_failed(&actor_key, e).await);
+                }
+                turn.slot().activated = true;
+                self.activation_failures.clear(&actor_key);
             }
+            return Ok(turn);
         }
     }
 
-    async fn activate_actor(
+    async fn get_or_insert_cell(
         &self,
         actor_type: &str,
         id: &str,
-    ) -> Result<Arc<dyn Actor>, ActorError> {
+    ) -> Result<Arc<ActorCell>, ActorError> {
+        let actor_key = (actor_type.to_string(), id.to_string());
+        if let Some(cell) = self.active_actors.read().await.get(&actor_key) {
+            return Ok(cell.clone());
+        }
+
         let types = self.registered_actors_types.read().await;
         let actor = match types.get(actor_type) {
-            Some(f) => f.create_actor(id, self.dapr_client.clone()),
+            Some(f) => std::panic::catch_unwind(AssertUnwindSafe(|| {
+                f.create_actor(id, self.backend.clone())
+            })),
             None => Err(ActorError::NotRegistered)?,
         };
+        drop(types);
+        let actor = match actor {
+            Ok(actor) => actor,
+            Err(panic) => {
+                let error = ActorError::Panicked(panic_message(panic));
+                return Err(self.activation_failed(&actor_key, error).await);
+            }
+        };
 
-        actor.on_activate().await?;
-
-        let actor_key = (actor_type.to_string(), id.to_string());
+        // Another request may have created the actor in the meantime; the
+        // instance built above is then dropped without being activated.
         let mut actors = self.active_actors.write().await;
-        actors.insert(actor_key, actor.clone());
+        let cell = actors
+            .entry(actor_key.clone())
+            .or_insert_with(|| ActorCell::new(actor))
+            .clone();
+
+        let evicted = match self.config().max_active_actors() {
+            Some(max) if actors.values().filter(|c| !c.is_deactivating()).count() > max => {
+                evict_least_recently_used(&actors, &actor_key)
+            }
+            _ => None,
+        };
+        drop(actors);
+
+        // Deactivating runs the evicted actor's `on_deactivate`, which this
+        // call should not wait for.
+        if let Some((key, evicted)) = evicted {
+            let actors = self.active_actors.clone();
+            tokio::spawn(async move {
+                let (actor_type, id) = &key;
+                log::info!("deactivating actor {actor_type}/{id} to stay within max active actors");
+                if let Err(e) = deactivate_cell(&actors, &key, evicted).await {
+                    log::error!("Error deactivating actor {actor_type}/{id}: {e}");
+                }
+            });
+        }
+        Ok(cell)
+    }
+}
+
+/// Picks the actor that has been idle the longest, other than `keep`, for
+/// deactivation.
+///
+/// Actors in the middle of a turn are never evicted, so the cap may be
+/// exceeded while every active actor is busy.
+fn evict_least_recently_used(
+    actors: &HashMap<(String, String), Arc<ActorCell>>,
+    keep: &(String, String),
+) -> Option<((String, String), Arc<ActorCell>)> {
+    let evicted = actors
+        .iter()
+        .filter(|(key, cell)| *key != keep && !cell.is_deactivating())
+        .filter_map(|(key, cell)| Some((key, cell, cell.idle_for()?)))
+        .max_by_key(|(_, _, idle)| *idle);
+    match evicted {
+        Some((key, cell, _)) => {
+            cell.start_deactivating();
+            Some((key.clone(), cell.clone()))
+        }
+        None => {
+            log::warn!("all active actors are busy, exceeding max active actors");
+            None
+        }
+    }
+}
+
+/// Deactivates the actor in `cell` once its current turn has finished, then
+/// removes it from `actors`.
+///
+/// The actor stays in `actors` until then, so calls to it wait for the
+/// deactivation and activate a new instance afterwards. A panicking
+/// `on_deactivate` is reported as [`ActorError::Panicked`], and the actor's
+/// pending state changes are dropped.
+async fn deactivate_cell(
+    actors: &ActiveActorMap,
+    key: &(String, String),
+    cell: Arc<ActorCell>,
+) -> Result<(), ActorError> {
+    let mut turn = cell.begin_turn(None).await?;
+    let result = deactivate_turn(&mut turn).await;
+    // Removed before the turn ends, so waiting calls find it gone.
+    remove_cell(actors, key, &cell).await;
+    result
+}
+
+/// Removes `cell` from `actors`, unless it was already replaced.
+async fn remove_cell(actors: &ActiveActorMap, key: &(String, String), cell: &Arc<ActorCell>) {
+    let mut actors = actors.write().await;
+    if actors.get(key).is_some_and(|c| Arc::ptr_eq(c, cell)) {
+        actors.remove(key);
+    }
+}
+
+/// Runs [`Actor::on_deactivate`] in `turn` and saves the actor's state.
+async fn deactivate_turn(turn: &mut ActorTurn) -> Result<(), ActorError> {
+    let slot = turn.slot();
+    if slot.deactivated {
+        // Another call deactivated the actor first.
+        return Err(ActorError::ActorNotFound);
+    }
+    slot.deactivated = true;
+    if slot.activated {
+        let outcome = AssertUnwindSafe(slot.actor.on_deactivate())
+            .catch_unwind()
+            .await;
+        let error = match outcome {
+            Ok(Ok(())) => None,
+            Ok(Err(e)) => Some(e),
+            Err(panic) => Some(ActorError::Panicked(panic_message(panic))),
+        };
+        if let Some(e) = error {
+            discard_state(turn).await;
+            return Err(e);
+        }
+        save_state(turn).await?;
+    }
+    Ok(())
+}
+
+/// Saves the pending state changes of a successful turn on `actor`.
+pub(crate) async fn save_state(actor: &mut ActorTurn) -> Result<(), ActorError> {
+    actor.reacquire().await;
+    if let Some(state) = actor.state_manager() {
+        state.save().await?;
+    }
+    Ok(())
+}
 
-        Ok(actor)
+/// Drops the pending state changes of a failed turn on `actor`.
+pub(crate) async fn discard_state(actor: &mut ActorTurn) {
+    actor.reacquire().await;
+    if let Some(state) = actor.state_manager() {
+        state.clear_cache();
     }
 }
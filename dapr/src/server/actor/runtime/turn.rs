@@ -0,0 +1,254 @@
+use std::{
+    any::Any,
+    future::Future,
+    ops::{Deref, DerefMut},
+    sync::{
+        Arc, Mutex as SyncMutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use tokio::sync::{Mutex, Notify, OwnedMutexGuard};
+
+use crate::server::actor::{Actor, ActorError};
+
+/// An active actor instance and the turn lock guarding it.
+///
+/// A turn is held for the whole duration of a method call, timer, reminder
+/// or deactivation. Calls belonging to the reentrant call chain that holds
+/// the turn may take nested turns, up to the configured stack depth.
+///
+/// The instance itself sits behind a separate lock so a turn can lend it to
+/// nested turns while it waits on an outbound call, see
+/// [`ActorTurn::allow_reentry`].
+pub(super) struct ActorCell {
+    owner: SyncMutex<TurnOwner>,
+    turn_released: Notify,
+    slot: Arc<Mutex<ActorSlot>>,
+    /// Set once the actor is picked for deactivation. It stays active until
+    /// the deactivation is done, so calls to it wait for that.
+    deactivating: AtomicBool,
+}
+
+struct TurnOwner {
+    depth: u32,
+    reentrancy_id: Option<String>,
+    /// When the last turn ended, or the cell was created.
+    last_used: Instant,
+}
+
+pub(super) struct ActorSlot {
+    pub(super) actor: Box<dyn Actor>,
+    pub(super) activated: bool,
+    pub(super) deactivated: bool,
+}
+
+/// The reentrancy id of a call and the maximum depth it may re-enter to.
+#[derive(Clone, Copy)]
+pub(super) struct Reentrancy<'a> {
+    pub(super) id: &'a str,
+    pub(super) max_stack_depth: u32,
+}
+
+impl ActorCell {
+    pub(super) fn new(actor: Box<dyn Actor>) -> Arc<Self> {
+        Arc::new(ActorCell {
+            owner: SyncMutex::new(TurnOwner {
+                depth: 0,
+                reentrancy_id: None,
+                last_used: Instant::now(),
+            }),
+            turn_released: Notify::new(),
+            slot: Arc::new(Mutex::new(ActorSlot {
+                actor,
+                activated: false,
+                deactivated: false,
+            })),
+            deactivating: AtomicBool::new(false),
+        })
+    }
+
+    /// Waits for the turn, or joins the current one when `reentrancy`
+    /// carries the id of the call chain holding it.
+    pub(super) async fn begin_turn(
+        self: &Arc<Self>,
+        reentrancy: Option<Reentrancy<'_>>,
+    ) -> Result<ActorTurn, ActorError> {
+        loop {
+            let released = self.turn_released.notified();
+            tokio::pin!(released);
+            // Register for the wake-up before looking at the owner so a
+            // release in between is not missed.
+            released.as_mut().enable();
+
+            if self.try_take_turn(reentrancy)? {
+                // Ends the turn again if we are cancelled while waiting for
+                // the instance.
+                let mut turn = ActorTurn {
+                    slot: None,
+                    cell: self.clone(),
+                };
+                turn.slot = Some(self.slot.clone().lock_owned().await);
+                return Ok(turn);
+            }
+            released.await;
+        }
+    }
+
+    fn try_take_turn(&self, reentrancy: Option<Reentrancy<'_>>) -> Result<bool, ActorError> {
+        let mut owner = self.owner.lock().unwrap_or_else(|e| e.into_inner());
+        if owner.depth == 0 {
+            owner.depth = 1;
+            owner.reentrancy_id = reentrancy.map(|r| r.id.to_string());
+            return Ok(true);
+        }
+        match reentrancy {
+            Some(r) if owner.reentrancy_id.as_deref() == Some(r.id) => {
+                if owner.depth >= r.max_stack_depth {
+                    return Err(ActorError::MaxStackDepthExceeded);
+                }
+                owner.depth += 1;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// How long the actor has gone without a turn, or `None` while a turn
+    /// is in progress.
+    pub(super) fn idle_for(&self) -> Option<Duration> {
+        let owner = self.owner.lock().unwrap_or_else(|e| e.into_inner());
+        (owner.depth == 0).then(|| owner.last_used.elapsed())
+    }
+
+    /// Picks the actor for deactivation, returning `false` if it already was.
+    pub(super) fn start_deactivating(&self) -> bool {
+        !self.deactivating.swap(true, Ordering::SeqCst)
+    }
+
+    pub(super) fn is_deactivating(&self) -> bool {
+        self.deactivating.load(Ordering::SeqCst)
+    }
+
+    fn end_turn(&self) {
+        let mut owner = self.owner.lock().unwrap_or_else(|e| e.into_inner());
+        owner.depth -= 1;
+        if owner.depth == 0 {
+            owner.reentrancy_id = None;
+            owner.last_used = Instant::now();
+            drop(owner);
+            self.turn_released.notify_waiters();
+        }
+    }
+}
+
+/// Exclusive access to an active actor for the duration of one turn.
+///
+/// Obtained from [`super::ActorRuntime::get_or_create_actor`]. Other turns
+/// on the same actor wait until this value is dropped.
+pub struct ActorTurn {
+    slot: Option<OwnedMutexGuard<ActorSlot>>,
+    cell: Arc<ActorCell>,
+}
+
+impl ActorTurn {
+    pub(super) fn slot(&mut self) -> &mut ActorSlot {
+        self.slot
+            .as_mut()
+            .expect("actor used after an interrupted `allow_reentry`; `reacquire` it first")
+    }
+
+    /// The actor instance as its concrete type, or `None` if it is not an `A`
+    /// or is still lent out by an interrupted [`ActorTurn::allow_reentry`].
+    pub fn downcast_ref<A: Actor>(&self) -> Option<&A> {
+        let actor: &dyn Any = self.slot.as_ref()?.actor.as_ref();
+        actor.downcast_ref()
+    }
+
+    /// The actor instance as its concrete type, or `None` if it is not an `A`
+    /// or is still lent out by an interrupted [`ActorTurn::allow_reentry`].
+    pub fn downcast_mut<A: Actor>(&mut self) -> Option<&mut A> {
+        let actor: &mut dyn Any = self.slot.as_mut()?.actor.as_mut();
+        actor.downcast_mut()
+    }
+
+    /// Takes the instance back if an interrupted
+    /// [`ActorTurn::allow_reentry`] could not, once the nested calls holding
+    /// it have finished. Does nothing if the turn already has it.
+    pub async fn reacquire(&mut self) {
+        if self.slot.is_none() {
+            self.slot = Some(self.cell.slot.clone().lock_owned().await);
+        }
+    }
+
+    /// Marks the instance as discarded and drops its pending state changes,
+    /// e.g. after it panicked; the next turn creates a fresh instance.
+    pub(super) async fn discard(&mut self) {
+        self.reacquire().await;
+        let slot = self.slot();
+        slot.deactivated = true;
+        if let Some(state) = slot.actor.state_manager() {
+            state.clear_cache();
+        }
+    }
+
+    /// Whether this is a turn on `cell`.
+    pub(super) fn is_on(&self, cell: &Arc<ActorCell>) -> bool {
+        Arc::ptr_eq(&self.cell, cell)
+    }
+
+    /// Awaits `fut` while letting calls from the same reentrant call chain
+    /// run on this actor, e.g. when calling another actor that calls back.
+    ///
+    /// The actor instance is handed to such nested calls until `fut`
+    /// completes; calls from other chains keep waiting for the turn. If the
+    /// returned future is dropped before completing, e.g. on a timeout, the
+    /// instance is taken back right away unless a nested call still holds
+    /// it. Call [`ActorTurn::reacquire`] to wait for it before using the
+    /// actor again; until then dereferencing the turn panics.
+    pub async fn allow_reentry<F: Future>(&mut self, fut: F) -> F::Output {
+        /// Takes the instance back without waiting if the turn is cancelled.
+        struct Restore<'a>(&'a mut ActorTurn);
+
+        impl Drop for Restore<'_> {
+            fn drop(&mut self) {
+                if self.0.slot.is_none() {
+                    self.0.slot = self.0.cell.slot.clone().try_lock_owned().ok();
+                }
+            }
+        }
+
+        self.slot = None;
+        let restore = Restore(self);
+        let output = fut.await;
+        restore.0.reacquire().await;
+        output
+    }
+}
+
+impl Deref for ActorTurn {
+    type Target = dyn Actor;
+
+    fn deref(&self) -> &Self::Target {
+        self.slot
+            .as_ref()
+            .expect("actor used after an interrupted `allow_reentry`; `reacquire` it first")
+            .actor
+            .as_ref()
+    }
+}
+
+impl DerefMut for ActorTurn {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.slot().actor.as_mut()
+    }
+}
+
+impl Drop for ActorTurn {
+    fn drop(&mut self) {
+        // Hand the instance back before letting the next turn in.
+        self.slot = None;
+        self.cell.end_turn();
+    }
+}
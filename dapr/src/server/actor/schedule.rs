@@ -0,0 +1,434 @@
+//! Timers and reminders registered by an actor on itself.
+//!
+//! [`ReminderSpec`] and [`TimerSpec`] describe when a reminder or timer
+//! fires and what data it carries; register them with
+//! [`ActorContextClient::register_reminder`] and
+//! [`ActorContextClient::register_timer`].
+//!
+//! Data attached with `with_data` is serialized as JSON. The actor receives
+//! the same bytes in [`Actor::on_reminder`] / [`Actor::on_timer`] and can
+//! turn them back into the original value with [`from_data`].
+//!
+//! # Example:
+//! ```no_run
+//! # use std::time::Duration;
+//! # use dapr::server::actor::{context_client::ActorContextClient, schedule::{ReminderSpec, TimerSpec}};
+//! # async fn run(mut client: ActorContextClient) -> Result<(), dapr::error::Error> {
+//! // Fires after 5 seconds, then 3 more times every 10 seconds.
+//! let reminder = ReminderSpec::new("wake-up")
+//!     .with_due_time(Duration::from_secs(5))
+//!     .with_period(Duration::from_secs(10))
+//!     .with_repetitions(3)
+//!     .with_data(&"hello")?;
+//! client.register_reminder(reminder).await?;
+//!
+//! // Calls the callback registered as "tick" every second.
+//! let timer = TimerSpec::new("ticker")
+//!     .with_period(Duration::from_secs(1))
+//!     .with_callback("tick");
+//! client.register_timer(timer).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`ActorContextClient::register_reminder`]: super::context_client::ActorContextClient::register_reminder
+//! [`ActorContextClient::register_timer`]: super::context_client::ActorContextClient::register_timer
+//! [`Actor::on_reminder`]: super::Actor::on_reminder
+//! [`Actor::on_timer`]: super::Actor::on_timer
+
+use std::time::Duration;
+
+use base64::Engine;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::dapr::proto::common::v1::JobFailurePolicy;
+use crate::error::Error as DaprError;
+
+use super::ActorError;
+
+/// A reminder to register on the current actor.
+///
+/// Reminders are persisted by the sidecar and fire even if the actor is
+/// deactivated in the meantime; they are delivered to
+/// [`super::Actor::on_reminder`].
+#[derive(Clone, Debug)]
+pub struct ReminderSpec {
+    pub(crate) name: String,
+    schedule: Schedule,
+    pub(crate) data: Vec<u8>,
+    pub(crate) overwrite: Option<bool>,
+    pub(crate) failure_policy: Option<JobFailurePolicy>,
+}
+
+impl ReminderSpec {
+    pub fn new(name: impl Into<String>) -> Self {
+        ReminderSpec {
+            name: name.into(),
+            schedule: Schedule::default(),
+            data: Vec::new(),
+            overwrite: None,
+            failure_policy: None,
+        }
+    }
+
+    /// Fires first after `due_time`; immediately if unset.
+    pub fn with_due_time(mut self, due_time: Duration) -> Self {
+        self.schedule.due_time = Some(TimeValue::After(due_time));
+        self
+    }
+
+    /// Fires first at `due_at`.
+    pub fn with_due_at(mut self, due_at: DateTime<Utc>) -> Self {
+        self.schedule.due_time = Some(TimeValue::At(due_at));
+        self
+    }
+
+    /// Fires again every `period` after the first time; only once if unset.
+    pub fn with_period(mut self, period: Duration) -> Self {
+        self.schedule.period = Some(Period::Every(period));
+        self
+    }
+
+    /// Limits how often the reminder fires again after the first time.
+    /// Requires a [period](ReminderSpec::with_period) in whole seconds; sent
+    /// as an ISO-8601 repetition such as `R5/PT10S`.
+    pub fn with_repetitions(mut self, repetitions: u32) -> Self {
+        self.schedule.repetitions = Some(repetitions);
+        self
+    }
+
+    /// Sets the period as a schedule expression passed to the sidecar
+    /// verbatim, e.g. `R5/PT10S`, `PT1H30M` or `90s`.
+    pub fn with_period_expr(mut self, period: impl Into<String>) -> Self {
+        self.schedule.period = Some(Period::Expr(period.into()));
+        self
+    }
+
+    /// Deletes the reminder `ttl` after it is registered.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.schedule.ttl = Some(TimeValue::After(ttl));
+        self
+    }
+
+    /// Deletes the reminder at `expires_at`.
+    pub fn with_expires_at(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.schedule.ttl = Some(TimeValue::At(expires_at));
+        self
+    }
+
+    /// Attaches `data`, serialized as JSON. See [`from_data`].
+    pub fn with_data<T: Serialize>(mut self, data: &T) -> Result<Self, DaprError> {
+        self.data = serde_json::to_vec(data).map_err(|_| DaprError::SerializationError)?;
+        Ok(self)
+    }
+
+    /// Attaches `data` as is.
+    pub fn with_raw_data(mut self, data: Vec<u8>) -> Self {
+        self.data = data;
+        self
+    }
+
+    /// Whether to replace an existing reminder with the same name. Defaults
+    /// to `true`.
+    pub fn with_overwrite(mut self, overwrite: bool) -> Self {
+        self.overwrite = Some(overwrite);
+        self
+    }
+
+    /// The job failure policy applied to this reminder, see
+    /// [`crate::client::JobFailurePolicyBuilder`].
+    pub fn with_failure_policy(mut self, failure_policy: JobFailurePolicy) -> Self {
+        self.failure_policy = Some(failure_policy);
+        self
+    }
+
+    /// The due time, period and ttl in the sidecar's formats.
+    pub(crate) fn encode_schedule(&self) -> Result<EncodedSchedule, DaprError> {
+        self.schedule.encode()
+    }
+}
+
+/// A timer to register on the current actor.
+///
+/// Timers only live as long as the actor is active. They are delivered to
+/// the [callback](TimerSpec::with_callback) registered under the given
+/// name, or to [`super::Actor::on_timer`] when none is set.
+#[derive(Clone, Debug)]
+pub struct TimerSpec {
+    pub(crate) name: String,
+    schedule: Schedule,
+    pub(crate) data: Vec<u8>,
+    pub(crate) callback: Option<String>,
+}
+
+impl TimerSpec {
+    pub fn new(name: impl Into<String>) -> Self {
+        TimerSpec {
+            name: name.into(),
+            schedule: Schedule::default(),
+            data: Vec::new(),
+            callback: None,
+        }
+    }
+
+    /// See [`ReminderSpec::with_due_time`].
+    pub fn with_due_time(mut self, due_time: Duration) -> Self {
+        self.schedule.due_time = Some(TimeValue::After(due_time));
+        self
+    }
+
+    /// See [`ReminderSpec::with_due_at`].
+    pub fn with_due_at(mut self, due_at: DateTime<Utc>) -> Self {
+        self.schedule.due_time = Some(TimeValue::At(due_at));
+        self
+    }
+
+    /// See [`ReminderSpec::with_period`].
+    pub fn with_period(mut self, period: Duration) -> Self {
+        self.schedule.period = Some(Period::Every(period));
+        self
+    }
+
+    /// See [`ReminderSpec::with_repetitions`].
+    pub fn with_repetitions(mut self, repetitions: u32) -> Self {
+        self.schedule.repetitions = Some(repetitions);
+        self
+    }
+
+    /// See [`ReminderSpec::with_period_expr`].
+    pub fn with_period_expr(mut self, period: impl Into<String>) -> Self {
+        self.schedule.period = Some(Period::Expr(period.into()));
+        self
+    }
+
+    /// See [`ReminderSpec::with_ttl`].
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.schedule.ttl = Some(TimeValue::After(ttl));
+        self
+    }
+
+    /// See [`ReminderSpec::with_expires_at`].
+    pub fn with_expires_at(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.schedule.ttl = Some(TimeValue::At(expires_at));
+        self
+    }
+
+    /// See [`ReminderSpec::with_data`].
+    pub fn with_data<T: Serialize>(mut self, data: &T) -> Result<Self, DaprError> {
+        self.data = serde_json::to_vec(data).map_err(|_| DaprError::SerializationError)?;
+        Ok(self)
+    }
+
+    /// See [`ReminderSpec::with_raw_data`].
+    pub fn with_raw_data(mut self, data: Vec<u8>) -> Self {
+        self.data = data;
+        self
+    }
+
+    /// Delivers the timer to the callback registered under `callback` with
+    /// [`super::runtime::ActorTypeRegistration::register_timer_callback`].
+    pub fn with_callback(mut self, callback: impl Into<String>) -> Self {
+        self.callback = Some(callback.into());
+        self
+    }
+
+    /// The due time, period and ttl in the sidecar's formats.
+    pub(crate) fn encode_schedule(&self) -> Result<EncodedSchedule, DaprError> {
+        self.schedule.encode()
+    }
+}
+
+/// Deserializes the data of a timer or reminder attached with `with_data`.
+pub fn from_data<T: DeserializeOwned>(data: &[u8]) -> Result<T, ActorError> {
+    serde_json::from_slice(data).map_err(|_| ActorError::SerializationError())
+}
+
+#[derive(Clone, Debug, Default)]
+struct Schedule {
+    due_time: Option<TimeValue>,
+    period: Option<Period>,
+    repetitions: Option<u32>,
+    ttl: Option<TimeValue>,
+}
+
+#[derive(Clone, Debug)]
+enum TimeValue {
+    After(Duration),
+    At(DateTime<Utc>),
+}
+
+#[derive(Clone, Debug)]
+enum Period {
+    Every(Duration),
+    Expr(String),
+}
+
+/// Due time, period and ttl as sent to the sidecar; empty when unset.
+pub(crate) struct EncodedSchedule {
+    pub(crate) due_time: String,
+    pub(crate) period: String,
+    pub(crate) ttl: String,
+}
+
+impl Schedule {
+    fn encode(&self) -> Result<EncodedSchedule, DaprError> {
+        let period = match (&self.period, self.repetitions) {
+            (None, None) => String::new(),
+            (None, Some(_)) => {
+                return Err(DaprError::InvalidDuration(
+                    "repetitions require a period".to_string(),
+                ));
+            }
+            (Some(Period::Every(every)), None) => format_duration(*every)?,
+            (Some(Period::Every(every)), Some(times)) => {
+                format!("R{times}/{}", iso8601_duration(*every)?)
+            }
+            (Some(Period::Expr(expr)), None) => expr.clone(),
+            (Some(Period::Expr(_)), Some(_)) => {
+                return Err(DaprError::InvalidDuration(
+                    "repetitions cannot be combined with a period expression".to_string(),
+                ));
+            }
+        };
+        Ok(EncodedSchedule {
+            due_time: encode_time(self.due_time.as_ref())?,
+            period,
+            ttl: encode_time(self.ttl.as_ref())?,
+        })
+    }
+}
+
+fn encode_time(value: Option<&TimeValue>) -> Result<String, DaprError> {
+    match value {
+        None => Ok(String::new()),
+        Some(TimeValue::After(d)) => format_duration(*d),
+        Some(TimeValue::At(t)) => Ok(t.to_rfc3339_opts(SecondsFormat::AutoSi, true)),
+    }
+}
+
+/// Formats `d` as an ISO-8601 duration, or as a Go duration if it is not a
+/// whole number of seconds.
+fn format_duration(d: Duration) -> Result<String, DaprError> {
+    check_range(d)?;
+    if d.subsec_nanos() == 0 {
+        Ok(format!("PT{}S", d.as_secs()))
+    } else {
+        Ok(format!("{}ns", d.as_nanos()))
+    }
+}
+
+/// Formats `d` as an ISO-8601 duration, which only has whole seconds.
+fn iso8601_duration(d: Duration) -> Result<String, DaprError> {
+    check_range(d)?;
+    if d.subsec_nanos() != 0 {
+        return Err(DaprError::InvalidDuration(format!(
+            "{d:?} is not a whole number of seconds"
+        )));
+    }
+    Ok(format!("PT{}S", d.as_secs()))
+}
+
+/// The sidecar parses durations into Go's `time.Duration`, an `i64` of
+/// nanoseconds.
+fn check_range(d: Duration) -> Result<(), DaprError> {
+    if d.as_nanos() > i64::MAX as u128 {
+        return Err(DaprError::InvalidDuration(format!(
+            "{d:?} is longer than the sidecar supports"
+        )));
+    }
+    Ok(())
+}
+
+/// Recovers the registered bytes from the `data` field of a timer or
+/// reminder invocation.
+///
+/// The sidecar stores data registered over gRPC, as this SDK does, as a
+/// JSON string holding the base64-encoded bytes, so strings are always
+/// decoded as base64, even when they also read as plain text. Data
+/// registered through its HTTP API arrives as the JSON value it was
+/// registered with and is returned as that JSON; a JSON string registered
+/// that way is indistinguishable from gRPC data unless it is not valid
+/// base64.
+pub(crate) fn decode_data(data: Option<serde_json::Value>) -> Vec<u8> {
+    match data {
+        None | Some(serde_json::Value::Null) => Vec::new(),
+        Some(serde_json::Value::String(s)) => base64::engine::general_purpose::STANDARD
+            .decode(&s)
+            .unwrap_or_else(|_| serde_json::Value::String(s).to_string().into_bytes()),
+        Some(value) => value.to_string().into_bytes(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_schedules() {
+        let spec = ReminderSpec::new("r")
+            .with_due_time(Duration::from_secs(5))
+            .with_period(Duration::from_secs(10))
+            .with_repetitions(5)
+            .with_ttl(Duration::from_millis(1500));
+        let encoded = spec.encode_schedule().unwrap();
+        assert_eq!(encoded.due_time, "PT5S");
+        assert_eq!(encoded.period, "R5/PT10S");
+        assert_eq!(encoded.ttl, "1500000000ns");
+
+        let at = DateTime::parse_from_rfc3339("2030-01-02T03:04:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let spec = TimerSpec::new("t")
+            .with_due_at(at)
+            .with_period_expr("R3/PT1M");
+        let encoded = spec.encode_schedule().unwrap();
+        assert_eq!(encoded.due_time, "2030-01-02T03:04:05Z");
+        assert_eq!(encoded.period, "R3/PT1M");
+        assert_eq!(encoded.ttl, "");
+    }
+
+    #[test]
+    fn rejects_unrepresentable_schedules() {
+        let too_long = ReminderSpec::new("r").with_period(Duration::MAX);
+        assert!(matches!(
+            too_long.encode_schedule(),
+            Err(DaprError::InvalidDuration(_))
+        ));
+
+        let fractional = TimerSpec::new("t")
+            .with_period(Duration::from_millis(1500))
+            .with_repetitions(2);
+        assert!(matches!(
+            fractional.encode_schedule(),
+            Err(DaprError::InvalidDuration(_))
+        ));
+    }
+
+    #[test]
+    fn round_trips_data() {
+        #[derive(Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct Payload {
+            count: u32,
+        }
+
+        let spec = ReminderSpec::new("r")
+            .with_data(&Payload { count: 7 })
+            .unwrap();
+        // As delivered by the sidecar for data registered over gRPC.
+        let delivered = base64::engine::general_purpose::STANDARD.encode(&spec.data);
+        let data = decode_data(Some(serde_json::Value::String(delivered)));
+        assert_eq!(from_data::<Payload>(&data).unwrap(), Payload { count: 7 });
+
+        let data = decode_data(Some(serde_json::json!({ "count": 3 })));
+        assert_eq!(from_data::<Payload>(&data).unwrap(), Payload { count: 3 });
+
+        // Registered bytes whose base64 encoding also reads as plain text.
+        let data = decode_data(Some(serde_json::json!("abcd")));
+        assert_eq!(data, [0x69, 0xb7, 0x1d]);
+
+        // Only the HTTP API can register a string that is not base64.
+        let data = decode_data(Some(serde_json::json!("not base64!")));
+        assert_eq!(from_data::<String>(&data).unwrap(), "not base64!");
+    }
+}
@@ -0,0 +1,273 @@
+use std::{collections::HashMap, time::Duration};
+
+use prost_types::Any;
+use serde::{Serialize, de::DeserializeOwned};
+
+use super::context_client::{ActorContextClient, TransactionalActorStateOperation};
+use crate::error::Error as DaprError;
+
+/// Metadata key for the time-to-live of an actor state value, in seconds.
+pub const TTL_METADATA_KEY: &str = "ttlInSeconds";
+
+/// Typed access to the state of one actor instance.
+///
+/// Values are JSON encoded, read from the sidecar at most once per
+/// activation and cached. Changes are kept locally until
+/// [`ActorStateManager::save`] writes them in a single
+/// `ExecuteActorStateTransaction`. When the actor returns the manager from
+/// [`super::Actor::state_manager`], the runtime saves at the end of every
+/// successful method, timer and reminder turn and drops the pending changes
+/// of failed ones.
+///
+/// # Example:
+/// ```ignore
+/// struct Counter {
+///     state: ActorStateManager,
+/// }
+///
+/// impl Counter {
+///     async fn increment(mut actor: ActorGuard<Self>) -> Result<Json<u64>, ActorError> {
+///         let count = actor.state.get::<u64>("count").await?.unwrap_or_default() + 1;
+///         actor.state.set("count", &count)?;
+///         Ok(Json(count))
+///     }
+/// }
+///
+/// #[async_trait]
+/// impl Actor for Counter {
+///     fn state_manager(&mut self) -> Option<&mut ActorStateManager> {
+///         Some(&mut self.state)
+///     }
+///     // ...
+/// }
+/// ```
+pub struct ActorStateManager {
+    client: ActorContextClient,
+    cache: HashMap<String, StateEntry>,
+}
+
+struct StateEntry {
+    /// The encoded value, `None` if the key has no value.
+    value: Option<Vec<u8>>,
+    change: StateChange,
+    ttl: Option<Duration>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum StateChange {
+    None,
+    Upsert,
+    Remove,
+}
+
+impl ActorStateManager {
+    pub fn new(client: ActorContextClient) -> Self {
+        ActorStateManager {
+            client,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Gets the value stored under `key`, or `None` if there is none.
+    pub async fn get<T: DeserializeOwned>(&mut self, key: &str) -> Result<Option<T>, DaprError> {
+        match self.load(key).await? {
+            Some(bytes) => serde_json::from_slice(bytes)
+                .map(Some)
+                .map_err(|_| DaprError::SerializationError),
+            None => Ok(None),
+        }
+    }
+
+    /// Sets the value stored under `key`.
+    pub fn set<T: Serialize>(&mut self, key: &str, value: &T) -> Result<(), DaprError> {
+        self.upsert(key, value, None)
+    }
+
+    /// Sets the value stored under `key`, to expire after `ttl`.
+    ///
+    /// The sidecar takes whole seconds, so `ttl` is rounded up to the next
+    /// second, and to at least one second.
+    pub fn set_with_ttl<T: Serialize>(
+        &mut self,
+        key: &str,
+        value: &T,
+        ttl: Duration,
+    ) -> Result<(), DaprError> {
+        self.upsert(key, value, Some(ttl))
+    }
+
+    /// Removes the value stored under `key`. Returns whether there was one.
+    pub async fn remove(&mut self, key: &str) -> Result<bool, DaprError> {
+        if self.load(key).await?.is_none() {
+            return Ok(false);
+        }
+        let entry = self.cache.get_mut(key).expect("loaded above");
+        entry.value = None;
+        entry.change = StateChange::Remove;
+        entry.ttl = None;
+        Ok(true)
+    }
+
+    /// Whether a value is stored under `key`.
+    pub async fn contains(&mut self, key: &str) -> Result<bool, DaprError> {
+        Ok(self.load(key).await?.is_some())
+    }
+
+    /// Sets the value stored under `key` unless there already is one.
+    /// Returns whether the value was added.
+    pub async fn try_add<T: Serialize>(&mut self, key: &str, value: &T) -> Result<bool, DaprError> {
+        if self.contains(key).await? {
+            return Ok(false);
+        }
+        self.set(key, value)?;
+        Ok(true)
+    }
+
+    /// Whether there are changes not yet saved.
+    pub fn has_pending_changes(&self) -> bool {
+        self.cache.values().any(|e| e.change != StateChange::None)
+    }
+
+    /// Writes all pending changes in one transaction.
+    pub async fn save(&mut self) -> Result<(), DaprError> {
+        let operations = self.pending_operations();
+        if operations.is_empty() {
+            return Ok(());
+        }
+        self.client.execute_transaction(operations).await?;
+        for entry in self.cache.values_mut() {
+            entry.change = StateChange::None;
+        }
+        Ok(())
+    }
+
+    /// Drops the cache, including any pending changes.
+    pub fn clear_cache(&mut self) {
+        self.cache.clear();
+    }
+
+    async fn load(&mut self, key: &str) -> Result<Option<&[u8]>, DaprError> {
+        if !self.cache.contains_key(key) {
+            let data = self.client.get_actor_state(key).await?.data;
+            self.cache.insert(
+                key.to_string(),
+                StateEntry {
+                    value: (!data.is_empty()).then_some(data),
+                    change: StateChange::None,
+                    ttl: None,
+                },
+            );
+        }
+        Ok(self.cache[key].value.as_deref())
+    }
+
+    fn upsert<T: Serialize>(
+        &mut self,
+        key: &str,
+        value: &T,
+        ttl: Option<Duration>,
+    ) -> Result<(), DaprError> {
+        let value = serde_json::to_vec(value).map_err(|_| DaprError::SerializationError)?;
+        self.cache.insert(
+            key.to_string(),
+            StateEntry {
+                value: Some(value),
+                change: StateChange::Upsert,
+                ttl,
+            },
+        );
+        Ok(())
+    }
+
+    fn pending_operations(&self) -> Vec<TransactionalActorStateOperation> {
+        let mut operations: Vec<_> = self
+            .cache
+            .iter()
+            .filter_map(|(key, entry)| {
+                let operation_type = match entry.change {
+                    StateChange::None => return None,
+                    StateChange::Upsert => "upsert",
+                    StateChange::Remove => "delete",
+                };
+                let mut metadata = HashMap::new();
+                if let Some(ttl) = entry.ttl {
+                    metadata.insert(TTL_METADATA_KEY.to_string(), ttl_in_seconds(ttl));
+                }
+                Some(TransactionalActorStateOperation {
+                    operation_type: operation_type.to_string(),
+                    key: key.clone(),
+                    value: entry.value.clone().map(|v| Any {
+                        type_url: "type.googleapis.com/bytes".to_string(),
+                        value: v,
+                    }),
+                    metadata,
+                })
+            })
+            .collect();
+        operations.sort_by(|a, b| a.key.cmp(&b.key));
+        operations
+    }
+}
+
+/// `ttl` in whole seconds for [TTL_METADATA_KEY], rounded up: a store may
+/// read `0` as "never expire".
+fn ttl_in_seconds(ttl: Duration) -> String {
+    let seconds = ttl.as_secs() + u64::from(ttl.subsec_nanos() > 0);
+    seconds.max(1).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{ApiTokenInterceptor, TraceContextInterceptor};
+    use crate::dapr::proto::runtime::v1::dapr_client::DaprClient;
+    use tonic::service::interceptor::InterceptedService;
+
+    fn state_manager() -> ActorStateManager {
+        // Never connected: the tests below only touch locally set keys.
+        let channel = tonic::transport::Channel::from_static("http://127.0.0.1:1").connect_lazy();
+        let channel = InterceptedService::new(
+            InterceptedService::new(channel, TraceContextInterceptor),
+            ApiTokenInterceptor::default(),
+        );
+        let client = ActorContextClient::new(DaprClient::new(channel), "MyActor", "1");
+        ActorStateManager::new(client)
+    }
+
+    #[tokio::test]
+    async fn tracks_changes_as_transaction_operations() {
+        let mut state = state_manager();
+        state.set("a", &1u32).unwrap();
+        state
+            .set_with_ttl("b", &"two", Duration::from_secs(90))
+            .unwrap();
+        assert!(!state.try_add("a", &3u32).await.unwrap());
+        assert!(state.remove("a").await.unwrap());
+        assert!(!state.contains("a").await.unwrap());
+        assert_eq!(
+            state.get::<String>("b").await.unwrap().as_deref(),
+            Some("two")
+        );
+
+        let operations = state.pending_operations();
+        assert_eq!(operations.len(), 2);
+        assert_eq!(operations[0].key, "a");
+        assert_eq!(operations[0].operation_type, "delete");
+        assert!(operations[0].value.is_none());
+        assert_eq!(operations[1].key, "b");
+        assert_eq!(operations[1].operation_type, "upsert");
+        assert_eq!(operations[1].value.as_ref().unwrap().value, b"\"two\"");
+        assert_eq!(operations[1].metadata[TTL_METADATA_KEY], "90");
+
+        state.clear_cache();
+        assert!(!state.has_pending_changes());
+    }
+
+    #[test]
+    fn ttls_round_up_to_whole_seconds() {
+        assert_eq!(ttl_in_seconds(Duration::from_secs(90)), "90");
+        assert_eq!(ttl_in_seconds(Duration::from_millis(1500)), "2");
+        assert_eq!(ttl_in_seconds(Duration::from_millis(200)), "1");
+        assert_eq!(ttl_in_seconds(Duration::ZERO), "1");
+    }
+}
@@ -0,0 +1,446 @@
+//! Testing actors in-process, without a Dapr sidecar, enabled by the
+//! `testing` feature.
+//!
+//! An [`ActorTestHarness`] hosts one actor type on an [`ActorRuntime`] whose
+//! [`ActorContextClient`](super::context_client::ActorContextClient)s are
+//! backed by memory: state, timers and reminders are recorded instead of
+//! being sent to the sidecar, and timers and reminders only fire when the
+//! test fires them.
+//!
+//! ```ignore
+//! let harness = ActorTestHarness::new(
+//!     ActorTypeRegistration::new::<Counter>("Counter", factory)
+//!         .register_method("increment", Counter::increment),
+//! )
+//! .await;
+//!
+//! let count: u64 = harness.invoke("c1", "increment", ()).await?;
+//! assert_eq!(count, 1);
+//! assert_eq!(harness.state::<u64>("c1", "count")?, Some(1));
+//! assert_eq!(harness.transactions("c1").len(), 1);
+//!
+//! harness.fire_reminder("c1", "reset").await?;
+//! ```
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex as SyncMutex},
+};
+
+use axum::{
+    Router,
+    body::{Body, to_bytes},
+    http::{Request, header::CONTENT_TYPE},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use tower::util::ServiceExt;
+
+use super::{
+    ACTOR_ERROR_HEADER, ActorError, ActorErrorEnvelope,
+    context_client::{
+        ActorBackend, ExecuteActorStateTransactionRequest, GetActorReminderRequest,
+        GetActorStateRequest, GetActorStateResponse, ListActorRemindersRequest,
+        RegisterActorReminderRequest, RegisterActorTimerRequest, TransactionalActorStateOperation,
+        UnregisterActorReminderRequest, UnregisterActorRemindersByTypeRequest,
+        UnregisterActorTimerRequest,
+    },
+    runtime::{ActorRuntime, ActorTurn, ActorTypeRegistration},
+};
+use crate::client::{
+    ActorReminder,
+    codec::{Decode, Encode, Json},
+};
+use crate::error::Error;
+
+type ActorKey = (String, String);
+
+/// The state, timers and reminders of actors hosted by an
+/// [`ActorTestHarness`], standing in for the sidecar's actor APIs.
+#[derive(Default)]
+pub(crate) struct InMemoryActors {
+    store: SyncMutex<Store>,
+}
+
+#[derive(Default)]
+struct Store {
+    state: HashMap<ActorKey, HashMap<String, Vec<u8>>>,
+    transactions: HashMap<ActorKey, Vec<Vec<TransactionalActorStateOperation>>>,
+    timers: HashMap<ActorKey, BTreeMap<String, RegisterActorTimerRequest>>,
+    reminders: HashMap<ActorKey, BTreeMap<String, RegisterActorReminderRequest>>,
+}
+
+impl InMemoryActors {
+    fn store(&self) -> std::sync::MutexGuard<'_, Store> {
+        self.store.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn get_actor_state(&self, request: GetActorStateRequest) -> GetActorStateResponse {
+        let key = (request.actor_type, request.actor_id);
+        let data = self
+            .store()
+            .state
+            .get(&key)
+            .and_then(|state| state.get(&request.key))
+            .cloned()
+            .unwrap_or_default();
+        GetActorStateResponse {
+            data,
+            metadata: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn execute_actor_state_transaction(
+        &self,
+        request: ExecuteActorStateTransactionRequest,
+    ) {
+        let key = (request.actor_type, request.actor_id);
+        let mut store = self.store();
+        let state = store.state.entry(key.clone()).or_default();
+        for operation in &request.operations {
+            match operation.operation_type.as_str() {
+                "upsert" => {
+                    let value = operation.value.as_ref().map(|v| v.value.clone());
+                    state.insert(operation.key.clone(), value.unwrap_or_default());
+                }
+                _ => {
+                    state.remove(&operation.key);
+                }
+            }
+        }
+        store
+            .transactions
+            .entry(key)
+            .or_default()
+            .push(request.operations);
+    }
+
+    pub(crate) fn register_actor_reminder(&self, request: RegisterActorReminderRequest) {
+        let key = (request.actor_type.clone(), request.actor_id.clone());
+        let mut store = self.store();
+        let reminders = store.reminders.entry(key).or_default();
+        if request.overwrite == Some(false) && reminders.contains_key(&request.name) {
+            return;
+        }
+        reminders.insert(request.name.clone(), request);
+    }
+
+    pub(crate) fn unregister_actor_reminder(&self, request: UnregisterActorReminderRequest) {
+        let key = (request.actor_type, request.actor_id);
+        if let Some(reminders) = self.store().reminders.get_mut(&key) {
+            reminders.remove(&request.name);
+        }
+    }
+
+    pub(crate) fn get_actor_reminder(
+        &self,
+        request: GetActorReminderRequest,
+    ) -> Result<ActorReminder, Error> {
+        let key = (request.actor_type, request.actor_id);
+        self.store()
+            .reminders
+            .get(&key)
+            .and_then(|reminders| reminders.get(&request.name))
+            .map(reminder)
+            .ok_or_else(|| tonic::Status::not_found("reminder not found").into())
+    }
+
+    pub(crate) fn list_actor_reminders(
+        &self,
+        request: ListActorRemindersRequest,
+    ) -> Vec<ActorReminder> {
+        self.store()
+            .reminders
+            .iter()
+            .filter(|((actor_type, actor_id), _)| {
+                *actor_type == request.actor_type
+                    && request.actor_id.as_ref().is_none_or(|id| id == actor_id)
+            })
+            .flat_map(|(_, reminders)| reminders.values().map(reminder))
+            .collect()
+    }
+
+    pub(crate) fn unregister_actor_reminders_by_type(
+        &self,
+        request: UnregisterActorRemindersByTypeRequest,
+    ) {
+        self.store().reminders.retain(|(actor_type, actor_id), _| {
+            *actor_type != request.actor_type
+                || request.actor_id.as_ref().is_some_and(|id| id != actor_id)
+        });
+    }
+
+    pub(crate) fn register_actor_timer(&self, request: RegisterActorTimerRequest) {
+        let key = (request.actor_type.clone(), request.actor_id.clone());
+        self.store()
+            .timers
+            .entry(key)
+            .or_default()
+            .insert(request.name.clone(), request);
+    }
+
+    pub(crate) fn unregister_actor_timer(&self, request: UnregisterActorTimerRequest) {
+        let key = (request.actor_type, request.actor_id);
+        if let Some(timers) = self.store().timers.get_mut(&key) {
+            timers.remove(&request.name);
+        }
+    }
+}
+
+fn reminder(request: &RegisterActorReminderRequest) -> ActorReminder {
+    let non_empty = |s: &String| (!s.is_empty()).then(|| s.clone());
+    ActorReminder {
+        actor_type: request.actor_type.clone(),
+        actor_id: request.actor_id.clone(),
+        name: request.name.clone(),
+        due_time: non_empty(&request.due_time),
+        period: non_empty(&request.period),
+        ttl: non_empty(&request.ttl),
+        data: request.data.clone(),
+    }
+}
+
+/// Hosts an actor type in-process for tests, see the [module docs](self).
+///
+/// Methods are invoked through the handlers registered on the
+/// [`ActorTypeRegistration`], as one turn each, with the same state saving
+/// and error handling as when the sidecar calls them.
+pub struct ActorTestHarness {
+    actor_type: String,
+    runtime: Arc<ActorRuntime>,
+    actors: Arc<InMemoryActors>,
+    router: Router,
+}
+
+impl ActorTestHarness {
+    /// Hosts the actor type described by `registration`.
+    pub async fn new(registration: ActorTypeRegistration) -> Self {
+        let actor_type = registration.name().to_string();
+        let actors = Arc::new(InMemoryActors::default());
+        let runtime = Arc::new(ActorRuntime::with_backend(ActorBackend::InMemory(
+            actors.clone(),
+        )));
+        runtime.register_actor(registration).await;
+        let router = runtime
+            .configure_method_routes(Router::new(), runtime.clone())
+            .await;
+        ActorTestHarness {
+            actor_type,
+            runtime,
+            actors,
+            router,
+        }
+    }
+
+    /// The runtime hosting the actors, e.g. to configure it or to deactivate
+    /// actors.
+    pub fn runtime(&self) -> &Arc<ActorRuntime> {
+        &self.runtime
+    }
+
+    /// Invokes `method` on the actor with a JSON encoded `input`, like
+    /// [`crate::Client::invoke_actor`].
+    pub async fn invoke<TInput, TOutput>(
+        &self,
+        actor_id: &str,
+        method: &str,
+        input: TInput,
+    ) -> Result<TOutput, Error>
+    where
+        TInput: Serialize,
+        TOutput: DeserializeOwned,
+    {
+        self.invoke_with_codec(actor_id, method, input, Json).await
+    }
+
+    /// Invokes `method` on the actor with `input` encoded by `codec`, like
+    /// [`crate::Client::invoke_actor_with_codec`].
+    ///
+    /// Errors returned by the method, and failed responses such as for an
+    /// unknown method, are reported as [`Error::ActorMethodError`].
+    pub async fn invoke_with_codec<C, TInput, TOutput>(
+        &self,
+        actor_id: &str,
+        method: &str,
+        input: TInput,
+        codec: C,
+    ) -> Result<TOutput, Error>
+    where
+        C: Encode<TInput> + Decode<TOutput>,
+    {
+        let request = Request::put(format!(
+            "/actors/{}/{actor_id}/method/{method}",
+            self.actor_type
+        ))
+        .header(CONTENT_TYPE, C::CONTENT_TYPE)
+        .body(Body::from(codec.encode(&input)?))
+        .expect("actor id and method name must be valid in a URI path");
+
+        let response = match self.router.clone().oneshot(request).await {
+            Ok(response) => response,
+            Err(infallible) => match infallible {},
+        };
+        let status = response.status();
+        let is_error = response.headers().contains_key(ACTOR_ERROR_HEADER);
+        let body = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .map_err(|_| Error::SerializationError)?;
+
+        if is_error {
+            let envelope: ActorErrorEnvelope =
+                serde_json::from_slice(&body).map_err(|_| Error::SerializationError)?;
+            return Err(Error::ActorMethodError {
+                status: envelope.error.status,
+                body: serde_json::to_vec(&envelope.error.body)
+                    .map_err(|_| Error::SerializationError)?,
+            });
+        }
+        if !status.is_success() {
+            return Err(Error::ActorMethodError {
+                status: status.as_u16(),
+                body: body.to_vec(),
+            });
+        }
+        codec.decode(&body)
+    }
+
+    /// Starts a turn on the actor, activating it if needed, e.g. to inspect
+    /// it with [`ActorTurn::downcast_ref`].
+    pub async fn turn(&self, actor_id: &str) -> Result<ActorTurn, ActorError> {
+        self.runtime
+            .get_or_create_actor(&self.actor_type, actor_id)
+            .await
+    }
+
+    /// Fires the timer registered on the actor under `timer_name`, running
+    /// its callback or [`super::Actor::on_timer`]. A timer without a period
+    /// is removed once fired.
+    ///
+    /// Fails with [`ActorError::MethodNotFound`] if there is no such timer.
+    pub async fn fire_timer(&self, actor_id: &str, timer_name: &str) -> Result<(), ActorError> {
+        let key = (self.actor_type.clone(), actor_id.to_string());
+        let timer = {
+            let mut store = self.actors.store();
+            let timers = store.timers.entry(key).or_default();
+            let timer = timers
+                .get(timer_name)
+                .cloned()
+                .ok_or(ActorError::MethodNotFound)?;
+            if timer.period.is_empty() {
+                timers.remove(timer_name);
+            }
+            timer
+        };
+        match timer.callback.as_str() {
+            "" => {
+                self.runtime
+                    .invoke_timer(&self.actor_type, actor_id, timer_name, timer.data)
+                    .await
+            }
+            callback => {
+                self.runtime
+                    .invoke_timer_callback(&self.actor_type, actor_id, callback, timer.data)
+                    .await
+            }
+        }
+    }
+
+    /// Fires the reminder registered on the actor under `reminder_name`,
+    /// running [`super::Actor::on_reminder`]. A reminder without a period is
+    /// removed once fired.
+    ///
+    /// Fails with [`ActorError::MethodNotFound`] if there is no such
+    /// reminder.
+    pub async fn fire_reminder(
+        &self,
+        actor_id: &str,
+        reminder_name: &str,
+    ) -> Result<(), ActorError> {
+        let key = (self.actor_type.clone(), actor_id.to_string());
+        let reminder = {
+            let mut store = self.actors.store();
+            let reminders = store.reminders.entry(key).or_default();
+            let reminder = reminders
+                .get(reminder_name)
+                .cloned()
+                .ok_or(ActorError::MethodNotFound)?;
+            if reminder.period.is_empty() {
+                reminders.remove(reminder_name);
+            }
+            reminder
+        };
+        self.runtime
+            .invoke_reminder(&self.actor_type, actor_id, reminder_name, reminder.data)
+            .await
+    }
+
+    /// The timers registered on the actor, by name.
+    pub fn timers(&self, actor_id: &str) -> Vec<RegisterActorTimerRequest> {
+        let key = (self.actor_type.clone(), actor_id.to_string());
+        self.actors
+            .store()
+            .timers
+            .get(&key)
+            .map(|timers| timers.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// The reminders registered on the actor, by name.
+    pub fn reminders(&self, actor_id: &str) -> Vec<ActorReminder> {
+        self.actors.list_actor_reminders(ListActorRemindersRequest {
+            actor_type: self.actor_type.clone(),
+            actor_id: Some(actor_id.to_string()),
+        })
+    }
+
+    /// The saved value of the actor's state under `key`, decoded from JSON.
+    pub fn state<T: DeserializeOwned>(
+        &self,
+        actor_id: &str,
+        key: &str,
+    ) -> Result<Option<T>, Error> {
+        let data = self
+            .actors
+            .get_actor_state(GetActorStateRequest {
+                actor_type: self.actor_type.clone(),
+                actor_id: actor_id.to_string(),
+                key: key.to_string(),
+            })
+            .data;
+        if data.is_empty() {
+            return Ok(None);
+        }
+        serde_json::from_slice(&data)
+            .map(Some)
+            .map_err(|_| Error::SerializationError)
+    }
+
+    /// Seeds the actor's state under `key` with a JSON encoded `value`,
+    /// without recording a transaction.
+    pub fn set_state<T: Serialize>(
+        &self,
+        actor_id: &str,
+        key: &str,
+        value: &T,
+    ) -> Result<(), Error> {
+        let value = serde_json::to_vec(value).map_err(|_| Error::SerializationError)?;
+        let actor_key = (self.actor_type.clone(), actor_id.to_string());
+        self.actors
+            .store()
+            .state
+            .entry(actor_key)
+            .or_default()
+            .insert(key.to_string(), value);
+        Ok(())
+    }
+
+    /// The state transactions saved by the actor, oldest first.
+    pub fn transactions(&self, actor_id: &str) -> Vec<Vec<TransactionalActorStateOperation>> {
+        let key = (self.actor_type.clone(), actor_id.to_string());
+        self.actors
+            .store()
+            .transactions
+            .get(&key)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
@@ -5,17 +5,78 @@ use async_trait::async_trait;
 use axum::body::{Body as AxumBody, to_bytes as axum_to_bytes};
 use axum::http::Request as AxumRequest;
 use axum::{Json, Router};
+use dapr::client::{ActorInterface, ActorProxy};
 use dapr::server::{
     DaprHttpServer,
-    actor::{Actor, ActorError, runtime::ActorTypeRegistration},
+    actor::{
+        Actor, ActorError, ActorGuard,
+        reentrancy::ReentrancyConfig,
+        runtime::{ActorRuntimeConfig, ActorTypeRegistration},
+    },
 };
-use dapr_macros::actor;
+use dapr_macros::{actor, actor_interface};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tokio::{net::TcpListener, sync::Mutex};
+use tokio::{
+    net::TcpListener,
+    sync::{Mutex, Notify},
+};
 use uuid::Uuid;
 
+/// Implements [Actor] for a test actor from the hooks it is given, e.g.
+/// `impl_actor!(MyActor, on_activate(actor) { .. })`; the others do nothing.
+macro_rules! impl_actor {
+    (@hook $actor:ident, $want:ident, $default:expr,) => {
+        $default
+    };
+    (@hook $actor:ident, on_activate, $default:expr, on_activate($this:ident) $body:block $($rest:tt)*) => {{
+        let $this = $actor;
+        $body
+    }};
+    (@hook $actor:ident, on_deactivate, $default:expr, on_deactivate($this:ident) $body:block $($rest:tt)*) => {{
+        let $this = $actor;
+        $body
+    }};
+    (@hook $actor:ident, on_reminder, $default:expr, on_reminder($this:ident) $body:block $($rest:tt)*) => {{
+        let $this = $actor;
+        $body
+    }};
+    (@hook $actor:ident, on_timer, $default:expr, on_timer($this:ident) $body:block $($rest:tt)*) => {{
+        let $this = $actor;
+        $body
+    }};
+    (@hook $actor:ident, state_manager, $default:expr, state_manager($this:ident) $body:block $($rest:tt)*) => {{
+        let $this = $actor;
+        $body
+    }};
+    (@hook $actor:ident, $want:ident, $default:expr, $other:ident($this:ident) $body:block $($rest:tt)*) => {
+        impl_actor!(@hook $actor, $want, $default, $($rest)*)
+    };
+    ($ty:ty $(, $hook:ident($this:ident) $body:block)* $(,)?) => {
+        #[async_trait]
+        impl Actor for $ty {
+            async fn on_activate(&mut self) -> Result<(), ActorError> {
+                impl_actor!(@hook self, on_activate, Ok(()), $($hook($this) $body)*)
+            }
+            async fn on_deactivate(&mut self) -> Result<(), ActorError> {
+                impl_actor!(@hook self, on_deactivate, Ok(()), $($hook($this) $body)*)
+            }
+            async fn on_reminder(&mut self, _: &str, _: Vec<u8>) -> Result<(), ActorError> {
+                impl_actor!(@hook self, on_reminder, Ok(()), $($hook($this) $body)*)
+            }
+            async fn on_timer(&mut self, _: &str, _: Vec<u8>) -> Result<(), ActorError> {
+                impl_actor!(@hook self, on_timer, Ok(()), $($hook($this) $body)*)
+            }
+            fn state_manager(
+                &mut self,
+            ) -> Option<&mut dapr::server::actor::state_manager::ActorStateManager> {
+                impl_actor!(@hook self, state_manager, None, $($hook($this) $body)*)
+            }
+        }
+    };
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct MyResponse {
     pub actor_id: String,
@@ -31,65 +92,70 @@ pub struct MyRequest {
 #[actor]
 struct MyActor {
     id: String,
+    count: u32,
 }
 
-#[async_trait]
-impl Actor for MyActor {
-    async fn on_activate(&self) -> Result<(), ActorError> {
-        TEST_STATE.increment_on_activate(&self.id).await;
+impl_actor!(
+    MyActor,
+    on_activate(actor) {
+        TEST_STATE.increment_on_activate(&actor.id).await;
         Ok(())
-    }
-
-    async fn on_deactivate(&self) -> Result<(), ActorError> {
-        TEST_STATE.increment_on_deactivate(&self.id).await;
+    },
+    on_deactivate(actor) {
+        TEST_STATE.increment_on_deactivate(&actor.id).await;
         Ok(())
+    },
+);
+
+impl MyActor {
+    async fn do_stuff(actor: ActorGuard<Self>, Json(req): Json<MyRequest>) -> Json<MyResponse> {
+        Json(MyResponse {
+            actor_id: actor.id.clone(),
+            name: req.name,
+            available: true,
+        })
     }
 
-    async fn on_reminder(&self, _reminder_name: &str, _data: Vec<u8>) -> Result<(), ActorError> {
-        Ok(())
+    async fn slow_increment(mut actor: ActorGuard<Self>) -> Json<u32> {
+        let count = actor.count;
+        // Yield mid-turn; a concurrent turn on this actor would lose an update.
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        actor.count = count + 1;
+        Json(actor.count)
     }
 
-    async fn on_timer(&self, _timer_name: &str, _data: Vec<u8>) -> Result<(), ActorError> {
-        Ok(())
+    async fn wait_for_reentry(mut actor: ActorGuard<Self>) -> Json<u32> {
+        actor.allow_reentry(REENTRY_DONE.notified()).await;
+        Json(actor.count)
     }
 }
 
-impl MyActor {
-    async fn do_stuff(&self, Json(req): Json<MyRequest>) -> Json<MyResponse> {
-        Json(MyResponse {
-            actor_id: self.id.clone(),
-            name: req.name,
-            available: true,
-        })
+#[actor_interface(actor_type = "MyActor")]
+trait CounterApi {
+    async fn add(&mut self, by: u32) -> Result<u32, ActorError>;
+    async fn get(&self) -> Result<u32, ActorError>;
+}
+
+#[async_trait]
+impl CounterApi for MyActor {
+    async fn add(&mut self, by: u32) -> Result<u32, ActorError> {
+        self.count += by;
+        Ok(self.count)
+    }
+
+    async fn get(&self) -> Result<u32, ActorError> {
+        Ok(self.count)
     }
 }
 
 #[tokio::test]
 async fn test_actor_invoke() {
-    let dapr_port = get_available_port().await.unwrap();
-
-    let fake_sidecar = tokio::spawn(async move {
-        let sidecar = Router::new();
-        let address = format!("127.0.0.1:{dapr_port}");
-        let listener = TcpListener::bind(address).await.unwrap();
-        _ = axum::serve(listener, sidecar.into_make_service()).await;
-    });
-    tokio::task::yield_now().await;
+    let dapr_port = spawn_fake_sidecar(Router::new()).await;
 
     let mut dapr_server = DaprHttpServer::with_dapr_port(dapr_port).await;
 
     dapr_server
-        .register_actor(
-            ActorTypeRegistration::new::<MyActor>(
-                "MyActor",
-                Box::new(|_actor_type, actor_id, _context| {
-                    Arc::new(MyActor {
-                        id: actor_id.to_string(),
-                    })
-                }),
-            )
-            .register_method("do_stuff", MyActor::do_stuff),
-        )
+        .register_actor(my_actor().register_method("do_stuff", MyActor::do_stuff))
         .await;
 
     let actor_id = Uuid::new_v4().to_string();
@@ -151,36 +217,16 @@ async fn test_actor_invoke() {
             .on_activate,
         1
     );
-
-    fake_sidecar.abort();
 }
 
 #[tokio::test]
 async fn test_actor_deactivate() {
-    let dapr_port = get_available_port().await.unwrap();
-
-    let fake_sidecar = tokio::spawn(async move {
-        let sidecar = Router::new();
-        let address = format!("127.0.0.1:{dapr_port}");
-        let listener = TcpListener::bind(address).await.unwrap();
-        _ = axum::serve(listener, sidecar.into_make_service()).await;
-    });
-    tokio::task::yield_now().await;
+    let dapr_port = spawn_fake_sidecar(Router::new()).await;
 
     let mut dapr_server = DaprHttpServer::with_dapr_port(dapr_port).await;
 
     dapr_server
-        .register_actor(
-            ActorTypeRegistration::new::<MyActor>(
-                "MyActor",
-                Box::new(|_actor_type, actor_id, _context| {
-                    Arc::new(MyActor {
-                        id: actor_id.to_string(),
-                    })
-                }),
-            )
-            .register_method("do_stuff", MyActor::do_stuff),
-        )
+        .register_actor(my_actor().register_method("do_stuff", MyActor::do_stuff))
         .await;
 
     let app = dapr_server.build_test_router().await;
@@ -232,8 +278,498 @@ async fn test_actor_deactivate() {
             .on_deactivate,
         1
     );
+}
+
+#[tokio::test]
+async fn test_idle_actors_are_deactivated() {
+    let dapr_port = spawn_fake_sidecar(Router::new()).await;
+
+    let dapr_server = DaprHttpServer::with_dapr_port(dapr_port)
+        .await
+        .with_actor_config(
+            ActorRuntimeConfig::new()
+                .with_idle_timeout(std::time::Duration::from_millis(50))
+                .with_max_active_actors(2),
+        );
+    dapr_server.register_actor(my_actor()).await;
+    let runtime = dapr_server.actor_runtime();
+
+    let ids: Vec<String> = (0..3).map(|_| Uuid::new_v4().to_string()).collect();
+    for id in &ids {
+        drop(runtime.get_or_create_actor("MyActor", id).await.unwrap());
+    }
+
+    // Activating the third actor evicts the least recently used one, in the
+    // background.
+    tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        while runtime.active_actor_count().await > 2 {
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .unwrap();
+    let active = runtime.list_active_actors().await;
+    assert!(!active["MyActor"].contains(&ids[0]));
+    assert_eq!(
+        TEST_STATE
+            .get_actor_state(&ids[0])
+            .await
+            .unwrap()
+            .on_deactivate,
+        1
+    );
+
+    // A busy actor is never idle.
+    let busy = runtime
+        .get_or_create_actor("MyActor", &ids[1])
+        .await
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(60)).await;
+    assert_eq!(runtime.deactivate_idle_actors().await, 1);
+    assert_eq!(
+        TEST_STATE
+            .get_actor_state(&ids[2])
+            .await
+            .unwrap()
+            .on_deactivate,
+        1
+    );
+
+    drop(busy);
+    tokio::time::sleep(std::time::Duration::from_millis(60)).await;
+    assert_eq!(runtime.deactivate_idle_actors().await, 1);
+    assert_eq!(runtime.active_actor_count().await, 0);
+}
+
+#[tokio::test]
+async fn test_evicted_actor_is_deactivated_before_reactivation() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct Instances {
+        live: AtomicU32,
+        activations: AtomicU32,
+        overlaps: AtomicU32,
+        deactivating: Notify,
+        release: Notify,
+    }
+
+    struct SlowActor {
+        id: String,
+        instances: Arc<Instances>,
+    }
+
+    impl_actor!(
+        SlowActor,
+        on_activate(actor) {
+            if actor.id == "a" {
+                let instances = &actor.instances;
+                instances.activations.fetch_add(1, Ordering::SeqCst);
+                if instances.live.fetch_add(1, Ordering::SeqCst) > 0 {
+                    instances.overlaps.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+            Ok(())
+        },
+        on_deactivate(actor) {
+            if actor.id == "a" {
+                actor.instances.deactivating.notify_one();
+                actor.instances.release.notified().await;
+                actor.instances.live.fetch_sub(1, Ordering::SeqCst);
+            }
+            Ok(())
+        },
+    );
+
+    let dapr_port = spawn_fake_sidecar(Router::new()).await;
+    let dapr_server = DaprHttpServer::with_dapr_port(dapr_port)
+        .await
+        .with_actor_config(ActorRuntimeConfig::new().with_max_active_actors(1));
+    let instances = Arc::new(Instances::default());
+    let shared = instances.clone();
+    dapr_server
+        .register_actor(ActorTypeRegistration::new::<SlowActor>(
+            "SlowActor",
+            Box::new(move |_actor_type, actor_id, _context| {
+                Box::new(SlowActor {
+                    id: actor_id.to_string(),
+                    instances: shared.clone(),
+                })
+            }),
+        ))
+        .await;
+    let runtime = dapr_server.actor_runtime();
+
+    drop(runtime.get_or_create_actor("SlowActor", "a").await.unwrap());
+    // Evicting "a" does not hold up the call that activated "b"...
+    let b = tokio::time::timeout(
+        Duration::from_secs(5),
+        runtime.get_or_create_actor("SlowActor", "b"),
+    )
+    .await
+    .expect("eviction should not block activation")
+    .unwrap();
+    drop(b);
+    instances.deactivating.notified().await;
+
+    // ...but a call to "a" waits for its deactivation to finish.
+    let reactivation = tokio::spawn({
+        let runtime = runtime.clone();
+        async move {
+            drop(runtime.get_or_create_actor("SlowActor", "a").await.unwrap());
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!reactivation.is_finished());
+
+    instances.release.notify_one();
+    tokio::time::timeout(Duration::from_secs(5), reactivation)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(instances.activations.load(Ordering::SeqCst), 2);
+    assert_eq!(instances.overlaps.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn test_timer_callback() {
+    let dapr_port = spawn_fake_sidecar(Router::new()).await;
+
+    let mut dapr_server = DaprHttpServer::with_dapr_port(dapr_port).await;
+    dapr_server
+        .register_actor(my_actor().register_timer_callback(
+            "add",
+            |actor: &mut MyActor, by: u32| {
+                Box::pin(async move {
+                    actor.count += by;
+                    Ok(())
+                })
+            },
+        ))
+        .await;
+
+    let app = dapr_server.build_test_router().await;
+    let actor_id = Uuid::new_v4().to_string();
+    let fire = |callback: &str| {
+        // The sidecar delivers data registered over gRPC base64-encoded.
+        let req = AxumRequest::builder()
+            .method("PUT")
+            .uri(format!("/actors/MyActor/{actor_id}/method/timer/t1"))
+            .header("content-type", "application/json")
+            .body(AxumBody::from(
+                json!({ "data": "NQ==", "callback": callback }).to_string(),
+            ))
+            .unwrap();
+        tower::util::ServiceExt::<AxumRequest<AxumBody>>::oneshot(app.clone(), req)
+    };
+
+    assert!(fire("add").await.unwrap().status().is_success());
+    assert!(fire("add").await.unwrap().status().is_success());
+    assert_eq!(
+        fire("missing").await.unwrap().status(),
+        axum::http::StatusCode::INTERNAL_SERVER_ERROR
+    );
+
+    let actor = dapr_server
+        .actor_runtime()
+        .get_or_create_actor("MyActor", &actor_id)
+        .await
+        .unwrap();
+    assert_eq!(actor.downcast_ref::<MyActor>().unwrap().count, 10);
+}
+
+#[tokio::test]
+async fn test_actor_turns_are_serialized() {
+    let dapr_port = spawn_fake_sidecar(Router::new()).await;
+
+    let mut dapr_server = DaprHttpServer::with_dapr_port(dapr_port).await;
+
+    dapr_server
+        .register_actor(my_actor().register_method("slow_increment", MyActor::slow_increment))
+        .await;
+
+    let app = dapr_server.build_test_router().await;
+    let actor_id = Uuid::new_v4().to_string();
+
+    let calls = (0..10).map(|_| {
+        let req = AxumRequest::builder()
+            .method("PUT")
+            .uri(format!("/actors/MyActor/{actor_id}/method/slow_increment"))
+            .body(AxumBody::empty())
+            .unwrap();
+        tower::util::ServiceExt::<AxumRequest<AxumBody>>::oneshot(app.clone(), req)
+    });
+    let mut counts = Vec::new();
+    for resp in futures::future::join_all(calls).await {
+        let resp = resp.unwrap();
+        assert!(resp.status().is_success());
+        let bytes = axum_to_bytes(resp.into_body(), 64 * 1024).await.unwrap();
+        counts.push(serde_json::from_slice::<u32>(&bytes).unwrap());
+    }
+    counts.sort_unstable();
+    assert_eq!(counts, (1..=10).collect::<Vec<_>>());
+
+    // Deactivation waits for its own turn too.
+    let delete_req = AxumRequest::builder()
+        .method("DELETE")
+        .uri(format!("/actors/MyActor/{actor_id}"))
+        .body(AxumBody::empty())
+        .unwrap();
+    let delete_resp = tower::util::ServiceExt::<AxumRequest<AxumBody>>::oneshot(app, delete_req)
+        .await
+        .unwrap();
+    assert!(delete_resp.status().is_success());
+    assert_eq!(
+        TEST_STATE
+            .get_actor_state(&actor_id)
+            .await
+            .unwrap()
+            .on_deactivate,
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_actor_reentrancy() {
+    let dapr_port = spawn_fake_sidecar(Router::new()).await;
+
+    let mut dapr_server = DaprHttpServer::with_dapr_port(dapr_port)
+        .await
+        .with_actor_reentrancy(ReentrancyConfig::enabled().with_max_stack_depth(4));
+
+    dapr_server
+        .register_actor(
+            my_actor()
+                .register_method("slow_increment", MyActor::slow_increment)
+                .register_method("wait_for_reentry", MyActor::wait_for_reentry),
+        )
+        .await;
+
+    let app = dapr_server.build_test_router().await;
+    let actor_id = Uuid::new_v4().to_string();
+    let call = |method: &str, reentrancy_id: &str| {
+        let req = AxumRequest::builder()
+            .method("PUT")
+            .uri(format!("/actors/MyActor/{actor_id}/method/{method}"))
+            .header("Dapr-Reentrancy-Id", reentrancy_id)
+            .body(AxumBody::empty())
+            .unwrap();
+        tower::util::ServiceExt::<AxumRequest<AxumBody>>::oneshot(app.clone(), req)
+    };
+
+    // The sidecar is told about the reentrancy settings.
+    let config_req = AxumRequest::builder()
+        .method("GET")
+        .uri("/dapr/config")
+        .body(AxumBody::empty())
+        .unwrap();
+    let resp = tower::util::ServiceExt::<AxumRequest<AxumBody>>::oneshot(app.clone(), config_req)
+        .await
+        .unwrap();
+    let bytes = axum_to_bytes(resp.into_body(), 64 * 1024).await.unwrap();
+    let config: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(
+        config["reentrancy"],
+        json!({ "enabled": true, "maxStackDepth": 4 })
+    );
+
+    let outer = tokio::spawn(call("wait_for_reentry", "chain-1"));
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    // A call from another chain waits for the outer turn...
+    let other_chain = tokio::time::timeout(
+        std::time::Duration::from_millis(50),
+        call("slow_increment", "chain-2"),
+    )
+    .await;
+    assert!(other_chain.is_err());
+
+    // ...while a call from the same chain re-enters the actor.
+    let nested = tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        call("slow_increment", "chain-1"),
+    )
+    .await
+    .expect("reentrant call should not wait for the outer turn")
+    .unwrap();
+    assert!(nested.status().is_success());
+
+    REENTRY_DONE.notify_one();
+    let outer = outer.await.unwrap().unwrap();
+    let bytes = axum_to_bytes(outer.into_body(), 64 * 1024).await.unwrap();
+    assert_eq!(serde_json::from_slice::<u32>(&bytes).unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_interrupted_allow_reentry_keeps_the_actor() {
+    use std::time::Duration;
+
+    struct PatientActor {
+        waits: u32,
+    }
+
+    impl_actor!(PatientActor);
+
+    impl PatientActor {
+        async fn wait(mut actor: ActorGuard<Self>) -> Json<u32> {
+            let call = actor.allow_reentry(std::future::pending::<()>());
+            let timed_out = tokio::time::timeout(Duration::from_millis(10), call).await;
+            assert!(timed_out.is_err());
+            actor.waits += 1;
+            Json(actor.waits)
+        }
+    }
+
+    let dapr_port = spawn_fake_sidecar(Router::new()).await;
+    let mut dapr_server = DaprHttpServer::with_dapr_port(dapr_port)
+        .await
+        .with_actor_reentrancy(ReentrancyConfig::enabled());
+    dapr_server
+        .register_actor(
+            ActorTypeRegistration::new::<PatientActor>(
+                "PatientActor",
+                Box::new(|_actor_type, _actor_id, _context| Box::new(PatientActor { waits: 0 })),
+            )
+            .register_method("wait", PatientActor::wait),
+        )
+        .await;
+
+    let app = dapr_server.build_test_router().await;
+    for expected in 1..=2u32 {
+        let req = AxumRequest::builder()
+            .method("PUT")
+            .uri("/actors/PatientActor/p1/method/wait")
+            .body(AxumBody::empty())
+            .unwrap();
+        let resp = tower::util::ServiceExt::<AxumRequest<AxumBody>>::oneshot(app.clone(), req)
+            .await
+            .unwrap();
+        assert!(resp.status().is_success());
+        let bytes = axum_to_bytes(resp.into_body(), 64 * 1024).await.unwrap();
+        assert_eq!(serde_json::from_slice::<u32>(&bytes).unwrap(), expected);
+    }
+}
+
+#[tokio::test]
+async fn test_reacquire_after_interrupted_allow_reentry() {
+    use std::time::Duration;
+
+    let dapr_port = spawn_fake_sidecar(Router::new()).await;
+    let dapr_server = DaprHttpServer::with_dapr_port(dapr_port)
+        .await
+        .with_actor_reentrancy(ReentrancyConfig::enabled());
+    dapr_server.register_actor(my_actor()).await;
+    let runtime = dapr_server.actor_runtime();
+    let actor_id = Uuid::new_v4().to_string();
+
+    let mut outer = runtime
+        .get_or_create_actor_reentrant("MyActor", &actor_id, Some("chain"))
+        .await
+        .unwrap();
+    let (lent_tx, lent_rx) = tokio::sync::oneshot::channel();
+    let call = outer.allow_reentry(async {
+        let nested = runtime
+            .get_or_create_actor_reentrant("MyActor", &actor_id, Some("chain"))
+            .await
+            .unwrap();
+        lent_tx.send(nested).ok();
+        std::future::pending::<()>().await
+    });
+    assert!(
+        tokio::time::timeout(Duration::from_millis(50), call)
+            .await
+            .is_err()
+    );
+
+    // The nested call still holds the actor.
+    assert!(outer.downcast_ref::<MyActor>().is_none());
+    let mut nested = lent_rx.await.unwrap();
+    nested.downcast_mut::<MyActor>().unwrap().count = 5;
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        drop(nested);
+    });
+
+    outer.reacquire().await;
+    assert_eq!(outer.downcast_ref::<MyActor>().unwrap().count, 5);
+}
+
+#[tokio::test]
+async fn test_actor_interface() {
+    let dapr_port = spawn_fake_sidecar(Router::new()).await;
+
+    let mut dapr_server = DaprHttpServer::with_dapr_port(dapr_port).await;
+
+    dapr_server
+        .register_actor(my_actor().register_interface::<dyn CounterApi, MyActor>())
+        .await;
+
+    let app = dapr_server.build_test_router().await;
+    let actor_id = Uuid::new_v4().to_string();
+    let call = |method: &str, body: &str| {
+        let req = AxumRequest::builder()
+            .method("PUT")
+            .uri(format!("/actors/MyActor/{actor_id}/method/{method}"))
+            .header("content-type", "application/json")
+            .body(AxumBody::from(body.to_string()))
+            .unwrap();
+        tower::util::ServiceExt::<AxumRequest<AxumBody>>::oneshot(app.clone(), req)
+    };
+
+    let resp = call("add", "3").await.unwrap();
+    assert!(resp.status().is_success());
+    let bytes = axum_to_bytes(resp.into_body(), 64 * 1024).await.unwrap();
+    assert_eq!(serde_json::from_slice::<u32>(&bytes).unwrap(), 3);
+
+    let resp = call("get", "").await.unwrap();
+    assert!(resp.status().is_success());
+    let bytes = axum_to_bytes(resp.into_body(), 64 * 1024).await.unwrap();
+    assert_eq!(serde_json::from_slice::<u32>(&bytes).unwrap(), 3);
+}
+
+#[tokio::test]
+async fn test_actor_guard_rejects_other_actor_type() {
+    struct OtherActor;
+
+    impl_actor!(OtherActor);
+
+    let dapr_port = spawn_fake_sidecar(Router::new()).await;
+
+    let mut dapr_server = DaprHttpServer::with_dapr_port(dapr_port).await;
+
+    // The factory builds a different type than the handler extracts.
+    dapr_server
+        .register_actor(
+            ActorTypeRegistration::new::<MyActor>(
+                "MismatchedActor",
+                Box::new(|_actor_type, _actor_id, _context| Box::new(OtherActor)),
+            )
+            .register_method("do_stuff", MyActor::do_stuff),
+        )
+        .await;
+
+    let app = dapr_server.build_test_router().await;
+    let req = AxumRequest::builder()
+        .method("PUT")
+        .uri("/actors/MismatchedActor/a1/method/do_stuff")
+        .header("content-type", "application/json")
+        .body(AxumBody::from(json!({ "name": "foo" }).to_string()))
+        .unwrap();
+    let resp = tower::util::ServiceExt::<AxumRequest<AxumBody>>::oneshot(app, req)
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+}
 
-    fake_sidecar.abort();
+#[test]
+fn test_actor_interface_proxy() {
+    // Calls through the proxy need a sidecar; this checks the generated impl.
+    async fn _add(client: dapr::Client<dapr::client::TonicClientWithAuth>) -> u32 {
+        let mut proxy = ActorProxy::<dyn CounterApi>::new(client, "a1");
+        proxy.add(1).await.unwrap() + proxy.get().await.unwrap()
+    }
+    assert_eq!(<dyn CounterApi as ActorInterface>::ACTOR_TYPE, "MyActor");
 }
 
 #[derive(Clone, Debug)]
@@ -283,45 +819,43 @@ impl TestState {
 
 static TEST_STATE: Lazy<TestState> = Lazy::new(TestState::new);
 
-async fn get_available_port() -> Option<u16> {
-    for port in 8000..9000 {
-        if TcpListener::bind(format!("127.0.0.1:{port}")).await.is_ok() {
-            return Some(port);
-        }
-    }
-    None
+static REENTRY_DONE: Lazy<Notify> = Lazy::new(Notify::new);
+
+/// Serves `sidecar` as a fake Dapr sidecar, returning its port.
+async fn spawn_fake_sidecar(sidecar: Router) -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        _ = axum::serve(listener, sidecar.into_make_service()).await;
+    });
+    port
+}
+
+/// The registration of [MyActor], to register methods on.
+fn my_actor() -> ActorTypeRegistration {
+    ActorTypeRegistration::new::<MyActor>(
+        "MyActor",
+        Box::new(|_actor_type, actor_id, _context| {
+            Box::new(MyActor {
+                id: actor_id.to_string(),
+                count: 0,
+            })
+        }),
+    )
 }
 
 #[tokio::test]
 async fn test_actor_server_enforces_app_api_token() {
     use dapr::client::AppApiTokenLayer;
 
-    let dapr_port = get_available_port().await.unwrap();
-
-    let fake_sidecar = tokio::spawn(async move {
-        let sidecar = Router::new();
-        let address = format!("127.0.0.1:{dapr_port}");
-        let listener = TcpListener::bind(address).await.unwrap();
-        _ = axum::serve(listener, sidecar.into_make_service()).await;
-    });
-    tokio::task::yield_now().await;
+    let dapr_port = spawn_fake_sidecar(Router::new()).await;
 
     let mut dapr_server = DaprHttpServer::with_dapr_port(dapr_port)
         .await
         .with_app_api_token_layer(AppApiTokenLayer::new(Some("expected".to_string())));
 
     dapr_server
-        .register_actor(
-            ActorTypeRegistration::new::<MyActor>(
-                "MyActor",
-                Box::new(|_actor_type, actor_id, _context| {
-                    Arc::new(MyActor {
-                        id: actor_id.to_string(),
-                    })
-                }),
-            )
-            .register_method("do_stuff", MyActor::do_stuff),
-        )
+        .register_actor(my_actor().register_method("do_stuff", MyActor::do_stuff))
         .await;
 
     let app = dapr_server.build_test_router().await;
@@ -377,6 +911,644 @@ async fn test_actor_server_enforces_app_api_token() {
         .await
         .unwrap();
     assert!(resp.status().is_success());
+}
+
+#[tokio::test]
+async fn test_actor_calls_send_api_token() {
+    use axum::http::HeaderMap;
+    use dapr::client::ClientOptions;
+    use dapr::server::actor::context_client::ActorContextClient;
+
+    struct TokenActor {
+        client: ActorContextClient,
+    }
+
+    impl_actor!(TokenActor);
+
+    async fn read_state(mut actor: ActorGuard<TokenActor>) -> Json<bool> {
+        Json(actor.client.get_actor_state("key").await.is_ok())
+    }
+
+    let tokens = Arc::new(Mutex::new(Vec::new()));
+    let seen = tokens.clone();
+    // Records the token of every gRPC call and answers `Unimplemented`.
+    let dapr_port = spawn_fake_sidecar(Router::new().fallback(
+        move |headers: HeaderMap| async move {
+            let token = headers
+                .get("dapr-api-token")
+                .map(|v| v.to_str().unwrap().to_string());
+            seen.lock().await.push(token);
+            (
+                [("content-type", "application/grpc"), ("grpc-status", "12")],
+                "",
+            )
+        },
+    ))
+    .await;
+
+    let opts = ClientOptions::new()
+        .with_address(format!("http://127.0.0.1:{dapr_port}"))
+        .with_api_token("actor-secret");
+    let mut dapr_server = DaprHttpServer::from_options(opts).await.unwrap();
+
+    dapr_server
+        .register_actor(
+            ActorTypeRegistration::new::<TokenActor>(
+                "TokenActor",
+                Box::new(|_actor_type, _actor_id, client| Box::new(TokenActor { client })),
+            )
+            .register_method("read_state", read_state),
+        )
+        .await;
+
+    let app = dapr_server.build_test_router().await;
+    let req = AxumRequest::builder()
+        .method("PUT")
+        .uri("/actors/TokenActor/a1/method/read_state")
+        .body(AxumBody::empty())
+        .unwrap();
+    let resp = tower::util::ServiceExt::<AxumRequest<AxumBody>>::oneshot(app, req)
+        .await
+        .unwrap();
+    assert!(resp.status().is_success());
+    let body = axum_to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(body.as_ref(), b"false");
+
+    assert_eq!(*tokens.lock().await, vec![Some("actor-secret".to_string())]);
+}
+
+#[tokio::test]
+async fn test_actor_application_error_reaches_caller() {
+    use axum::body::Bytes;
+    use axum::http::{HeaderMap, StatusCode};
+    use dapr::client::ClientOptions;
+    use dapr::dapr::proto::runtime::v1::InvokeActorResponse;
+    use http_body_util::StreamBody;
+    use hyper::body::Frame;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct InsufficientFunds {
+        balance: u32,
+    }
+
+    async fn withdraw(mut actor: ActorGuard<MyActor>) -> Result<Json<u32>, ActorError> {
+        actor.count += 1;
+        Err(ActorError::application(
+            StatusCode::CONFLICT,
+            &InsufficientFunds { balance: 3 },
+        ))
+    }
+
+    let invoke_data = Arc::new(Mutex::new(Vec::new()));
+    let data = invoke_data.clone();
+    // Answers every gRPC call with an `InvokeActorResponse` carrying the body
+    // the app returned, as the sidecar does for actor errors.
+    let dapr_port = spawn_fake_sidecar(Router::new().fallback(move || async move {
+        let message = prost::Message::encode_to_vec(&InvokeActorResponse {
+            data: data.lock().await.clone(),
+        });
+        let mut frame = vec![0u8];
+        frame.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&message);
+        let mut trailers = HeaderMap::new();
+        trailers.insert("grpc-status", "0".parse().unwrap());
+        let frames = futures::stream::iter([
+            Ok::<_, std::convert::Infallible>(Frame::data(Bytes::from(frame))),
+            Ok(Frame::trailers(trailers)),
+        ]);
+        (
+            [("content-type", "application/grpc")],
+            AxumBody::new(StreamBody::new(frames)),
+        )
+    }))
+    .await;
+
+    let mut dapr_server = DaprHttpServer::with_dapr_port(dapr_port).await;
+    dapr_server
+        .register_actor(my_actor().register_method("withdraw", withdraw))
+        .await;
+
+    let app = dapr_server.build_test_router().await;
+    let actor_id = Uuid::new_v4().to_string();
+    let req = AxumRequest::builder()
+        .method("PUT")
+        .uri(format!("/actors/MyActor/{actor_id}/method/withdraw"))
+        .body(AxumBody::empty())
+        .unwrap();
+    let resp = tower::util::ServiceExt::<AxumRequest<AxumBody>>::oneshot(app, req)
+        .await
+        .unwrap();
+    // Marked so that the sidecar forwards the body instead of failing the call.
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["X-DaprErrorResponseHeader"], "true");
+    let body = axum_to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    *invoke_data.lock().await = body.to_vec();
+
+    let opts = ClientOptions::new().with_address(format!("http://127.0.0.1:{dapr_port}"));
+    let mut client = dapr::Client::from_options(opts).await.unwrap();
+    let err = client
+        .invoke_actor::<_, _, _, u32>("MyActor", actor_id.as_str(), "withdraw", (), None)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        dapr::error::Error::ActorMethodError { status: 409, .. }
+    ));
+    assert_eq!(
+        err.actor_method_error::<InsufficientFunds>(),
+        Some(InsufficientFunds { balance: 3 })
+    );
+}
+
+#[tokio::test]
+async fn test_actor_method_codecs() {
+    use axum::http::StatusCode;
+    use dapr::server::utils::{DaprJson, DaprProto};
+    use prost_types::Timestamp;
+
+    async fn bump(
+        _actor: ActorGuard<MyActor>,
+        DaprProto(ts): DaprProto<Timestamp>,
+    ) -> DaprProto<Timestamp> {
+        DaprProto(Timestamp {
+            seconds: ts.seconds + 1,
+            nanos: ts.nanos,
+        })
+    }
+
+    async fn echo(_actor: ActorGuard<MyActor>, DaprJson(value): DaprJson<u32>) -> Json<u32> {
+        Json(value)
+    }
+
+    async fn reverse(_actor: ActorGuard<MyActor>, body: axum::body::Bytes) -> Vec<u8> {
+        body.iter().rev().copied().collect()
+    }
+
+    let dapr_port = spawn_fake_sidecar(Router::new()).await;
+
+    let mut dapr_server = DaprHttpServer::with_dapr_port(dapr_port).await;
+    dapr_server
+        .register_actor(
+            my_actor()
+                .register_method("bump", bump)
+                .register_method("echo", echo)
+                .register_method("reverse", reverse),
+        )
+        .await;
+
+    let app = dapr_server.build_test_router().await;
+    let call = |method: &str, content_type: &str, body: Vec<u8>| {
+        let req = AxumRequest::builder()
+            .method("PUT")
+            .uri(format!("/actors/MyActor/a1/method/{method}"))
+            .header("content-type", content_type)
+            .body(AxumBody::from(body))
+            .unwrap();
+        tower::util::ServiceExt::<AxumRequest<AxumBody>>::oneshot(app.clone(), req)
+    };
+
+    let ts = Timestamp {
+        seconds: 1,
+        nanos: 2,
+    };
+    let resp = call(
+        "bump",
+        "application/x-protobuf",
+        prost::Message::encode_to_vec(&ts),
+    )
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "application/x-protobuf");
+    let body = axum_to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let bumped: Timestamp = prost::Message::decode(body).unwrap();
+    assert_eq!(bumped.seconds, 2);
+
+    // Each extractor honours the content type it was sent with.
+    let resp = call("bump", "application/json", b"{}".to_vec())
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    let resp = call("echo", "application/x-protobuf", b"7".to_vec())
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    let resp = call("echo", "text/plain", b"7".to_vec()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = call("reverse", "application/octet-stream", vec![1, 2, 3])
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = axum_to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(body.as_ref(), [3, 2, 1]);
+}
+
+#[tokio::test]
+async fn test_deactivate_all_drains_in_flight_turns() {
+    struct FailingActor;
+
+    impl_actor!(FailingActor, on_deactivate(_actor) {
+        Err(ActorError::CorruptedState)
+    });
+
+    let dapr_port = spawn_fake_sidecar(Router::new()).await;
+
+    let dapr_server = DaprHttpServer::with_dapr_port(dapr_port)
+        .await
+        .with_actor_config(
+            ActorRuntimeConfig::new()
+                .with_drain_ongoing_call_timeout(std::time::Duration::from_millis(200))
+                .with_drain_concurrency(2),
+        );
+    dapr_server.register_actor(my_actor()).await;
+    dapr_server
+        .register_actor(ActorTypeRegistration::new::<FailingActor>(
+            "FailingActor",
+            Box::new(|_actor_type, _actor_id, _context| Box::new(FailingActor)),
+        ))
+        .await;
+    let runtime = dapr_server.actor_runtime();
+
+    let ids: Vec<String> = (0..3).map(|_| Uuid::new_v4().to_string()).collect();
+    drop(
+        runtime
+            .get_or_create_actor("MyActor", &ids[0])
+            .await
+            .unwrap(),
+    );
+    drop(
+        runtime
+            .get_or_create_actor("FailingActor", &ids[1])
+            .await
+            .unwrap(),
+    );
+    // A turn that finishes within the drain timeout...
+    let finishing = runtime
+        .get_or_create_actor("MyActor", &ids[2])
+        .await
+        .unwrap();
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        drop(finishing);
+    });
+    // ...and one that does not.
+    let stuck_id = Uuid::new_v4().to_string();
+    let stuck = runtime
+        .get_or_create_actor("MyActor", &stuck_id)
+        .await
+        .unwrap();
+
+    let summary = runtime.deactivate_all().await;
+    assert_eq!(summary.deactivated, 2);
+    assert_eq!(summary.failed, 1);
+    assert_eq!(summary.timed_out, 1);
+    assert_eq!(runtime.active_actor_count().await, 0);
+    assert_eq!(
+        TEST_STATE
+            .get_actor_state(&ids[2])
+            .await
+            .unwrap()
+            .on_deactivate,
+        1
+    );
+    assert!(
+        TEST_STATE
+            .get_actor_state(&stuck_id)
+            .await
+            .is_some_and(|state| state.on_deactivate == 0)
+    );
+
+    drop(stuck);
+}
+
+#[tokio::test]
+async fn test_deactivate_all_uses_per_type_drain_timeout() {
+    use dapr::server::actor::runtime::ActorTypeConfig;
+
+    let dapr_port = spawn_fake_sidecar(Router::new()).await;
+
+    let dapr_server = DaprHttpServer::with_dapr_port(dapr_port)
+        .await
+        .with_actor_config(
+            ActorRuntimeConfig::new()
+                .with_drain_ongoing_call_timeout(std::time::Duration::from_millis(50)),
+        );
+    dapr_server
+        .register_actor(
+            my_actor().with_config(
+                ActorTypeConfig::new()
+                    .with_drain_ongoing_call_timeout(std::time::Duration::from_secs(5)),
+            ),
+        )
+        .await;
+    let runtime = dapr_server.actor_runtime();
+
+    // The turn outlasts the global drain timeout but not the per-type one.
+    let id = Uuid::new_v4().to_string();
+    let busy = runtime.get_or_create_actor("MyActor", &id).await.unwrap();
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        drop(busy);
+    });
+
+    let summary = runtime.deactivate_all().await;
+    assert_eq!(summary.deactivated, 1);
+    assert_eq!(summary.timed_out, 0);
+    assert_eq!(
+        TEST_STATE.get_actor_state(&id).await.unwrap().on_deactivate,
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_actor_failures_are_isolated() {
+    use dapr::server::actor::{ACTOR_ERROR_HEADER, runtime::ActivationFailure};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    static INSTANCES: AtomicU32 = AtomicU32::new(0);
+
+    struct FlakyActor {
+        id: String,
+        instance: u32,
+    }
+
+    impl_actor!(FlakyActor, on_activate(actor) {
+        match actor.id.as_str() {
+            "bad" => Err(ActorError::CorruptedState),
+            _ => Ok(()),
+        }
+    });
+
+    impl FlakyActor {
+        async fn instance(actor: ActorGuard<Self>) -> Json<u32> {
+            Json(actor.instance)
+        }
+
+        async fn explode(_actor: ActorGuard<Self>) -> Json<u32> {
+            panic!("boom")
+        }
+    }
+
+    let dapr_port = spawn_fake_sidecar(Router::new()).await;
+
+    let failures = Arc::new(std::sync::Mutex::new(Vec::<ActivationFailure>::new()));
+    let recorded = failures.clone();
+    let mut dapr_server = DaprHttpServer::with_dapr_port(dapr_port)
+        .await
+        .with_actor_config(
+            ActorRuntimeConfig::new()
+                .with_activation_backoff(
+                    std::time::Duration::from_millis(100),
+                    std::time::Duration::from_secs(1),
+                )
+                .with_max_activation_failures(2),
+        );
+    dapr_server
+        .register_actor(
+            ActorTypeRegistration::new::<FlakyActor>(
+                "FlakyActor",
+                Box::new(|_actor_type, actor_id, _context| {
+                    Box::new(FlakyActor {
+                        id: actor_id.to_string(),
+                        instance: INSTANCES.fetch_add(1, Ordering::SeqCst),
+                    })
+                }),
+            )
+            .register_method("instance", FlakyActor::instance)
+            .register_method("explode", FlakyActor::explode)
+            .on_activation_failed(move |failure| recorded.lock().unwrap().push(failure.clone())),
+        )
+        .await;
+    let runtime = dapr_server.actor_runtime();
+    let app = dapr_server.build_test_router().await;
+
+    let call = |actor_id: &str, method: &str| {
+        let req = AxumRequest::builder()
+            .method("PUT")
+            .uri(format!("/actors/FlakyActor/{actor_id}/method/{method}"))
+            .body(AxumBody::empty())
+            .unwrap();
+        let app = app.clone();
+        async move {
+            let resp = tower::util::ServiceExt::<AxumRequest<AxumBody>>::oneshot(app, req)
+                .await
+                .unwrap();
+            assert_eq!(resp.status(), axum::http::StatusCode::OK);
+            let is_error = resp.headers().contains_key(ACTOR_ERROR_HEADER);
+            let body = axum_to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            match is_error {
+                true => Err(body["actorError"]["status"].as_u64().unwrap()),
+                false => Ok(body),
+            }
+        }
+    };
+
+    // A panicking handler discards the instance; the next call gets a new one.
+    let first = call("good", "instance").await.unwrap();
+    assert_eq!(call("good", "explode").await, Err(500));
+    let second = call("good", "instance").await.unwrap();
+    assert_ne!(first, second);
+
+    // A failed activation is reported, then calls fail fast while backing off.
+    assert_eq!(call("bad", "instance").await, Err(500));
+    assert_eq!(call("bad", "instance").await, Err(503));
+    {
+        let failures = failures.lock().unwrap();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].actor_id, "bad");
+        assert_eq!(
+            failures[0].retry_after,
+            Some(std::time::Duration::from_millis(100))
+        );
+    }
+
+    // The second failure poisons the actor until its failures are cleared.
+    tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+    assert_eq!(call("bad", "instance").await, Err(500));
+    tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+    assert_eq!(call("bad", "instance").await, Err(503));
+    {
+        let failures = failures.lock().unwrap();
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[1].attempts, 2);
+        assert_eq!(failures[1].retry_after, None);
+    }
+    assert!(runtime.clear_activation_failures("FlakyActor", "bad"));
+    assert_eq!(call("bad", "instance").await, Err(500));
+
+    assert_eq!(runtime.list_active_actors().await["FlakyActor"], ["good"]);
+}
+
+#[tokio::test]
+async fn test_panicking_on_deactivate_is_isolated() {
+    use std::time::Duration;
+
+    struct FragileActor {
+        id: String,
+    }
+
+    impl_actor!(FragileActor, on_deactivate(actor) {
+        match actor.id.as_str() {
+            "bad" => panic!("boom"),
+            _ => Ok(()),
+        }
+    });
+
+    let dapr_port = spawn_fake_sidecar(Router::new()).await;
+    let dapr_server = DaprHttpServer::with_dapr_port(dapr_port)
+        .await
+        .with_actor_config(ActorRuntimeConfig::new().with_idle_timeout(Duration::ZERO));
+    dapr_server
+        .register_actor(ActorTypeRegistration::new::<FragileActor>(
+            "FragileActor",
+            Box::new(|_actor_type, actor_id, _context| {
+                Box::new(FragileActor {
+                    id: actor_id.to_string(),
+                })
+            }),
+        ))
+        .await;
+    let runtime = &dapr_server.actor_runtime();
+    let activate = |ids: [&'static str; 2]| async move {
+        for id in ids {
+            drop(
+                runtime
+                    .get_or_create_actor("FragileActor", id)
+                    .await
+                    .unwrap(),
+            );
+        }
+    };
+
+    // The idle scan deactivates every idle actor, and keeps working.
+    activate(["bad", "good"]).await;
+    assert_eq!(runtime.deactivate_idle_actors().await, 2);
+    assert_eq!(runtime.active_actor_count().await, 0);
+    activate(["bad", "good"]).await;
+    assert_eq!(runtime.deactivate_idle_actors().await, 2);
+
+    // The drain counts the panic as a failure.
+    activate(["bad", "good"]).await;
+    let summary = runtime.deactivate_all().await;
+    assert_eq!(summary.deactivated, 1);
+    assert_eq!(summary.failed, 1);
+    assert_eq!(runtime.active_actor_count().await, 0);
+}
+
+#[tokio::test]
+async fn test_actor_test_harness() {
+    use dapr::server::actor::{
+        context_client::ActorContextClient,
+        schedule::{ReminderSpec, TimerSpec},
+        state_manager::ActorStateManager,
+        testing::ActorTestHarness,
+    };
+    use std::time::Duration;
+
+    struct Counter {
+        client: ActorContextClient,
+        state: ActorStateManager,
+        ticks: u32,
+    }
+
+    impl_actor!(
+        Counter,
+        on_reminder(actor) {
+            actor.state.set("count", &0u32)?;
+            Ok(())
+        },
+        state_manager(actor) { Some(&mut actor.state) },
+    );
+
+    impl Counter {
+        async fn increment(mut actor: ActorGuard<Self>) -> Result<Json<u32>, ActorError> {
+            let count = actor.state.get::<u32>("count").await?.unwrap_or_default() + 1;
+            actor.state.set("count", &count)?;
+            let reset = ReminderSpec::new("reset").with_due_time(Duration::from_secs(3600));
+            actor.client.register_reminder(reset).await?;
+            Ok(Json(count))
+        }
+
+        async fn fail(mut actor: ActorGuard<Self>) -> Result<Json<u32>, ActorError> {
+            actor.state.set("count", &99u32)?;
+            Err(ActorError::application(
+                axum::http::StatusCode::CONFLICT,
+                &"nope",
+            ))
+        }
+
+        async fn schedule(mut actor: ActorGuard<Self>) -> Result<Json<()>, ActorError> {
+            let tick = TimerSpec::new("tick")
+                .with_period(Duration::from_secs(1))
+                .with_callback("add")
+                .with_data(&5u32)?;
+            actor.client.register_timer(tick).await?;
+            Ok(Json(()))
+        }
+    }
+
+    let harness = ActorTestHarness::new(
+        ActorTypeRegistration::new::<Counter>(
+            "Counter",
+            Box::new(|_actor_type, _actor_id, context| {
+                Box::new(Counter {
+                    client: context.clone(),
+                    state: ActorStateManager::new(context),
+                    ticks: 0,
+                })
+            }),
+        )
+        .register_method("increment", Counter::increment)
+        .register_method("fail", Counter::fail)
+        .register_method("schedule", Counter::schedule)
+        .register_timer_callback("add", |actor: &mut Counter, by: u32| {
+            Box::pin(async move {
+                actor.ticks += by;
+                Ok(())
+            })
+        }),
+    )
+    .await;
+
+    // Methods run as turns, saving state in one transaction.
+    harness.set_state("c1", "count", &41u32).unwrap();
+    let count: u32 = harness.invoke("c1", "increment", ()).await.unwrap();
+    assert_eq!(count, 42);
+    assert_eq!(harness.state::<u32>("c1", "count").unwrap(), Some(42));
+    let transactions = harness.transactions("c1");
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0][0].key, "count");
+    assert_eq!(harness.reminders("c1")[0].name, "reset");
+
+    // Failed turns save nothing.
+    let error = harness
+        .invoke::<_, u32>("c1", "fail", ())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        dapr::error::Error::ActorMethodError { status: 409, .. }
+    ));
+    assert_eq!(error.actor_method_error::<String>().unwrap(), "nope");
+    assert_eq!(harness.transactions("c1").len(), 1);
+
+    // One-shot reminders are removed once fired.
+    harness.fire_reminder("c1", "reset").await.unwrap();
+    assert_eq!(harness.state::<u32>("c1", "count").unwrap(), Some(0));
+    assert!(harness.reminders("c1").is_empty());
 
-    fake_sidecar.abort();
+    // Periodic timers keep firing when told to.
+    harness.invoke::<_, ()>("c1", "schedule", ()).await.unwrap();
+    harness.fire_timer("c1", "tick").await.unwrap();
+    harness.fire_timer("c1", "tick").await.unwrap();
+    assert_eq!(harness.timers("c1").len(), 1);
+    let turn = harness.turn("c1").await.unwrap();
+    assert_eq!(turn.downcast_ref::<Counter>().unwrap().ticks, 10);
+    drop(turn);
+    assert!(matches!(
+        harness.fire_timer("c1", "missing").await,
+        Err(ActorError::MethodNotFound)
+    ));
 }
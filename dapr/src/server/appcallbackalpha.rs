@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use tonic::{Request, Response, Status};
 
+use crate::client::{TraceContext, instrument};
 use crate::dapr::proto::runtime;
 use crate::dapr::proto::runtime::v1::app_callback_alpha_server::AppCallbackAlpha;
 
@@ -40,6 +41,7 @@ impl AppCallbackAlpha for AppCallbackServiceAlpha {
         &self,
         request: Request<runtime::v1::JobEventRequest>,
     ) -> Result<Response<runtime::v1::JobEventResponse>, Status> {
+        let trace = TraceContext::from_metadata(request.metadata());
         let request_inner = request.into_inner();
         let job_name = if !request_inner.name.is_empty() {
             request_inner.name.clone()
@@ -53,7 +55,9 @@ impl AppCallbackAlpha for AppCallbackServiceAlpha {
         };
 
         if let Some(handler) = self.job_handlers.get(&job_name) {
-            let handle_response = handler.handler(request_inner).await;
+            let handle_response =
+                instrument::server_call("OnJobEventAlpha1", trace, handler.handler(request_inner))
+                    .await;
             handle_response.map(Response::new)
         } else {
             Err(Status::not_found(format!(
@@ -0,0 +1,515 @@
+use std::{
+    convert::Infallible,
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures::{FutureExt, future::BoxFuture};
+use tokio::net::TcpListener;
+use tonic::{
+    Request, Response, Status,
+    server::NamedService,
+    service::Routes,
+    transport::{Server, server::TcpIncoming},
+};
+use tower::{Layer, Service, ServiceExt};
+
+use super::DaprHttpServer;
+use crate::client::{AppApiTokenLayer, AppApiTokenService};
+use crate::dapr::proto::runtime::v1::{
+    HealthCheckResponse,
+    app_callback_alpha_server::{AppCallbackAlpha, AppCallbackAlphaServer},
+    app_callback_health_check_server::{AppCallbackHealthCheck, AppCallbackHealthCheckServer},
+    app_callback_server::{AppCallback, AppCallbackServer},
+};
+use crate::error::Error;
+
+/// Environment variable holding the port the app listens on for callbacks
+/// from the Dapr sidecar (`dapr run --app-port`).
+pub const APP_PORT_ENV: &str = "APP_PORT";
+
+/// Default port of the gRPC app-callback server when `APP_PORT` is unset.
+pub const DEFAULT_APP_GRPC_PORT: u16 = 50051;
+
+/// The Dapr gRPC app-callback server.
+///
+/// Hosts an [`AppCallback`] implementation such as
+/// [`crate::appcallback::AppCallbackService`] for the Dapr sidecar, with:
+///
+/// - `AppCallback` and, for services that implement it, `AppCallbackAlpha`
+///   registered (older runtimes deliver jobs and bulk events through the
+///   alpha service);
+/// - an `AppCallbackHealthCheck` service, answering healthy by default;
+/// - inbound `dapr-api-token` checks via [`AppApiTokenLayer::from_env`]
+///   (a no-op when `APP_API_TOKEN` is unset);
+/// - the listening port taken from `APP_PORT`, defaulting to
+///   [`DEFAULT_APP_GRPC_PORT`];
+/// - optional graceful shutdown and, with the `reflection` feature, gRPC
+///   server reflection;
+/// - optionally, the actor types of a [`DaprHttpServer`] on the same port,
+///   see [`DaprGrpcServer::with_actors`].
+///
+/// # Example:
+/// ```no_run
+/// use dapr::appcallback::AppCallbackService;
+/// use dapr::server::DaprGrpcServer;
+///
+/// # async fn run() -> Result<(), dapr::error::Error> {
+/// let callback_service = AppCallbackService::new();
+///
+/// DaprGrpcServer::new(callback_service)
+///     .with_graceful_shutdown(async {
+///         let _ = tokio::signal::ctrl_c().await;
+///     })
+///     .serve()
+///     .await?;
+/// # Ok(()) }
+/// ```
+pub struct DaprGrpcServer {
+    routes: Routes,
+    health_check: Arc<dyn AppCallbackHealthCheck>,
+    address: Option<SocketAddr>,
+    app_api_token_layer: AppApiTokenLayer,
+    shutdown_signal: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+    actors: Option<DaprHttpServer>,
+    #[cfg(feature = "reflection")]
+    reflection: Option<Vec<&'static [u8]>>,
+}
+
+impl DaprGrpcServer {
+    /// Host `service` as both `AppCallback` and `AppCallbackAlpha`.
+    pub fn new<S>(service: S) -> Self
+    where
+        S: AppCallback + AppCallbackAlpha,
+    {
+        let service = Arc::new(service);
+        let routes = Routes::default()
+            .add_service(AppCallbackServer::from_arc(service.clone()))
+            .add_service(AppCallbackAlphaServer::from_arc(service));
+        Self::with_routes(routes)
+    }
+
+    /// Host a service that only implements `AppCallback`.
+    pub fn from_app_callback<S: AppCallback>(service: S) -> Self {
+        Self::with_routes(Routes::new(AppCallbackServer::new(service)))
+    }
+
+    fn with_routes(routes: Routes) -> Self {
+        DaprGrpcServer {
+            routes,
+            health_check: Arc::new(AlwaysHealthy),
+            address: None,
+            // Reads `APP_API_TOKEN` from the environment. Permissive if unset.
+            app_api_token_layer: AppApiTokenLayer::from_env(),
+            shutdown_signal: None,
+            actors: None,
+            #[cfg(feature = "reflection")]
+            reflection: None,
+        }
+    }
+
+    /// Register an additional gRPC service on the same server, e.g. an
+    /// `AppCallbackAlphaServer` for a custom callback or an unrelated
+    /// service of your own.
+    pub fn add_service<S>(mut self, service: S) -> Self
+    where
+        S: Service<
+                http::Request<tonic::body::Body>,
+                Response = http::Response<tonic::body::Body>,
+                Error = Infallible,
+            > + NamedService
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        S::Future: Send + 'static,
+    {
+        self.routes = self.routes.add_service(service);
+        self
+    }
+
+    /// Replace the default health check, which always reports healthy.
+    pub fn with_health_check(mut self, health_check: impl AppCallbackHealthCheck) -> Self {
+        self.health_check = Arc::new(health_check);
+        self
+    }
+
+    /// Listen on `address` instead of `127.0.0.1:$APP_PORT`.
+    pub fn with_address(mut self, address: SocketAddr) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    /// Listen on `127.0.0.1:port` instead of `127.0.0.1:$APP_PORT`.
+    pub fn with_port(self, port: u16) -> Self {
+        self.with_address(SocketAddr::from(([127, 0, 0, 1], port)))
+    }
+
+    /// Override the [`AppApiTokenLayer`] used to authenticate inbound
+    /// requests from the Dapr sidecar.
+    ///
+    /// By default, the server reads `APP_API_TOKEN` from the environment;
+    /// when the env var is unset the layer is a no-op. Unlike the HTTP
+    /// server, the health check is not exempt: the sidecar presents the
+    /// token on health probes too.
+    pub fn with_app_api_token_layer(mut self, layer: AppApiTokenLayer) -> Self {
+        self.app_api_token_layer = layer;
+        self
+    }
+
+    /// Stop accepting new requests once `signal` completes, and return from
+    /// [`DaprGrpcServer::serve`] after in-flight requests have finished.
+    pub fn with_graceful_shutdown<F>(mut self, signal: F) -> Self
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.shutdown_signal = Some(signal.boxed());
+        self
+    }
+
+    /// Also host the actor types registered on `actors`, on the same port.
+    ///
+    /// Requests are told apart by their content type: gRPC calls go to the
+    /// callback services, everything else to the actor endpoints of
+    /// `actors` (`/dapr/config`, `/actors/...` and `/healthz`). Both share
+    /// this server's [`AppApiTokenLayer`] and shutdown signal; the actors
+    /// are deactivated once the server stops.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// use dapr::appcallback::AppCallbackService;
+    /// use dapr::server::{DaprGrpcServer, DaprHttpServer};
+    ///
+    /// # async fn run() -> Result<(), dapr::error::Error> {
+    /// let actors = DaprHttpServer::new().await;
+    /// // actors.register_actor(...).await;
+    ///
+    /// DaprGrpcServer::new(AppCallbackService::new())
+    ///     .with_actors(actors)
+    ///     .serve()
+    ///     .await?;
+    /// # Ok(()) }
+    /// ```
+    pub fn with_actors(mut self, actors: DaprHttpServer) -> Self {
+        self.actors = Some(actors);
+        self
+    }
+
+    /// Serve the gRPC reflection service for the Dapr protos.
+    #[cfg(feature = "reflection")]
+    pub fn with_reflection(mut self) -> Self {
+        self.reflection
+            .get_or_insert_with(|| vec![crate::dapr::FILE_DESCRIPTOR_SET]);
+        self
+    }
+
+    /// Also serve reflection for the services in an encoded
+    /// `FileDescriptorSet`, e.g. one added with
+    /// [`DaprGrpcServer::add_service`]. Enables reflection; serving fails
+    /// with [`Error::InvalidDescriptorSet`] if the set cannot be decoded.
+    #[cfg(feature = "reflection")]
+    pub fn with_reflection_descriptor_set(mut self, descriptor_set: &'static [u8]) -> Self {
+        self.reflection
+            .get_or_insert_with(|| vec![crate::dapr::FILE_DESCRIPTOR_SET])
+            .push(descriptor_set);
+        self
+    }
+
+    /// Bind the configured address and serve until the shutdown signal (if
+    /// any) completes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ParseIntError`] if `APP_PORT` is not a valid port,
+    /// [`Error::InvalidDescriptorSet`] if a descriptor set passed to
+    /// `with_reflection_descriptor_set` cannot be decoded, and
+    /// [`Error::TransportError`] if the address cannot be bound or the
+    /// server fails.
+    pub async fn serve(self) -> Result<(), Error> {
+        let address = match self.address {
+            Some(address) => address,
+            None => SocketAddr::from(([127, 0, 0, 1], app_port_from_env(DEFAULT_APP_GRPC_PORT)?)),
+        };
+        let listener = TcpListener::bind(address).await.map_err(|e| {
+            log::error!("failed to bind app server to {address}: {e}");
+            Error::TransportError
+        })?;
+        self.serve_with_listener(listener).await
+    }
+
+    /// Serve on an already bound listener, ignoring the configured address.
+    ///
+    /// # Errors
+    ///
+    /// As for [`DaprGrpcServer::serve`], except for binding.
+    pub async fn serve_with_listener(self, listener: TcpListener) -> Result<(), Error> {
+        let routes = self.routes()?;
+        if let Some(actors) = self.actors {
+            return serve_with_actors(
+                listener,
+                routes,
+                actors,
+                self.app_api_token_layer,
+                self.shutdown_signal,
+            )
+            .await;
+        }
+
+        let incoming = TcpIncoming::from(listener);
+        let mut server = Server::builder().layer(self.app_api_token_layer);
+        let router = server.add_routes(routes);
+        match self.shutdown_signal {
+            Some(signal) => {
+                router
+                    .serve_with_incoming_shutdown(incoming, signal)
+                    .await?
+            }
+            None => router.serve_with_incoming(incoming).await?,
+        }
+        Ok(())
+    }
+
+    fn routes(&self) -> Result<Routes, Error> {
+        let routes = self
+            .routes
+            .clone()
+            .add_service(AppCallbackHealthCheckServer::new(SharedHealthCheck(
+                self.health_check.clone(),
+            )));
+
+        #[cfg(feature = "reflection")]
+        let routes = match &self.reflection {
+            Some(sets) => {
+                let mut builder = tonic_reflection::server::Builder::configure();
+                for set in sets {
+                    builder = builder.register_encoded_file_descriptor_set(set);
+                }
+                let reflection = builder
+                    .build_v1()
+                    .map_err(|e| Error::InvalidDescriptorSet(e.to_string()))?;
+                routes.add_service(reflection)
+            }
+            None => routes,
+        };
+
+        Ok(routes)
+    }
+}
+
+/// Serves `routes` and the actor endpoints of `actors` on one listener.
+async fn serve_with_actors(
+    listener: TcpListener,
+    routes: Routes,
+    actors: DaprHttpServer,
+    app_api_token_layer: AppApiTokenLayer,
+    shutdown_signal: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+) -> Result<(), Error> {
+    let runtime = actors.actor_runtime();
+    let mut actors = actors.with_app_api_token_layer(app_api_token_layer.clone());
+    let service = Multiplex {
+        http: actors.build_router().await?,
+        grpc: app_api_token_layer.layer(routes),
+    };
+    let app = axum::Router::new().fallback_service(service);
+
+    let idle_scanner = runtime.spawn_idle_scanner();
+    let server = axum::serve(listener, app.into_make_service());
+    let result = match shutdown_signal {
+        Some(signal) => server.with_graceful_shutdown(signal).await,
+        None => server.await,
+    };
+    idle_scanner.abort();
+    runtime.deactivate_all().await;
+
+    result.map_err(|e| {
+        log::error!("app server failed: {e}");
+        Error::TransportError
+    })
+}
+
+/// Routes gRPC requests to the callback services and everything else to
+/// the actor endpoints.
+#[derive(Clone)]
+struct Multiplex {
+    http: axum::Router,
+    grpc: AppApiTokenService<Routes>,
+}
+
+impl Service<axum::extract::Request> for Multiplex {
+    type Response = axum::response::Response;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: axum::extract::Request) -> Self::Future {
+        let is_grpc = req
+            .headers()
+            .get(http::header::CONTENT_TYPE)
+            .is_some_and(|v| v.as_bytes().starts_with(b"application/grpc"));
+        if is_grpc {
+            let grpc = self.grpc.clone();
+            Box::pin(async move {
+                let response = grpc.oneshot(req).await?;
+                Ok(response.map(axum::body::Body::new))
+            })
+        } else {
+            Box::pin(self.http.clone().oneshot(req))
+        }
+    }
+}
+
+/// The port in `APP_PORT`, or `default` when it is unset.
+pub(super) fn app_port_from_env(default: u16) -> Result<u16, Error> {
+    match std::env::var(APP_PORT_ENV) {
+        Ok(port) if !port.is_empty() => Ok(port.parse()?),
+        _ => Ok(default),
+    }
+}
+
+struct AlwaysHealthy;
+
+#[tonic::async_trait]
+impl AppCallbackHealthCheck for AlwaysHealthy {
+    async fn health_check(
+        &self,
+        _request: Request<()>,
+    ) -> Result<Response<HealthCheckResponse>, Status> {
+        Ok(Response::new(HealthCheckResponse {}))
+    }
+}
+
+struct SharedHealthCheck(Arc<dyn AppCallbackHealthCheck>);
+
+#[tonic::async_trait]
+impl AppCallbackHealthCheck for SharedHealthCheck {
+    async fn health_check(
+        &self,
+        request: Request<()>,
+    ) -> Result<Response<HealthCheckResponse>, Status> {
+        self.0.health_check(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::appcallback::AppCallbackService;
+    use crate::client::API_TOKEN_METADATA_KEY;
+    use crate::dapr::proto::runtime::v1::{
+        app_callback_client::AppCallbackClient,
+        app_callback_health_check_client::AppCallbackHealthCheckClient,
+    };
+
+    async fn start(server: DaprGrpcServer) -> (String, tokio::sync::oneshot::Sender<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let server = server.with_graceful_shutdown(async {
+            let _ = rx.await;
+        });
+        tokio::spawn(server.serve_with_listener(listener));
+        (addr, tx)
+    }
+
+    #[tokio::test]
+    async fn serves_callback_and_health_check() {
+        let server = DaprGrpcServer::new(AppCallbackService::new())
+            .with_app_api_token_layer(AppApiTokenLayer::new(None));
+        let (addr, shutdown) = start(server).await;
+
+        let mut health = AppCallbackHealthCheckClient::connect(addr.clone())
+            .await
+            .unwrap();
+        health.health_check(()).await.unwrap();
+
+        let mut callback = AppCallbackClient::connect(addr).await.unwrap();
+        let subscriptions = callback
+            .list_topic_subscriptions(())
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(subscriptions.subscriptions.is_empty());
+
+        shutdown.send(()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn enforces_app_api_token() {
+        let server = DaprGrpcServer::new(AppCallbackService::new())
+            .with_app_api_token_layer(AppApiTokenLayer::new(Some("expected".to_string())));
+        let (addr, shutdown) = start(server).await;
+
+        let mut health = AppCallbackHealthCheckClient::connect(addr).await.unwrap();
+        let status = health.health_check(()).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+        let mut request = tonic::Request::new(());
+        request
+            .metadata_mut()
+            .insert(API_TOKEN_METADATA_KEY, "expected".parse().unwrap());
+        health.health_check(request).await.unwrap();
+
+        shutdown.send(()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn serves_actors_on_the_same_port() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let sidecar = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let dapr_port = sidecar.local_addr().unwrap().port();
+        let fake_sidecar = tokio::spawn(async move {
+            _ = axum::serve(sidecar, axum::Router::new().into_make_service()).await;
+        });
+        let actors = DaprHttpServer::with_dapr_port(dapr_port).await;
+
+        let server = DaprGrpcServer::new(AppCallbackService::new())
+            .with_app_api_token_layer(AppApiTokenLayer::new(None))
+            .with_actors(actors);
+        let (addr, shutdown) = start(server).await;
+
+        let mut health = AppCallbackHealthCheckClient::connect(addr.clone())
+            .await
+            .unwrap();
+        health.health_check(()).await.unwrap();
+
+        let mut stream = tokio::net::TcpStream::connect(addr.trim_start_matches("http://"))
+            .await
+            .unwrap();
+        stream
+            .write_all(b"GET /dapr/config HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.contains("\"entities\""), "{response}");
+
+        shutdown.send(()).unwrap();
+        fake_sidecar.abort();
+    }
+
+    #[cfg(feature = "reflection")]
+    #[test]
+    fn reflection_registers_dapr_descriptors() {
+        let server = DaprGrpcServer::new(AppCallbackService::new()).with_reflection();
+        assert!(server.routes().is_ok());
+    }
+
+    #[cfg(feature = "reflection")]
+    #[tokio::test]
+    async fn invalid_reflection_descriptor_set_is_an_error() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let result = DaprGrpcServer::new(AppCallbackService::new())
+            .with_reflection_descriptor_set(b"not a descriptor set")
+            .serve_with_listener(listener)
+            .await;
+        assert!(matches!(result, Err(Error::InvalidDescriptorSet(_))));
+    }
+}
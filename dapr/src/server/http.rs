@@ -1,19 +1,38 @@
 use axum::{
     Json, Router,
-    extract::{OriginalUri, Path, State},
+    body::Body,
+    extract::{MatchedPath, OriginalUri, Path, Request, State},
+    handler::Handler,
     http::StatusCode,
-    response::IntoResponse,
-    routing::{delete, get, put},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{MethodRouter, any, delete, get, post, put},
 };
 use futures::{Future, FutureExt};
-use std::{pin::Pin, sync::Arc, time::Duration};
+use std::{collections::HashSet, net::SocketAddr, pin::Pin, sync::Arc, time::Duration};
 use tokio::net::TcpListener;
+#[cfg(unix)]
+use tokio::net::UnixListener;
 
 use super::super::client::{
-    AppApiTokenLayer, TonicClient,
+    AppApiTokenLayer, ClientOptions, TonicClientWithAuth, TraceContext,
     config::{DAPR_GRPC_PORT_ENV, DEFAULT_DAPR_GRPC_PORT},
+    connect_with_options, instrument,
 };
-use super::actor::runtime::{ActorRuntime, ActorTypeRegistration};
+use super::super::error::Error as DaprError;
+use super::actor::{
+    reentrancy::{self, REENTRANCY_ID_HEADER, ReentrancyConfig},
+    runtime::{ActorRuntime, ActorRuntimeConfig, ActorTypeRegistration},
+    schedule,
+};
+use super::grpc::app_port_from_env;
+use super::pubsub::{Subscription, route_path};
+
+/// Default port of the HTTP server when `APP_PORT` is unset.
+pub const DEFAULT_APP_HTTP_PORT: u16 = 8080;
+
+/// Routes served by the SDK itself, which app routes must not shadow.
+const RESERVED_ROUTES: &[&str] = &["/healthz", "/dapr/config", "/dapr/subscribe"];
 
 /// The Dapr HTTP server.
 ///
@@ -21,8 +40,7 @@ use super::actor::runtime::{ActorRuntime, ActorTypeRegistration};
 ///
 /// # Example:
 /// ```ignore
-/// # use std::sync::Arc;
-/// # use dapr::server::actor::{context_client::ActorContextClient, Actor, ActorError, ActorFactory, runtime::ActorTypeRegistration};
+/// # use dapr::server::actor::{context_client::ActorContextClient, Actor, ActorError, ActorFactory, ActorGuard, runtime::ActorTypeRegistration};
 /// # use dapr::server::utils::DaprJson;
 /// # use dapr::actor;
 /// # use axum::{Json, Router};
@@ -35,16 +53,16 @@ use super::actor::runtime::{ActorRuntime, ActorTypeRegistration};
 /// #
 /// # #[async_trait::async_trait]
 /// # impl Actor for MyActor {
-/// #    async fn on_activate(&self) -> Result<(), ActorError> {
+/// #    async fn on_activate(&mut self) -> Result<(), ActorError> {
 /// #        todo!()
 /// #    }
-/// #    async fn on_deactivate(&self) -> Result<(), ActorError> {
+/// #    async fn on_deactivate(&mut self) -> Result<(), ActorError> {
 /// #         todo!()
 /// #    }
-/// #    async fn on_reminder(&self, reminder_name: &str, data: Vec<u8>) -> Result<(), ActorError> {
+/// #    async fn on_reminder(&mut self, reminder_name: &str, data: Vec<u8>) -> Result<(), ActorError> {
 /// #         todo!()
 /// #    }
-/// #    async fn on_timer(&self, timer_name: &str, data: Vec<u8>) -> Result<(), ActorError> {
+/// #    async fn on_timer(&mut self, timer_name: &str, data: Vec<u8>) -> Result<(), ActorError> {
 /// #         todo!()
 /// #    }
 /// # }
@@ -59,7 +77,7 @@ use super::actor::runtime::{ActorRuntime, ActorTypeRegistration};
 ///}   
 ///
 ///impl MyActor {
-///    fn do_stuff(&self, DaprJson(data): DaprJson<MyRequest>) -> Json<MyResponse> {        
+///    async fn do_stuff(_actor: ActorGuard<Self>, DaprJson(data): DaprJson<MyRequest>) -> Json<MyResponse> {        
 ///        println!("doing stuff with {}", data.name);        
 ///        Json(MyResponse {
 ///            available: true
@@ -70,7 +88,7 @@ use super::actor::runtime::{ActorRuntime, ActorTypeRegistration};
 /// let mut dapr_server = dapr::server::DaprHttpServer::new().await;
 ///     
 /// dapr_server.register_actor(ActorTypeRegistration::new::<MyActor>("MyActor", Box::new(|_actor_type, actor_id, context| {
-///     Arc::new(MyActor {
+///     Box::new(MyActor {
 ///         id: actor_id.to_string(),
 ///         client: context,
 ///     })}))
@@ -84,6 +102,8 @@ pub struct DaprHttpServer {
     actor_runtime: Arc<ActorRuntime>,
     shutdown_signal: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
     app_api_token_layer: AppApiTokenLayer,
+    subscriptions: Vec<Subscription>,
+    app_routes: Vec<(String, MethodRouter)>,
 }
 
 impl DaprHttpServer {
@@ -120,13 +140,28 @@ impl DaprHttpServer {
     /// not panic, but instead returns a Result.
     ///
     /// The connection uses exponential-backoff retries in case the sidecar
-    /// is not yet ready.
+    /// is not yet ready. The api token and timeout are read from the
+    /// environment as by [`ClientOptions::from_env`].
     pub async fn try_new_with_dapr_port(
         dapr_port: u16,
     ) -> Result<Self, Box<dyn std::error::Error>> {
-        let dapr_addr = format!("http://127.0.0.1:{dapr_port}");
+        let opts = ClientOptions::from_env()?.with_address(format!("http://127.0.0.1:{dapr_port}"));
+        Self::from_options(opts).await
+    }
 
-        let cc = Self::connect_with_retry(&dapr_addr).await?;
+    /// Creates a new instance of the Dapr HTTP server whose actors reach the
+    /// sidecar as described by `opts`.
+    ///
+    /// State, reminder and timer calls made through an actor's
+    /// [`ActorContextClient`](crate::server::actor::context_client::ActorContextClient)
+    /// send the same `dapr-api-token` and trace context, and use the same
+    /// address, TLS and connect timeout as a [`crate::Client`] built with
+    /// [`crate::Client::from_options`].
+    ///
+    /// The connection uses exponential-backoff retries in case the sidecar
+    /// is not yet ready.
+    pub async fn from_options(opts: ClientOptions) -> Result<Self, Box<dyn std::error::Error>> {
+        let cc = Self::connect_with_retry(&opts).await?;
         let rt = ActorRuntime::new(cc);
 
         Ok(DaprHttpServer {
@@ -134,21 +169,27 @@ impl DaprHttpServer {
             shutdown_signal: None,
             // Reads `APP_API_TOKEN` from the environment. Permissive if unset.
             app_api_token_layer: AppApiTokenLayer::from_env(),
+            subscriptions: Vec::new(),
+            app_routes: Vec::new(),
         })
     }
 
     /// Connect to the Dapr sidecar with exponential-backoff retries.
     async fn connect_with_retry(
-        dapr_addr: &str,
-    ) -> Result<TonicClient, Box<dyn std::error::Error>> {
+        opts: &ClientOptions,
+    ) -> Result<TonicClientWithAuth, Box<dyn std::error::Error>> {
         const MAX_RETRIES: u32 = 10;
         let mut retry_delay = Duration::from_millis(500);
         let max_delay = Duration::from_secs(2);
 
         let mut last_err = None;
         for attempt in 1..=MAX_RETRIES {
-            match TonicClient::connect(dapr_addr.to_string()).await {
+            match connect_with_options(opts).await {
                 Ok(client) => return Ok(client),
+                // A malformed address or token will not fix itself.
+                Err(e @ (DaprError::InvalidEndpoint(_) | DaprError::InvalidMetadata)) => {
+                    return Err(e.into());
+                }
                 Err(e) => {
                     if attempt < MAX_RETRIES {
                         log::warn!(
@@ -182,6 +223,27 @@ impl DaprHttpServer {
         self
     }
 
+    /// Sets the actor runtime configuration reported to the sidecar. Per
+    /// actor type overrides are set on the [ActorTypeRegistration].
+    pub fn with_actor_config(self, config: ActorRuntimeConfig) -> Self {
+        self.actor_runtime.set_config(config);
+        self
+    }
+
+    /// Enables actor reentrancy for the hosted actor types and advertises it
+    /// to the sidecar. See [`crate::server::actor::reentrancy`].
+    pub fn with_actor_reentrancy(self, config: ReentrancyConfig) -> Self {
+        self.actor_runtime
+            .update_config(|c| c.with_reentrancy(config));
+        self
+    }
+
+    /// The runtime hosting the registered actor types, e.g. to list the
+    /// active actors.
+    pub fn actor_runtime(&self) -> Arc<ActorRuntime> {
+        self.actor_runtime.clone()
+    }
+
     pub fn with_graceful_shutdown<F>(self, signal: F) -> Self
     where
         F: Future<Output = ()> + Send + 'static,
@@ -192,6 +254,62 @@ impl DaprHttpServer {
         }
     }
 
+    /// Subscribes to a topic, delivering its events to `handler` on the
+    /// subscription's route. See [`super::pubsub`].
+    ///
+    /// Routes of the subscription's rules are served with
+    /// [DaprHttpServer::with_topic_route].
+    pub fn with_subscription<H, T>(mut self, subscription: Subscription, handler: H) -> Self
+    where
+        H: Handler<T, ()> + Sync,
+        T: 'static,
+    {
+        let route = subscription.route().to_string();
+        self.subscriptions.push(subscription);
+        self.with_app_route(route, post(handler))
+    }
+
+    /// Serves `handler` on the topic route `route`, e.g. the route of a
+    /// [Subscription::with_rule].
+    pub fn with_topic_route<H, T>(self, route: impl Into<String>, handler: H) -> Self
+    where
+        H: Handler<T, ()> + Sync,
+        T: 'static,
+    {
+        self.with_app_route(route_path(route.into()), post(handler))
+    }
+
+    /// Delivers the events of the input binding `name` to `handler`.
+    ///
+    /// The sidecar posts each event to `/{name}` and treats any success
+    /// status as acknowledged; the route also answers the sidecar's
+    /// `OPTIONS` probe for the binding.
+    pub fn with_input_binding<H, T>(self, name: impl Into<String>, handler: H) -> Self
+    where
+        H: Handler<T, ()> + Sync,
+        T: 'static,
+    {
+        self.with_app_route(
+            route_path(name.into()),
+            post(handler).options(|| async { StatusCode::OK }),
+        )
+    }
+
+    /// Serves `handler` for service invocations of `method`, on any HTTP
+    /// verb, at `/{method}`.
+    pub fn with_service_method<H, T>(self, method: impl Into<String>, handler: H) -> Self
+    where
+        H: Handler<T, ()> + Sync,
+        T: 'static,
+    {
+        self.with_app_route(route_path(method.into()), any(handler))
+    }
+
+    fn with_app_route(mut self, route: String, method_router: MethodRouter) -> Self {
+        self.app_routes.push((route, method_router));
+        self
+    }
+
     /// Registers an actor type with the Dapr runtime.
     ///
     /// # Arguments:
@@ -200,55 +318,133 @@ impl DaprHttpServer {
         self.actor_runtime.register_actor(registration).await;
     }
 
-    /// Starts the Dapr HTTP server.
+    /// Starts the Dapr HTTP server on `127.0.0.1`.
     ///
     /// # Arguments:
-    /// * `port` - The port to listen on. If not specified, the APP_PORT environment variable will be used. If that is not specified, 8080 will be used.
+    /// * `port` - The port to listen on. If not specified, the APP_PORT environment variable will be used. If that is not specified, [DEFAULT_APP_HTTP_PORT] will be used.
+    ///
+    /// Use [DaprHttpServer::start_with_address] to listen on another
+    /// interface, e.g. the pod IP the sidecar connects to.
     pub async fn start(&mut self, port: Option<u16>) -> Result<(), Box<dyn std::error::Error>> {
-        let app = self.build_router().await;
-
-        let default_port: u16 = std::env::var("APP_PORT")
-            .unwrap_or(String::from("8080"))
-            .parse()
-            .unwrap_or(8080);
+        let port = match port {
+            Some(port) => port,
+            None => app_port_from_env(DEFAULT_APP_HTTP_PORT)?,
+        };
+        self.start_with_address(SocketAddr::from(([127, 0, 0, 1], port)))
+            .await
+    }
 
-        let address = format!("127.0.0.1:{}", port.unwrap_or(default_port));
+    /// Starts the Dapr HTTP server on `address`.
+    pub async fn start_with_address(
+        &mut self,
+        address: SocketAddr,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         let listener = TcpListener::bind(address).await?;
+        self.start_with_listener(listener).await
+    }
 
+    /// Starts the Dapr HTTP server on an already bound listener.
+    pub async fn start_with_listener(
+        &mut self,
+        listener: TcpListener,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let app = self.build_router().await?;
         let server = axum::serve(listener, app.into_make_service());
+        let signal = self.shutdown_signal.take();
 
-        let final_result = match self.shutdown_signal.take() {
-            Some(signal) => {
-                server
-                    .with_graceful_shutdown(async move {
-                        signal.await;
-                    })
-                    .await
+        self.run(async move {
+            match signal {
+                Some(signal) => server.with_graceful_shutdown(signal).await,
+                None => server.await,
             }
-            None => server.await,
-        };
+        })
+        .await
+    }
+
+    /// Starts the Dapr HTTP server on a Unix domain socket, for sidecars
+    /// configured with `--app-channel-address` pointing at a socket.
+    #[cfg(unix)]
+    pub async fn start_with_unix_listener(
+        &mut self,
+        listener: UnixListener,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let app = self.build_router().await?;
+        let signal = self.shutdown_signal.take();
+
+        self.run(serve_unix(listener, app, signal)).await
+    }
+
+    /// Builds the actor routes without serving them, to mount into another
+    /// axum application:
+    ///
+    /// ```ignore
+    /// let app = Router::new()
+    ///     .route("/orders", post(create_order))
+    ///     .merge(dapr_server.into_router().await?);
+    /// axum::serve(listener, app).await?;
+    /// ```
+    ///
+    /// Returns [DaprError::DuplicateRoute] if a route was registered twice.
+    /// Idle actors keep being deactivated while the router is alive; call
+    /// [ActorRuntime::deactivate_all] on the [DaprHttpServer::actor_runtime]
+    /// when the application stops. The graceful shutdown signal is unused.
+    pub async fn into_router(mut self) -> Result<Router, DaprError> {
+        let router = self.build_router().await?;
+        // The scanner only holds a weak reference and stops once the
+        // router, and with it the runtime, is dropped.
+        drop(self.actor_runtime.spawn_idle_scanner());
+        Ok(router)
+    }
+
+    /// Runs `server` with the idle scanner, deactivating all actors once it
+    /// returns.
+    async fn run<F>(&mut self, server: F) -> Result<(), Box<dyn std::error::Error>>
+    where
+        F: Future<Output = std::io::Result<()>>,
+    {
+        let idle_scanner = self.actor_runtime.spawn_idle_scanner();
+        let final_result = server.await;
 
+        idle_scanner.abort();
         self.actor_runtime.deactivate_all().await;
 
         Ok(final_result?)
     }
 
+    /// # Panics
+    ///
+    /// If a route was registered twice, see [DaprHttpServer::into_router].
     pub async fn build_test_router(&mut self) -> Router {
-        self.build_router().await
+        self.build_router()
+            .await
+            .unwrap_or_else(|e| panic!("invalid app routes: {e}"))
     }
 
-    async fn build_router(&mut self) -> Router {
+    pub(super) async fn build_router(&mut self) -> Result<Router, DaprError> {
+        // axum panics on overlapping routes, so reject them up front.
+        let mut routes: HashSet<&str> = RESERVED_ROUTES.iter().copied().collect();
+        for (route, _) in &self.app_routes {
+            if !routes.insert(route) {
+                return Err(DaprError::DuplicateRoute(route.clone()));
+            }
+        }
+
         let rt = self.actor_runtime.clone();
 
         // All actor / config endpoints — protected by the APP_API_TOKEN
         // layer when configured. `/healthz` is intentionally excluded so
         // that infrastructure liveness probes don't need to present the
         // token.
-        let protected = Router::new()
+        let subscriptions = Arc::new(self.subscriptions.clone());
+        let mut protected = Router::new()
             .route(
                 "/dapr/config",
                 get(registered_actors).with_state(rt.clone()),
             )
+            .route(
+                "/dapr/subscribe",
+                get(move || async move { Json(subscriptions.as_ref().clone()) }),
+            )
             .route(
                 "/actors/:actor_type/:actor_id",
                 delete(deactivate_actor).with_state(rt.clone()),
@@ -261,18 +457,75 @@ impl DaprHttpServer {
                 "/actors/:actor_type/:actor_id/method/timer/:timer_name",
                 put(invoke_timer).with_state(rt.clone()),
             );
+        for (route, method_router) in &self.app_routes {
+            protected = protected.route(route, method_router.clone());
+        }
 
+        // Layers run outermost-last: the token check happens before the
+        // handler is entered inside the caller's trace context.
         let protected = self
             .actor_runtime
             .configure_method_routes(protected, rt.clone())
             .await
+            .layer(middleware::from_fn(propagate_reentrancy_id))
+            .layer(middleware::from_fn(propagate_trace_context))
             .layer(self.app_api_token_layer.clone());
 
-        Router::new()
+        Ok(Router::new()
             .route("/healthz", get(health_check))
             .merge(protected)
-            .fallback(fallback_handler)
+            .fallback(fallback_handler))
+    }
+}
+
+/// Serves `app` on `listener` until `signal` completes, then waits for open
+/// connections to finish.
+#[cfg(unix)]
+async fn serve_unix(
+    listener: UnixListener,
+    app: Router,
+    signal: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+) -> std::io::Result<()> {
+    use hyper_util::{
+        rt::{TokioExecutor, TokioIo},
+        server::{conn::auto, graceful::GracefulShutdown},
+        service::TowerToHyperService,
+    };
+    use tower::ServiceExt;
+
+    let mut signal = signal.unwrap_or_else(|| futures::future::pending().boxed());
+    let graceful = GracefulShutdown::new();
+    loop {
+        let accepted = tokio::select! {
+            accepted = listener.accept() => accepted,
+            _ = &mut signal => break,
+        };
+        let stream = match accepted {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                // Like `axum::serve`, keep accepting after e.g. running out
+                // of file descriptors.
+                log::error!("Error accepting connection: {e}");
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                continue;
+            }
+        };
+        let service = TowerToHyperService::new(
+            app.clone()
+                .map_request(|req: Request<hyper::body::Incoming>| req.map(Body::new)),
+        );
+        let connection = auto::Builder::new(TokioExecutor::new())
+            .serve_connection(TokioIo::new(stream), service)
+            .into_owned();
+        let connection = graceful.watch(connection);
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                log::debug!("Error serving connection: {e}");
+            }
+        });
     }
+    graceful.shutdown().await;
+    Ok(())
 }
 
 async fn fallback_handler(OriginalUri(uri): OriginalUri) -> impl IntoResponse {
@@ -283,6 +536,31 @@ async fn fallback_handler(OriginalUri(uri): OriginalUri) -> impl IntoResponse {
     )
 }
 
+/// Runs the request inside the trace context sent by the sidecar, so actor
+/// calls back into Dapr continue the caller's trace.
+async fn propagate_trace_context(req: Request, next: Next) -> Response {
+    let trace = TraceContext::from_headers(req.headers());
+    // Name the span after the route template rather than the path, which
+    // carries actor ids and other unbounded values.
+    let method = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| req.uri().path(), MatchedPath::as_str)
+        .to_string();
+    instrument::server_call(&method, trace, next.run(req)).await
+}
+
+/// Runs the request with the reentrancy id sent by the sidecar in scope, so
+/// actor calls made while handling it stay in the same call chain.
+async fn propagate_reentrancy_id(req: Request, next: Next) -> Response {
+    let id = req
+        .headers()
+        .get(REENTRANCY_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    reentrancy::scope(id, next.run(req)).await
+}
+
 async fn health_check() -> impl IntoResponse {
     log::debug!("recieved health check request");
     StatusCode::OK
@@ -291,7 +569,8 @@ async fn health_check() -> impl IntoResponse {
 async fn registered_actors(State(runtime): State<Arc<ActorRuntime>>) -> impl IntoResponse {
     log::debug!("daprd requested registered actors");
     let ra = runtime.list_registered_actors().await;
-    let result = super::models::RegisteredActorsResponse { entities: ra };
+    let type_configs = runtime.list_actor_type_configs().await;
+    let result = super::models::RegisteredActorsResponse::new(ra, &runtime.config(), &type_configs);
 
     Json(result)
 }
@@ -327,7 +606,7 @@ async fn invoke_reminder(
             &actor_type,
             &actor_id,
             &reminder_name,
-            payload.data.unwrap_or_default().into_bytes(),
+            schedule::decode_data(payload.data),
         )
         .await
     {
@@ -352,15 +631,20 @@ async fn invoke_timer(
 ) -> impl IntoResponse {
     log::debug!("invoke_timer: {actor_type} {actor_id} {timer_name}, {payload:?}");
 
-    match runtime
-        .invoke_timer(
-            &actor_type,
-            &actor_id,
-            &timer_name,
-            payload.data.unwrap_or_default().into_bytes(),
-        )
-        .await
-    {
+    let data = schedule::decode_data(payload.data);
+    let result = match payload.callback.filter(|c| !c.is_empty()) {
+        Some(callback) => {
+            runtime
+                .invoke_timer_callback(&actor_type, &actor_id, &callback, data)
+                .await
+        }
+        None => {
+            runtime
+                .invoke_timer(&actor_type, &actor_id, &timer_name, data)
+                .await
+        }
+    };
+    match result {
         Ok(_output) => StatusCode::OK,
         Err(err) => {
             log::error!("invoke_actor: {err:?}");
@@ -377,12 +661,13 @@ async fn invoke_timer(
 
 #[derive(serde::Deserialize, Debug)]
 struct ReminderPayload {
-    data: Option<String>,
+    data: Option<serde_json::Value>,
 }
 
 #[derive(serde::Deserialize, Debug)]
 struct TimerPayload {
-    data: Option<String>,
+    data: Option<serde_json::Value>,
+    callback: Option<String>,
 }
 
 fn dapr_grpc_port_from_env() -> u16 {
@@ -394,6 +679,7 @@ fn dapr_grpc_port_from_env() -> u16 {
 
 #[cfg(test)]
 mod tests {
+    use super::super::pubsub::TopicEventStatus;
     use super::*;
     use std::sync::Mutex;
 
@@ -432,4 +718,245 @@ mod tests {
             assert_eq!(dapr_grpc_port_from_env(), 12345);
         });
     }
+
+    #[tokio::test]
+    async fn handlers_run_in_incoming_trace_context() {
+        use http_body_util::BodyExt;
+        use tower::ServiceExt;
+
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let app = Router::new()
+            .route(
+                "/traced",
+                get(|| async {
+                    TraceContext::current()
+                        .map(|t| t.traceparent())
+                        .unwrap_or_default()
+                }),
+            )
+            .layer(middleware::from_fn(propagate_trace_context));
+
+        let resp = app
+            .oneshot(
+                http::Request::builder()
+                    .uri("/traced")
+                    .header("traceparent", traceparent)
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = resp.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(&body[..], traceparent.as_bytes());
+    }
+
+    async fn fake_sidecar() -> (u16, tokio::task::JoinHandle<()>) {
+        let sidecar = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = sidecar.local_addr().unwrap().port();
+        let handle = tokio::spawn(async move {
+            _ = axum::serve(sidecar, Router::new().into_make_service()).await;
+        });
+        (port, handle)
+    }
+
+    #[tokio::test]
+    async fn into_router_mounts_alongside_other_routes() {
+        use tower::ServiceExt;
+
+        let (dapr_port, sidecar) = fake_sidecar().await;
+        let server = DaprHttpServer::with_dapr_port(dapr_port).await;
+        let app = Router::new()
+            .route("/orders", get(|| async { "orders" }))
+            .merge(server.into_router().await.unwrap());
+
+        for uri in ["/orders", "/dapr/config", "/healthz"] {
+            let resp = app
+                .clone()
+                .oneshot(
+                    http::Request::builder()
+                        .uri(uri)
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(resp.status(), StatusCode::OK, "{uri}");
+        }
+
+        sidecar.abort();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn serves_on_a_unix_socket() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let (dapr_port, sidecar) = fake_sidecar().await;
+        let path = std::env::temp_dir().join(format!("dapr-app-{}.sock", uuid::Uuid::new_v4()));
+        let listener = UnixListener::bind(&path).unwrap();
+        let (shutdown, signal) = tokio::sync::oneshot::channel::<()>();
+        let mut server = DaprHttpServer::with_dapr_port(dapr_port)
+            .await
+            .with_graceful_shutdown(async {
+                _ = signal.await;
+            });
+        let serving =
+            tokio::spawn(async move { server.start_with_unix_listener(listener).await.is_ok() });
+
+        let mut stream = tokio::net::UnixStream::connect(&path).await.unwrap();
+        stream
+            .write_all(b"GET /dapr/config HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.contains("\"entities\""), "{response}");
+
+        shutdown.send(()).unwrap();
+        assert!(serving.await.unwrap());
+        _ = std::fs::remove_file(&path);
+        sidecar.abort();
+    }
+
+    #[tokio::test]
+    async fn rejects_duplicate_routes() {
+        let (dapr_port, sidecar) = fake_sidecar().await;
+        let server = DaprHttpServer::with_dapr_port(dapr_port)
+            .await
+            .with_service_method("orders", || async { "orders" })
+            .with_input_binding("orders", |body: String| async move { body });
+        assert!(matches!(
+            server.into_router().await,
+            Err(DaprError::DuplicateRoute(route)) if route == "/orders"
+        ));
+
+        let server = DaprHttpServer::with_dapr_port(dapr_port)
+            .await
+            .with_service_method("dapr/config", || async { "config" });
+        assert!(matches!(
+            server.into_router().await,
+            Err(DaprError::DuplicateRoute(route)) if route == "/dapr/config"
+        ));
+
+        let server = DaprHttpServer::with_dapr_port(dapr_port)
+            .await
+            .with_subscription(Subscription::new("pubsub", "orders", "orders"), || async {
+                TopicEventStatus::Success
+            })
+            .with_topic_route("/orders", || async { TopicEventStatus::Drop });
+        assert!(matches!(
+            server.into_router().await,
+            Err(DaprError::DuplicateRoute(route)) if route == "/orders"
+        ));
+
+        sidecar.abort();
+    }
+
+    #[tokio::test]
+    async fn serves_subscriptions_bindings_and_service_methods() {
+        use super::super::pubsub::CloudEvent;
+        use http_body_util::BodyExt;
+        use serde_json::{Value, json};
+        use tower::ServiceExt;
+
+        let orders = Arc::new(Mutex::new(Vec::new()));
+        let on_order = {
+            let orders = orders.clone();
+            move |event: CloudEvent<Value>| async move {
+                let status = match event.data["n"].as_u64() {
+                    Some(_) => TopicEventStatus::Success,
+                    None => TopicEventStatus::Retry,
+                };
+                orders.lock().unwrap().push(event.data);
+                status
+            }
+        };
+        let ticks = Arc::new(Mutex::new(Vec::new()));
+        let on_tick = {
+            let ticks = ticks.clone();
+            move |body: String| async move {
+                ticks.lock().unwrap().push(body);
+            }
+        };
+
+        let (dapr_port, sidecar) = fake_sidecar().await;
+        let server = DaprHttpServer::with_dapr_port(dapr_port)
+            .await
+            .with_subscription(
+                Subscription::new("pubsub", "orders", "orders")
+                    .with_rule("event.type == \"refund\"", "refunds")
+                    .with_dead_letter_topic("poison"),
+                on_order,
+            )
+            .with_topic_route("refunds", || async { TopicEventStatus::Drop })
+            .with_input_binding("cron", on_tick)
+            .with_service_method("hello", || async { "hi" });
+        let app = server.into_router().await.unwrap();
+
+        let call = |method: &str, uri: &str, body: String| {
+            app.clone().oneshot(
+                http::Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/cloudevents+json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+        };
+        let json_body = |resp: Response| async move {
+            let bytes = resp.into_body().collect().await.unwrap().to_bytes();
+            serde_json::from_slice::<Value>(&bytes).unwrap()
+        };
+
+        let resp = call("GET", "/dapr/subscribe", String::new()).await.unwrap();
+        assert_eq!(
+            json_body(resp).await,
+            json!([{
+                "pubsubname": "pubsub",
+                "topic": "orders",
+                "routes": {
+                    "rules": [{ "match": "event.type == \"refund\"", "path": "/refunds" }],
+                    "default": "/orders",
+                },
+                "deadLetterTopic": "poison",
+            }])
+        );
+
+        let event = json!({
+            "id": "1",
+            "source": "checkout",
+            "type": "com.dapr.event.sent",
+            "specversion": "1.0",
+            "data": { "n": 1 },
+        });
+        let resp = call("POST", "/orders", event.to_string()).await.unwrap();
+        assert_eq!(json_body(resp).await, json!({ "status": "SUCCESS" }));
+        let mut unprocessable = event.clone();
+        unprocessable["data"] = json!({});
+        let resp = call("POST", "/orders", unprocessable.to_string())
+            .await
+            .unwrap();
+        assert_eq!(json_body(resp).await, json!({ "status": "RETRY" }));
+        // Undecodable events never reach the handler.
+        let resp = call("POST", "/orders", "not json".to_string())
+            .await
+            .unwrap();
+        assert_eq!(json_body(resp).await, json!({ "status": "DROP" }));
+        assert_eq!(*orders.lock().unwrap(), [json!({ "n": 1 }), json!({})]);
+        let resp = call("POST", "/refunds", event.to_string()).await.unwrap();
+        assert_eq!(json_body(resp).await, json!({ "status": "DROP" }));
+
+        let resp = call("OPTIONS", "/cron", String::new()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = call("POST", "/cron", "tick".to_string()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(*ticks.lock().unwrap(), ["tick"]);
+
+        let resp = call("GET", "/hello", String::new()).await.unwrap();
+        let body = resp.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(&body[..], b"hi");
+
+        sidecar.abort();
+    }
 }
@@ -1,8 +1,11 @@
-pub use http::DaprHttpServer;
+pub use grpc::{APP_PORT_ENV, DEFAULT_APP_GRPC_PORT, DaprGrpcServer};
+pub use http::{DEFAULT_APP_HTTP_PORT, DaprHttpServer};
 
 #[macro_use]
 pub mod actor;
 pub mod appcallbackalpha;
+mod grpc;
 mod http;
 mod models;
+pub mod pubsub;
 pub mod utils;
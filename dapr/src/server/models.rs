@@ -1,6 +1,123 @@
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug)]
+use super::actor::{
+    reentrancy::ReentrancyConfig,
+    runtime::{ActorRuntimeConfig, ActorTypeConfig},
+};
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct RegisteredActorsResponse {
     pub entities: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor_idle_timeout: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor_scan_interval: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drain_ongoing_call_timeout: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drain_rebalanced_actors: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reentrancy: Option<ReentrancyConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reminders_storage_partitions: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entities_config: Vec<EntityConfig>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityConfig {
+    pub entities: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor_idle_timeout: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drain_ongoing_call_timeout: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drain_rebalanced_actors: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reentrancy: Option<ReentrancyConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reminders_storage_partitions: Option<u32>,
+}
+
+impl RegisteredActorsResponse {
+    pub fn new(
+        entities: Vec<String>,
+        config: &ActorRuntimeConfig,
+        type_configs: &[(String, ActorTypeConfig)],
+    ) -> Self {
+        RegisteredActorsResponse {
+            entities,
+            actor_idle_timeout: config.idle_timeout().map(go_duration),
+            actor_scan_interval: config.scan_interval().map(go_duration),
+            drain_ongoing_call_timeout: config.drain_ongoing_call_timeout().map(go_duration),
+            drain_rebalanced_actors: config.drain_rebalanced_actors(),
+            reentrancy: config.reentrancy().cloned(),
+            reminders_storage_partitions: config.reminders_storage_partitions(),
+            entities_config: type_configs
+                .iter()
+                .map(|(name, c)| EntityConfig {
+                    entities: vec![name.clone()],
+                    actor_idle_timeout: c.idle_timeout().map(go_duration),
+                    drain_ongoing_call_timeout: c.drain_ongoing_call_timeout().map(go_duration),
+                    drain_rebalanced_actors: c.drain_rebalanced_actors(),
+                    reentrancy: c.reentrancy().cloned(),
+                    reminders_storage_partitions: c.reminders_storage_partitions(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Formats `d` the way Go's `time.ParseDuration`, used by the sidecar,
+/// expects it.
+fn go_duration(d: Duration) -> String {
+    if d.subsec_nanos() == 0 {
+        format!("{}s", d.as_secs())
+    } else if d.subsec_nanos().is_multiple_of(1_000_000) {
+        format!("{}ms", d.as_millis())
+    } else {
+        format!("{}ns", d.as_nanos())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_runtime_and_type_config() {
+        let config = ActorRuntimeConfig::new()
+            .with_idle_timeout(Duration::from_secs(3600))
+            .with_scan_interval(Duration::from_millis(1500))
+            .with_drain_rebalanced_actors(true)
+            .with_reminders_storage_partitions(7);
+        let type_configs = vec![(
+            "MyActor".to_string(),
+            ActorTypeConfig::new()
+                .with_idle_timeout(Duration::from_secs(60))
+                .with_reentrancy(ReentrancyConfig::enabled()),
+        )];
+        let response =
+            RegisteredActorsResponse::new(vec!["MyActor".to_string()], &config, &type_configs);
+
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            serde_json::json!({
+                "entities": ["MyActor"],
+                "actorIdleTimeout": "3600s",
+                "actorScanInterval": "1500ms",
+                "drainRebalancedActors": true,
+                "remindersStoragePartitions": 7,
+                "entitiesConfig": [{
+                    "entities": ["MyActor"],
+                    "actorIdleTimeout": "60s",
+                    "reentrancy": { "enabled": true },
+                }],
+            })
+        );
+    }
 }
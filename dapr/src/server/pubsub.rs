@@ -0,0 +1,312 @@
+//! Pub/sub over the HTTP app channel (`--app-protocol http`).
+//!
+//! Subscriptions registered with [`crate::server::DaprHttpServer::with_subscription`]
+//! are reported to the sidecar on `GET /dapr/subscribe`, which then posts
+//! each event to the subscription's route as a [`CloudEvent`]. Handlers
+//! answer with a [`TopicEventStatus`]:
+//!
+//! ```ignore
+//! async fn on_order(CloudEvent { data, .. }: CloudEvent<Order>) -> TopicEventStatus {
+//!     match process(data).await {
+//!         Ok(()) => TopicEventStatus::Success,
+//!         Err(_) => TopicEventStatus::Retry,
+//!     }
+//! }
+//!
+//! let server = DaprHttpServer::new()
+//!     .await
+//!     .with_subscription(
+//!         Subscription::new("pubsub", "orders", "/orders").with_dead_letter_topic("poison"),
+//!         on_order,
+//!     );
+//! ```
+
+#![warn(missing_docs)]
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use axum::{
+    body::{Body, Bytes},
+    extract::FromRequest,
+    http::Request,
+    response::IntoResponse,
+};
+use base64::Engine;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+/// A topic subscription, as reported on `GET /dapr/subscribe`.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Subscription {
+    #[serde(rename = "pubsubname")]
+    pubsub_name: String,
+    topic: String,
+    routes: Routes,
+    #[serde(rename = "deadLetterTopic", skip_serializing_if = "Option::is_none")]
+    dead_letter_topic: Option<String>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    metadata: HashMap<String, String>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+struct Routes {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    rules: Vec<Rule>,
+    default: String,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+struct Rule {
+    #[serde(rename = "match")]
+    expression: String,
+    path: String,
+}
+
+impl Subscription {
+    /// Subscribes to `topic` of the pub/sub component `pubsub_name`,
+    /// delivering events to `route`.
+    pub fn new(
+        pubsub_name: impl Into<String>,
+        topic: impl Into<String>,
+        route: impl Into<String>,
+    ) -> Self {
+        Subscription {
+            pubsub_name: pubsub_name.into(),
+            topic: topic.into(),
+            routes: Routes {
+                rules: Vec::new(),
+                default: route_path(route.into()),
+            },
+            dead_letter_topic: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Delivers events matching the CEL `expression`, e.g.
+    /// `event.type == "order.refunded"`, to `route` instead. Rules are
+    /// evaluated in the order they are added. Register the route's handler
+    /// with [`crate::server::DaprHttpServer::with_topic_route`].
+    pub fn with_rule(mut self, expression: impl Into<String>, route: impl Into<String>) -> Self {
+        self.routes.rules.push(Rule {
+            expression: expression.into(),
+            path: route_path(route.into()),
+        });
+        self
+    }
+
+    /// Publishes events the app drops, or fails to process after all
+    /// retries, to `topic`.
+    pub fn with_dead_letter_topic(mut self, topic: impl Into<String>) -> Self {
+        self.dead_letter_topic = Some(topic.into());
+        self
+    }
+
+    /// Adds subscription metadata, e.g. `rawPayload`.
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// The name of the pub/sub component.
+    pub fn pubsub_name(&self) -> &str {
+        &self.pubsub_name
+    }
+
+    /// The subscribed topic.
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// The route events are delivered to unless a rule matches.
+    pub fn route(&self) -> &str {
+        &self.routes.default
+    }
+}
+
+/// `route` with a leading `/`.
+pub(crate) fn route_path(route: String) -> String {
+    if route.starts_with('/') {
+        route
+    } else {
+        format!("/{route}")
+    }
+}
+
+/// An event delivered to a topic route, decoded from its CloudEvents 1.0
+/// envelope.
+///
+/// `data` is read from the event's `data`, or from `data_base64` for binary
+/// payloads, as JSON. An event that cannot be decoded is answered with
+/// [`TopicEventStatus::Drop`], so that it goes to the dead-letter topic
+/// instead of being redelivered.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct CloudEvent<T> {
+    /// The event id.
+    pub id: String,
+    /// The app that published the event.
+    pub source: String,
+    /// The event type.
+    #[serde(rename = "type")]
+    pub event_type: String,
+    /// The CloudEvents version.
+    #[serde(rename = "specversion")]
+    pub spec_version: String,
+    /// The content type of `data`.
+    #[serde(rename = "datacontenttype", default)]
+    pub data_content_type: Option<String>,
+    /// The event subject.
+    #[serde(default)]
+    pub subject: Option<String>,
+    /// The topic the event was published to.
+    #[serde(default)]
+    pub topic: Option<String>,
+    /// The pub/sub component the event was published to.
+    #[serde(rename = "pubsubname", default)]
+    pub pubsub_name: Option<String>,
+    /// The trace the event was published in.
+    #[serde(default)]
+    pub traceparent: Option<String>,
+    /// The event payload.
+    pub data: T,
+}
+
+/// A CloudEvent without its `data`, which is decoded separately.
+#[derive(Deserialize)]
+struct Envelope {
+    #[serde(default)]
+    data: Option<serde_json::Value>,
+    #[serde(default)]
+    data_base64: Option<String>,
+    #[serde(flatten)]
+    attributes: serde_json::Map<String, serde_json::Value>,
+}
+
+impl<T: DeserializeOwned> CloudEvent<T> {
+    /// Decodes a CloudEvent from its JSON encoding.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, String> {
+        let envelope: Envelope = serde_json::from_slice(bytes).map_err(|e| e.to_string())?;
+        let data = match (envelope.data, envelope.data_base64) {
+            (Some(data), _) => data,
+            (None, Some(encoded)) => {
+                let bytes = base64::engine::general_purpose::STANDARD
+                    .decode(encoded)
+                    .map_err(|e| e.to_string())?;
+                serde_json::from_slice(&bytes).map_err(|e| e.to_string())?
+            }
+            (None, None) => serde_json::Value::Null,
+        };
+        let mut attributes = envelope.attributes;
+        attributes.insert("data".to_string(), data);
+        serde_json::from_value(serde_json::Value::Object(attributes)).map_err(|e| e.to_string())
+    }
+}
+
+#[async_trait]
+impl<T, S> FromRequest<S> for CloudEvent<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = CloudEventRejection;
+
+    async fn from_request(req: Request<Body>, state: &S) -> Result<Self, Self::Rejection> {
+        let bytes = Bytes::from_request(req, state)
+            .await
+            .map_err(|e| CloudEventRejection(e.to_string()))?;
+        CloudEvent::from_slice(&bytes).map_err(CloudEventRejection)
+    }
+}
+
+/// The rejection of an undecodable [`CloudEvent`], answered with
+/// [`TopicEventStatus::Drop`].
+#[derive(Debug)]
+pub struct CloudEventRejection(pub String);
+
+impl IntoResponse for CloudEventRejection {
+    fn into_response(self) -> axum::response::Response {
+        log::error!("Dropping undecodable event: {}", self.0);
+        TopicEventStatus::Drop.into_response()
+    }
+}
+
+/// How the sidecar proceeds with an event after a topic handler returns.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TopicEventStatus {
+    /// The event was processed.
+    Success,
+    /// The event is redelivered later.
+    Retry,
+    /// The event is discarded, or sent to the dead-letter topic if one is
+    /// configured.
+    Drop,
+}
+
+#[derive(Serialize)]
+struct TopicEventResponse {
+    status: TopicEventStatus,
+}
+
+impl IntoResponse for TopicEventStatus {
+    fn into_response(self) -> axum::response::Response {
+        axum::Json(TopicEventResponse { status: self }).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn serializes_programmatic_subscription() {
+        let subscription = Subscription::new("pubsub", "orders", "orders")
+            .with_rule("event.type == \"refund\"", "/refunds")
+            .with_dead_letter_topic("poison")
+            .with_metadata("rawPayload", "false");
+        assert_eq!(
+            serde_json::to_value(&subscription).unwrap(),
+            json!({
+                "pubsubname": "pubsub",
+                "topic": "orders",
+                "routes": {
+                    "rules": [{ "match": "event.type == \"refund\"", "path": "/refunds" }],
+                    "default": "/orders",
+                },
+                "deadLetterTopic": "poison",
+                "metadata": { "rawPayload": "false" },
+            })
+        );
+    }
+
+    #[test]
+    fn decodes_cloud_events() {
+        let event = json!({
+            "id": "1",
+            "source": "checkout",
+            "type": "com.dapr.event.sent",
+            "specversion": "1.0",
+            "datacontenttype": "application/json",
+            "topic": "orders",
+            "pubsubname": "pubsub",
+            "data": { "n": 1 },
+        });
+        let decoded =
+            CloudEvent::<serde_json::Value>::from_slice(event.to_string().as_bytes()).unwrap();
+        assert_eq!(decoded.topic.as_deref(), Some("orders"));
+        assert_eq!(decoded.data, json!({ "n": 1 }));
+
+        let event = json!({
+            "id": "2",
+            "source": "checkout",
+            "type": "com.dapr.event.sent",
+            "specversion": "1.0",
+            "data_base64": "eyJuIjoyfQ==",
+        });
+        let decoded =
+            CloudEvent::<serde_json::Value>::from_slice(event.to_string().as_bytes()).unwrap();
+        assert_eq!(decoded.data, json!({ "n": 2 }));
+
+        assert!(CloudEvent::<u32>::from_slice(br#"{"id":"3"}"#).is_err());
+    }
+}
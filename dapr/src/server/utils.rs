@@ -2,18 +2,24 @@ use async_trait::async_trait;
 use axum::{
     body::Body,
     extract::FromRequest,
-    http::{Request, StatusCode},
+    http::{HeaderMap, Request, StatusCode, header::CONTENT_TYPE},
     response::IntoResponse,
 };
 use serde::de::DeserializeOwned;
 
-/// Workaround for Dapr's JSON serialization not correcly setting Content-Type header
+use crate::client::codec::{Codec, Json, Protobuf, Raw};
 
+/// Workaround for Dapr's JSON serialization not correcly setting Content-Type header
+///
+/// A body without a content type, or with one that is not another known
+/// encoding, is read as JSON. Bodies sent as [`Protobuf`] or [`Raw`] are
+/// rejected with `415 Unsupported Media Type`.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct DaprJson<T>(pub T);
 
 pub enum JsonRejection {
     JsonError(String),
+    UnsupportedContentType(String),
 }
 
 #[async_trait]
@@ -25,6 +31,13 @@ where
     type Rejection = JsonRejection;
 
     async fn from_request(req: Request<Body>, state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(content_type) = content_type(req.headers())
+            && !Json::matches(&content_type)
+            && (Protobuf::matches(&content_type) || Raw::matches(&content_type))
+        {
+            log::error!("Expected a JSON body, got {content_type}");
+            return Err(JsonRejection::UnsupportedContentType(content_type));
+        }
         let bytes = match axum::body::Bytes::from_request(req, state).await {
             Ok(bytes) => bytes,
             Err(e) => {
@@ -48,6 +61,89 @@ impl IntoResponse for JsonRejection {
     fn into_response(self) -> axum::response::Response {
         match self {
             JsonRejection::JsonError(e) => (StatusCode::BAD_REQUEST, axum::Json(e)).into_response(),
+            JsonRejection::UnsupportedContentType(content_type) => (
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                axum::Json(format!("expected a JSON body, got {content_type}")),
+            )
+                .into_response(),
         }
     }
 }
+
+/// A protobuf message body, decoded with `prost`.
+///
+/// Extracts a body sent without a content type or as [`Protobuf`], e.g. by
+/// [`crate::Client::invoke_actor_with_codec`], and responds with the encoded
+/// message as `application/x-protobuf`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DaprProto<T>(pub T);
+
+pub enum ProtoRejection {
+    DecodeError(String),
+    UnsupportedContentType(String),
+}
+
+#[async_trait]
+impl<T, S> FromRequest<S> for DaprProto<T>
+where
+    T: prost::Message + Default,
+    S: Send + Sync,
+{
+    type Rejection = ProtoRejection;
+
+    async fn from_request(req: Request<Body>, state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(content_type) = content_type(req.headers())
+            && !Protobuf::matches(&content_type)
+        {
+            log::error!("Expected a protobuf body, got {content_type}");
+            return Err(ProtoRejection::UnsupportedContentType(content_type));
+        }
+        let bytes = match axum::body::Bytes::from_request(req, state).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                log::error!("Error getting bytes: {e}");
+                return Err(ProtoRejection::DecodeError(e.to_string()));
+            }
+        };
+        match T::decode(bytes) {
+            Ok(value) => Ok(DaprProto(value)),
+            Err(e) => {
+                log::error!("Error decoding protobuf: {e}");
+                Err(ProtoRejection::DecodeError(e.to_string()))
+            }
+        }
+    }
+}
+
+impl<T: prost::Message> IntoResponse for DaprProto<T> {
+    fn into_response(self) -> axum::response::Response {
+        (
+            [(CONTENT_TYPE, Protobuf::CONTENT_TYPE)],
+            self.0.encode_to_vec(),
+        )
+            .into_response()
+    }
+}
+
+impl IntoResponse for ProtoRejection {
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            ProtoRejection::DecodeError(e) => {
+                (StatusCode::BAD_REQUEST, axum::Json(e)).into_response()
+            }
+            ProtoRejection::UnsupportedContentType(content_type) => (
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                axum::Json(format!("expected a protobuf body, got {content_type}")),
+            )
+                .into_response(),
+        }
+    }
+}
+
+fn content_type(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
@@ -0,0 +1,882 @@
+//! An in-memory Dapr sidecar for tests, enabled by the `testing` feature.
+//!
+//! [`MockSidecar`] serves the Dapr gRPC API in-process over in-memory
+//! connections, so code using a [`Client`](crate::Client) can be tested
+//! without `daprd` or Docker. It keeps state, published events, secrets,
+//! configuration and locks in memory; other APIs answer
+//! [`tonic::Code::Unimplemented`].
+//!
+//! ```ignore
+//! let sidecar = MockSidecar::new();
+//! sidecar.set_secret("vault", "db", [("password", "hunter2")]);
+//!
+//! let mut client = dapr::Client::for_testing(&sidecar);
+//! client.save_state("store", "order-1", b"{}".to_vec(), None, None, None).await?;
+//! client.publish_event("pubsub", "orders", "application/json", b"{}".to_vec(), None).await?;
+//!
+//! assert_eq!(sidecar.state("store", "order-1").as_deref(), Some(&b"{}"[..]));
+//! assert_eq!(sidecar.published("pubsub", "orders").len(), 1);
+//! ```
+
+#![warn(missing_docs)]
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex as SyncMutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use futures::stream::{BoxStream, StreamExt};
+use hyper_util::rt::TokioIo;
+use tokio::sync::mpsc;
+use tonic::{
+    Request, Response, Status,
+    transport::{Channel, Endpoint, Server},
+};
+
+use crate::dapr::proto::common::v1 as common_v1;
+use crate::dapr::proto::runtime::v1 as dapr_v1;
+use dapr_v1::dapr_server::{Dapr, DaprServer};
+
+/// The buffer size of each in-memory connection to the sidecar.
+const CONNECTION_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// An event published through a [`MockSidecar`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublishedEvent {
+    /// The pub/sub component the event was published to.
+    pub pubsub_name: String,
+    /// The topic the event was published to.
+    pub topic: String,
+    /// The event payload.
+    pub data: Vec<u8>,
+    /// The content type of `data`.
+    pub data_content_type: String,
+    /// The metadata the event was published with.
+    pub metadata: HashMap<String, String>,
+}
+
+/// An in-process Dapr sidecar backed by memory, see the
+/// [module docs](self).
+///
+/// Clones share the same data. Any store or component name is accepted.
+/// Writes with an etag fail with [`tonic::Code::Aborted`] unless it matches
+/// the stored one, like with a real state store.
+#[derive(Clone, Default)]
+pub struct MockSidecar {
+    data: Arc<SyncMutex<Data>>,
+}
+
+#[derive(Default)]
+struct Data {
+    state: HashMap<(String, String), StateValue>,
+    etags: u64,
+    published: Vec<PublishedEvent>,
+    secrets: HashMap<String, HashMap<String, HashMap<String, String>>>,
+    configuration: HashMap<String, HashMap<String, common_v1::ConfigurationItem>>,
+    configuration_versions: u64,
+    subscriptions: HashMap<String, ConfigurationSubscription>,
+    subscription_ids: u64,
+    locks: HashMap<(String, String), Lock>,
+    metadata: HashMap<String, String>,
+}
+
+struct StateValue {
+    data: Vec<u8>,
+    etag: String,
+}
+
+struct ConfigurationSubscription {
+    store_name: String,
+    keys: Vec<String>,
+    sender: mpsc::UnboundedSender<Result<dapr_v1::SubscribeConfigurationResponse, Status>>,
+}
+
+struct Lock {
+    owner: String,
+    expires_at: Instant,
+}
+
+type ConfigurationStream =
+    BoxStream<'static, Result<dapr_v1::SubscribeConfigurationResponse, Status>>;
+
+impl MockSidecar {
+    /// A sidecar without any data.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A channel to this sidecar, e.g. for a raw
+    /// [`DaprClient`](dapr_v1::dapr_client::DaprClient). Each connection
+    /// is served in-memory by a task on the current tokio runtime.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn channel(&self) -> Channel {
+        let sidecar = self.clone();
+        Endpoint::from_static("http://mock-sidecar").connect_with_connector_lazy(tower::service_fn(
+            move |_: http::Uri| {
+                let sidecar = sidecar.clone();
+                async move {
+                    let (client, server) = tokio::io::duplex(CONNECTION_BUFFER_SIZE);
+                    tokio::spawn(async move {
+                        let incoming = futures::stream::iter([Ok::<_, std::io::Error>(server)]);
+                        if let Err(e) = Server::builder()
+                            .add_service(DaprServer::new(sidecar))
+                            .serve_with_incoming(incoming)
+                            .await
+                        {
+                            log::error!("mock sidecar connection failed: {e}");
+                        }
+                    });
+                    Ok::<_, std::io::Error>(TokioIo::new(client))
+                }
+            },
+        ))
+    }
+
+    /// The value stored under `key` in the state store `store_name`.
+    pub fn state(&self, store_name: &str, key: &str) -> Option<Vec<u8>> {
+        self.lock()
+            .state
+            .get(&(store_name.to_string(), key.to_string()))
+            .map(|value| value.data.clone())
+    }
+
+    /// Stores `value` under `key` in the state store `store_name`.
+    pub fn set_state(&self, store_name: &str, key: &str, value: impl Into<Vec<u8>>) {
+        let mut data = self.lock();
+        let etag = data.next_etag();
+        data.state.insert(
+            (store_name.to_string(), key.to_string()),
+            StateValue {
+                data: value.into(),
+                etag,
+            },
+        );
+    }
+
+    /// The events published to `topic` of the pub/sub component
+    /// `pubsub_name`, oldest first.
+    pub fn published(&self, pubsub_name: &str, topic: &str) -> Vec<PublishedEvent> {
+        self.lock()
+            .published
+            .iter()
+            .filter(|event| event.pubsub_name == pubsub_name && event.topic == topic)
+            .cloned()
+            .collect()
+    }
+
+    /// Stores the secret `key` in the secret store `store_name`.
+    pub fn set_secret<K, V>(
+        &self,
+        store_name: &str,
+        key: &str,
+        values: impl IntoIterator<Item = (K, V)>,
+    ) where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let values = values
+            .into_iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect();
+        self.lock()
+            .secrets
+            .entry(store_name.to_string())
+            .or_default()
+            .insert(key.to_string(), values);
+    }
+
+    /// Sets the configuration item `key` in the configuration store
+    /// `store_name`, notifying its subscribers.
+    pub fn set_configuration(&self, store_name: &str, key: &str, value: &str) {
+        let mut data = self.lock();
+        data.configuration_versions += 1;
+        let item = common_v1::ConfigurationItem {
+            value: value.to_string(),
+            version: data.configuration_versions.to_string(),
+            metadata: HashMap::new(),
+        };
+        data.configuration
+            .entry(store_name.to_string())
+            .or_default()
+            .insert(key.to_string(), item.clone());
+
+        // Subscribers whose stream was dropped are forgotten.
+        data.subscriptions.retain(|id, subscription| {
+            if subscription.store_name != store_name
+                || !(subscription.keys.is_empty() || subscription.keys.iter().any(|k| k == key))
+            {
+                return true;
+            }
+            let update = dapr_v1::SubscribeConfigurationResponse {
+                id: id.clone(),
+                items: HashMap::from([(key.to_string(), item.clone())]),
+            };
+            subscription.sender.send(Ok(update)).is_ok()
+        });
+    }
+
+    /// The owner of the unexpired lock on `resource_id` in the lock store
+    /// `store_name`.
+    pub fn lock_owner(&self, store_name: &str, resource_id: &str) -> Option<String> {
+        self.lock()
+            .locks
+            .get(&(store_name.to_string(), resource_id.to_string()))
+            .filter(|lock| lock.expires_at > Instant::now())
+            .map(|lock| lock.owner.clone())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Data> {
+        self.data.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Data {
+    fn next_etag(&mut self) -> String {
+        self.etags += 1;
+        self.etags.to_string()
+    }
+
+    /// Fails unless `etag` is unset or matches the one stored under `key`.
+    fn check_etag(
+        &self,
+        key: &(String, String),
+        etag: Option<&common_v1::Etag>,
+    ) -> Result<(), Status> {
+        let Some(etag) = etag.filter(|etag| !etag.value.is_empty()) else {
+            return Ok(());
+        };
+        match self.state.get(key) {
+            Some(value) if value.etag == etag.value => Ok(()),
+            _ => Err(Status::aborted(format!(
+                "possible etag mismatch for key {}",
+                key.1
+            ))),
+        }
+    }
+
+    fn get_state(
+        &mut self,
+        request: dapr_v1::GetStateRequest,
+    ) -> Result<dapr_v1::GetStateResponse, Status> {
+        let value = self.state.get(&(request.store_name, request.key));
+        Ok(dapr_v1::GetStateResponse {
+            data: value.map(|v| v.data.clone()).unwrap_or_default(),
+            etag: value.map(|v| v.etag.clone()).unwrap_or_default(),
+            metadata: HashMap::new(),
+        })
+    }
+
+    fn get_bulk_state(
+        &mut self,
+        request: dapr_v1::GetBulkStateRequest,
+    ) -> Result<dapr_v1::GetBulkStateResponse, Status> {
+        let items = request
+            .keys
+            .into_iter()
+            .map(|key| {
+                let value = self.state.get(&(request.store_name.clone(), key.clone()));
+                dapr_v1::BulkStateItem {
+                    key,
+                    data: value.map(|v| v.data.clone()).unwrap_or_default(),
+                    etag: value.map(|v| v.etag.clone()).unwrap_or_default(),
+                    error: String::new(),
+                    metadata: HashMap::new(),
+                }
+            })
+            .collect();
+        Ok(dapr_v1::GetBulkStateResponse { items })
+    }
+
+    fn save_state(&mut self, request: dapr_v1::SaveStateRequest) -> Result<(), Status> {
+        let operations = request
+            .states
+            .into_iter()
+            .map(|item| ("upsert".to_string(), item))
+            .collect();
+        self.apply(&request.store_name, operations)
+    }
+
+    fn delete_state(&mut self, request: dapr_v1::DeleteStateRequest) -> Result<(), Status> {
+        let item = common_v1::StateItem {
+            key: request.key,
+            etag: request.etag,
+            ..Default::default()
+        };
+        self.apply(&request.store_name, vec![("delete".to_string(), item)])
+    }
+
+    fn delete_bulk_state(
+        &mut self,
+        request: dapr_v1::DeleteBulkStateRequest,
+    ) -> Result<(), Status> {
+        let operations = request
+            .states
+            .into_iter()
+            .map(|item| ("delete".to_string(), item))
+            .collect();
+        self.apply(&request.store_name, operations)
+    }
+
+    fn execute_state_transaction(
+        &mut self,
+        request: dapr_v1::ExecuteStateTransactionRequest,
+    ) -> Result<(), Status> {
+        let operations = request
+            .operations
+            .into_iter()
+            .map(|operation| {
+                (
+                    operation.operation_type,
+                    operation.request.unwrap_or_default(),
+                )
+            })
+            .collect();
+        self.apply(&request.store_name, operations)
+    }
+
+    /// Applies upserts and deletes all at once, or none of them if an etag
+    /// does not match.
+    fn apply(
+        &mut self,
+        store_name: &str,
+        operations: Vec<(String, common_v1::StateItem)>,
+    ) -> Result<(), Status> {
+        for (operation_type, item) in &operations {
+            if operation_type != "upsert" && operation_type != "delete" {
+                return Err(Status::invalid_argument(format!(
+                    "unsupported operation type {operation_type}"
+                )));
+            }
+            self.check_etag(
+                &(store_name.to_string(), item.key.clone()),
+                item.etag.as_ref(),
+            )?;
+        }
+        for (operation_type, item) in operations {
+            let key = (store_name.to_string(), item.key);
+            if operation_type == "upsert" {
+                let etag = self.next_etag();
+                self.state.insert(
+                    key,
+                    StateValue {
+                        data: item.value,
+                        etag,
+                    },
+                );
+            } else {
+                self.state.remove(&key);
+            }
+        }
+        Ok(())
+    }
+
+    fn publish_event(&mut self, request: dapr_v1::PublishEventRequest) -> Result<(), Status> {
+        self.published.push(PublishedEvent {
+            pubsub_name: request.pubsub_name,
+            topic: request.topic,
+            data: request.data,
+            data_content_type: request.data_content_type,
+            metadata: request.metadata,
+        });
+        Ok(())
+    }
+
+    fn bulk_publish_event(
+        &mut self,
+        request: dapr_v1::BulkPublishRequest,
+    ) -> Result<dapr_v1::BulkPublishResponse, Status> {
+        for entry in request.entries {
+            let mut metadata = request.metadata.clone();
+            metadata.extend(entry.metadata);
+            self.published.push(PublishedEvent {
+                pubsub_name: request.pubsub_name.clone(),
+                topic: request.topic.clone(),
+                data: entry.event,
+                data_content_type: entry.content_type,
+                metadata,
+            });
+        }
+        Ok(dapr_v1::BulkPublishResponse {
+            failed_entries: Vec::new(),
+        })
+    }
+
+    fn get_secret(
+        &mut self,
+        request: dapr_v1::GetSecretRequest,
+    ) -> Result<dapr_v1::GetSecretResponse, Status> {
+        let secret = self
+            .secrets
+            .get(&request.store_name)
+            .and_then(|secrets| secrets.get(&request.key))
+            .ok_or_else(|| Status::not_found(format!("secret {} not found", request.key)))?;
+        Ok(dapr_v1::GetSecretResponse {
+            data: secret.clone(),
+        })
+    }
+
+    fn get_bulk_secret(
+        &mut self,
+        request: dapr_v1::GetBulkSecretRequest,
+    ) -> Result<dapr_v1::GetBulkSecretResponse, Status> {
+        let data = self
+            .secrets
+            .get(&request.store_name)
+            .into_iter()
+            .flatten()
+            .map(|(key, secrets)| {
+                let secrets = dapr_v1::SecretResponse {
+                    secrets: secrets.clone(),
+                };
+                (key.clone(), secrets)
+            })
+            .collect();
+        Ok(dapr_v1::GetBulkSecretResponse { data })
+    }
+
+    fn get_configuration(
+        &mut self,
+        request: dapr_v1::GetConfigurationRequest,
+    ) -> Result<dapr_v1::GetConfigurationResponse, Status> {
+        let items = self
+            .configuration
+            .get(&request.store_name)
+            .into_iter()
+            .flatten()
+            .filter(|(key, _)| request.keys.is_empty() || request.keys.contains(key))
+            .map(|(key, item)| (key.clone(), item.clone()))
+            .collect();
+        Ok(dapr_v1::GetConfigurationResponse { items })
+    }
+
+    /// Streams the subscription id first, then the changed items, like the
+    /// sidecar.
+    fn subscribe_configuration(
+        &mut self,
+        request: dapr_v1::SubscribeConfigurationRequest,
+    ) -> Result<ConfigurationStream, Status> {
+        self.subscription_ids += 1;
+        let id = self.subscription_ids.to_string();
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        _ = sender.send(Ok(dapr_v1::SubscribeConfigurationResponse {
+            id: id.clone(),
+            items: HashMap::new(),
+        }));
+        self.subscriptions.insert(
+            id,
+            ConfigurationSubscription {
+                store_name: request.store_name,
+                keys: request.keys,
+                sender,
+            },
+        );
+        Ok(futures::stream::poll_fn(move |cx| receiver.poll_recv(cx)).boxed())
+    }
+
+    fn unsubscribe_configuration(
+        &mut self,
+        request: dapr_v1::UnsubscribeConfigurationRequest,
+    ) -> Result<dapr_v1::UnsubscribeConfigurationResponse, Status> {
+        let removed = self.subscriptions.remove(&request.id).is_some();
+        Ok(dapr_v1::UnsubscribeConfigurationResponse {
+            ok: removed,
+            message: match removed {
+                true => String::new(),
+                false => format!("subscription {} not found", request.id),
+            },
+        })
+    }
+
+    fn try_lock_alpha1(
+        &mut self,
+        request: dapr_v1::TryLockRequest,
+    ) -> Result<dapr_v1::TryLockResponse, Status> {
+        let expiry = u64::try_from(request.expiry_in_seconds)
+            .ok()
+            .filter(|seconds| *seconds > 0)
+            .ok_or_else(|| Status::invalid_argument("expiry_in_seconds must be positive"))?;
+        let key = (request.store_name, request.resource_id);
+        let now = Instant::now();
+        if self
+            .locks
+            .get(&key)
+            .is_some_and(|lock| lock.expires_at > now)
+        {
+            return Ok(dapr_v1::TryLockResponse { success: false });
+        }
+        self.locks.insert(
+            key,
+            Lock {
+                owner: request.lock_owner,
+                expires_at: now + Duration::from_secs(expiry),
+            },
+        );
+        Ok(dapr_v1::TryLockResponse { success: true })
+    }
+
+    fn unlock_alpha1(
+        &mut self,
+        request: dapr_v1::UnlockRequest,
+    ) -> Result<dapr_v1::UnlockResponse, Status> {
+        use dapr_v1::unlock_response::Status as UnlockStatus;
+
+        let key = (request.store_name, request.resource_id);
+        let status = match self.locks.get(&key) {
+            Some(lock) if lock.expires_at <= Instant::now() => UnlockStatus::LockDoesNotExist,
+            Some(lock) if lock.owner != request.lock_owner => UnlockStatus::LockBelongsToOthers,
+            Some(_) => {
+                self.locks.remove(&key);
+                UnlockStatus::Success
+            }
+            None => UnlockStatus::LockDoesNotExist,
+        };
+        Ok(dapr_v1::UnlockResponse {
+            status: status.into(),
+        })
+    }
+
+    fn get_metadata(
+        &mut self,
+        _request: dapr_v1::GetMetadataRequest,
+    ) -> Result<dapr_v1::GetMetadataResponse, Status> {
+        Ok(dapr_v1::GetMetadataResponse {
+            id: "mock-sidecar".to_string(),
+            extended_metadata: self.metadata.clone(),
+            ..Default::default()
+        })
+    }
+
+    fn set_metadata(&mut self, request: dapr_v1::SetMetadataRequest) -> Result<(), Status> {
+        self.metadata.insert(request.key, request.value);
+        Ok(())
+    }
+
+    fn shutdown(&mut self, _request: dapr_v1::ShutdownRequest) -> Result<(), Status> {
+        Ok(())
+    }
+}
+
+// The generated `Dapr` trait has no default methods: the supported ones
+// delegate to `Data`, the others answer `Unimplemented`.
+macro_rules! impl_dapr_for_mock_sidecar {
+    (
+        supported { $($method:ident($request:ty) -> $response:ty => $handler:ident;)* }
+        unsupported { $($stub:ident($stub_request:ty) -> $stub_response:ty;)* }
+    ) => {
+        #[async_trait]
+        impl Dapr for MockSidecar {
+            type SubscribeTopicEventsAlpha1Stream =
+                BoxStream<'static, Result<dapr_v1::SubscribeTopicEventsResponseAlpha1, Status>>;
+            type SubscribeConfigurationAlpha1Stream = ConfigurationStream;
+            type SubscribeConfigurationStream = ConfigurationStream;
+            type EncryptAlpha1Stream = BoxStream<'static, Result<dapr_v1::EncryptResponse, Status>>;
+            type DecryptAlpha1Stream = BoxStream<'static, Result<dapr_v1::DecryptResponse, Status>>;
+
+            $(
+                async fn $method(
+                    &self,
+                    request: Request<$request>,
+                ) -> Result<Response<$response>, Status> {
+                    self.lock().$handler(request.into_inner()).map(Response::new)
+                }
+            )*
+
+            $(
+                async fn $stub(
+                    &self,
+                    _request: Request<$stub_request>,
+                ) -> Result<Response<$stub_response>, Status> {
+                    Err(Status::unimplemented(concat!(
+                        stringify!($stub),
+                        " is not supported by MockSidecar"
+                    )))
+                }
+            )*
+        }
+    };
+}
+
+impl_dapr_for_mock_sidecar! {
+    supported {
+        get_state(dapr_v1::GetStateRequest) -> dapr_v1::GetStateResponse => get_state;
+        get_bulk_state(dapr_v1::GetBulkStateRequest) -> dapr_v1::GetBulkStateResponse => get_bulk_state;
+        save_state(dapr_v1::SaveStateRequest) -> () => save_state;
+        delete_state(dapr_v1::DeleteStateRequest) -> () => delete_state;
+        delete_bulk_state(dapr_v1::DeleteBulkStateRequest) -> () => delete_bulk_state;
+        execute_state_transaction(dapr_v1::ExecuteStateTransactionRequest) -> () => execute_state_transaction;
+        publish_event(dapr_v1::PublishEventRequest) -> () => publish_event;
+        bulk_publish_event_alpha1(dapr_v1::BulkPublishRequest) -> dapr_v1::BulkPublishResponse => bulk_publish_event;
+        bulk_publish_event(dapr_v1::BulkPublishRequest) -> dapr_v1::BulkPublishResponse => bulk_publish_event;
+        get_secret(dapr_v1::GetSecretRequest) -> dapr_v1::GetSecretResponse => get_secret;
+        get_bulk_secret(dapr_v1::GetBulkSecretRequest) -> dapr_v1::GetBulkSecretResponse => get_bulk_secret;
+        get_configuration_alpha1(dapr_v1::GetConfigurationRequest) -> dapr_v1::GetConfigurationResponse => get_configuration;
+        get_configuration(dapr_v1::GetConfigurationRequest) -> dapr_v1::GetConfigurationResponse => get_configuration;
+        subscribe_configuration_alpha1(dapr_v1::SubscribeConfigurationRequest) -> Self::SubscribeConfigurationAlpha1Stream => subscribe_configuration;
+        subscribe_configuration(dapr_v1::SubscribeConfigurationRequest) -> Self::SubscribeConfigurationStream => subscribe_configuration;
+        unsubscribe_configuration_alpha1(dapr_v1::UnsubscribeConfigurationRequest) -> dapr_v1::UnsubscribeConfigurationResponse => unsubscribe_configuration;
+        unsubscribe_configuration(dapr_v1::UnsubscribeConfigurationRequest) -> dapr_v1::UnsubscribeConfigurationResponse => unsubscribe_configuration;
+        try_lock_alpha1(dapr_v1::TryLockRequest) -> dapr_v1::TryLockResponse => try_lock_alpha1;
+        unlock_alpha1(dapr_v1::UnlockRequest) -> dapr_v1::UnlockResponse => unlock_alpha1;
+        get_metadata(dapr_v1::GetMetadataRequest) -> dapr_v1::GetMetadataResponse => get_metadata;
+        set_metadata(dapr_v1::SetMetadataRequest) -> () => set_metadata;
+        shutdown(dapr_v1::ShutdownRequest) -> () => shutdown;
+    }
+    unsupported {
+        invoke_service(dapr_v1::InvokeServiceRequest) -> common_v1::InvokeResponse;
+        query_state_alpha1(dapr_v1::QueryStateRequest) -> dapr_v1::QueryStateResponse;
+        subscribe_topic_events_alpha1(tonic::Streaming<dapr_v1::SubscribeTopicEventsRequestAlpha1>) -> Self::SubscribeTopicEventsAlpha1Stream;
+        invoke_binding(dapr_v1::InvokeBindingRequest) -> dapr_v1::InvokeBindingResponse;
+        register_actor_timer(dapr_v1::RegisterActorTimerRequest) -> ();
+        unregister_actor_timer(dapr_v1::UnregisterActorTimerRequest) -> ();
+        register_actor_reminder(dapr_v1::RegisterActorReminderRequest) -> ();
+        unregister_actor_reminder(dapr_v1::UnregisterActorReminderRequest) -> ();
+        unregister_actor_reminders_by_type(dapr_v1::UnregisterActorRemindersByTypeRequest) -> dapr_v1::UnregisterActorRemindersByTypeResponse;
+        list_actor_reminders(dapr_v1::ListActorRemindersRequest) -> dapr_v1::ListActorRemindersResponse;
+        get_actor_state(dapr_v1::GetActorStateRequest) -> dapr_v1::GetActorStateResponse;
+        get_actor_reminder(dapr_v1::GetActorReminderRequest) -> dapr_v1::GetActorReminderResponse;
+        execute_actor_state_transaction(dapr_v1::ExecuteActorStateTransactionRequest) -> ();
+        invoke_actor(dapr_v1::InvokeActorRequest) -> dapr_v1::InvokeActorResponse;
+        encrypt_alpha1(tonic::Streaming<dapr_v1::EncryptRequest>) -> Self::EncryptAlpha1Stream;
+        decrypt_alpha1(tonic::Streaming<dapr_v1::DecryptRequest>) -> Self::DecryptAlpha1Stream;
+        subtle_get_key_alpha1(dapr_v1::SubtleGetKeyRequest) -> dapr_v1::SubtleGetKeyResponse;
+        subtle_encrypt_alpha1(dapr_v1::SubtleEncryptRequest) -> dapr_v1::SubtleEncryptResponse;
+        subtle_decrypt_alpha1(dapr_v1::SubtleDecryptRequest) -> dapr_v1::SubtleDecryptResponse;
+        subtle_wrap_key_alpha1(dapr_v1::SubtleWrapKeyRequest) -> dapr_v1::SubtleWrapKeyResponse;
+        subtle_unwrap_key_alpha1(dapr_v1::SubtleUnwrapKeyRequest) -> dapr_v1::SubtleUnwrapKeyResponse;
+        subtle_sign_alpha1(dapr_v1::SubtleSignRequest) -> dapr_v1::SubtleSignResponse;
+        subtle_verify_alpha1(dapr_v1::SubtleVerifyRequest) -> dapr_v1::SubtleVerifyResponse;
+        start_workflow_alpha1(dapr_v1::StartWorkflowRequest) -> dapr_v1::StartWorkflowResponse;
+        get_workflow_alpha1(dapr_v1::GetWorkflowRequest) -> dapr_v1::GetWorkflowResponse;
+        purge_workflow_alpha1(dapr_v1::PurgeWorkflowRequest) -> ();
+        terminate_workflow_alpha1(dapr_v1::TerminateWorkflowRequest) -> ();
+        pause_workflow_alpha1(dapr_v1::PauseWorkflowRequest) -> ();
+        resume_workflow_alpha1(dapr_v1::ResumeWorkflowRequest) -> ();
+        raise_event_workflow_alpha1(dapr_v1::RaiseEventWorkflowRequest) -> ();
+        start_workflow_beta1(dapr_v1::StartWorkflowRequest) -> dapr_v1::StartWorkflowResponse;
+        get_workflow_beta1(dapr_v1::GetWorkflowRequest) -> dapr_v1::GetWorkflowResponse;
+        purge_workflow_beta1(dapr_v1::PurgeWorkflowRequest) -> ();
+        terminate_workflow_beta1(dapr_v1::TerminateWorkflowRequest) -> ();
+        pause_workflow_beta1(dapr_v1::PauseWorkflowRequest) -> ();
+        resume_workflow_beta1(dapr_v1::ResumeWorkflowRequest) -> ();
+        raise_event_workflow_beta1(dapr_v1::RaiseEventWorkflowRequest) -> ();
+        schedule_job_alpha1(dapr_v1::ScheduleJobRequest) -> dapr_v1::ScheduleJobResponse;
+        schedule_job(dapr_v1::ScheduleJobRequest) -> dapr_v1::ScheduleJobResponse;
+        get_job_alpha1(dapr_v1::GetJobRequest) -> dapr_v1::GetJobResponse;
+        get_job(dapr_v1::GetJobRequest) -> dapr_v1::GetJobResponse;
+        delete_job_alpha1(dapr_v1::DeleteJobRequest) -> dapr_v1::DeleteJobResponse;
+        delete_job(dapr_v1::DeleteJobRequest) -> dapr_v1::DeleteJobResponse;
+        delete_jobs_by_prefix_alpha1(dapr_v1::DeleteJobsByPrefixRequestAlpha1) -> dapr_v1::DeleteJobsByPrefixResponseAlpha1;
+        delete_jobs_by_prefix(dapr_v1::DeleteJobsByPrefixRequest) -> dapr_v1::DeleteJobsByPrefixResponse;
+        list_jobs_alpha1(dapr_v1::ListJobsRequestAlpha1) -> dapr_v1::ListJobsResponseAlpha1;
+        list_jobs(dapr_v1::ListJobsRequest) -> dapr_v1::ListJobsResponse;
+        converse_alpha1(dapr_v1::ConversationRequest) -> dapr_v1::ConversationResponse;
+        converse_alpha2(dapr_v1::ConversationRequestAlpha2) -> dapr_v1::ConversationResponseAlpha2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Client;
+    use crate::error::Error;
+    use dapr_v1::dapr_client::DaprClient;
+
+    fn grpc_code(error: Error) -> tonic::Code {
+        match error {
+            Error::GrpcError(e) => e.code(),
+            other => panic!("expected a gRPC error, got {other}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn state_round_trips_with_etags() {
+        let sidecar = MockSidecar::new();
+        let mut client = Client::for_testing(&sidecar);
+
+        client
+            .save_state("store", "key", b"1".to_vec(), None, None, None)
+            .await
+            .unwrap();
+        let state = client.get_state("store", "key", None).await.unwrap();
+        assert_eq!(state.data, b"1");
+
+        let stale = common_v1::Etag {
+            value: "stale".to_string(),
+        };
+        let error = client
+            .save_state("store", "key", b"2".to_vec(), Some(stale), None, None)
+            .await
+            .unwrap_err();
+        assert_eq!(grpc_code(error), tonic::Code::Aborted);
+
+        let current = common_v1::Etag { value: state.etag };
+        client
+            .save_state("store", "key", b"2".to_vec(), Some(current), None, None)
+            .await
+            .unwrap();
+        assert_eq!(sidecar.state("store", "key"), Some(b"2".to_vec()));
+
+        client.delete_state("store", "key", None).await.unwrap();
+        assert_eq!(sidecar.state("store", "key"), None);
+    }
+
+    #[tokio::test]
+    async fn transactions_apply_all_or_nothing() {
+        let sidecar = MockSidecar::new();
+        sidecar.set_state("store", "a", "old");
+        let mut grpc = DaprClient::new(sidecar.channel());
+
+        let operation = |operation_type: &str, key: &str, etag: Option<&str>| {
+            dapr_v1::TransactionalStateOperation {
+                operation_type: operation_type.to_string(),
+                request: Some(common_v1::StateItem {
+                    key: key.to_string(),
+                    value: b"new".to_vec(),
+                    etag: etag.map(|value| common_v1::Etag {
+                        value: value.to_string(),
+                    }),
+                    ..Default::default()
+                }),
+            }
+        };
+        let transaction = |operations| dapr_v1::ExecuteStateTransactionRequest {
+            store_name: "store".to_string(),
+            operations,
+            metadata: HashMap::new(),
+        };
+
+        let status = grpc
+            .execute_state_transaction(transaction(vec![
+                operation("upsert", "b", None),
+                operation("delete", "a", Some("stale")),
+            ]))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Aborted);
+        assert_eq!(sidecar.state("store", "a"), Some(b"old".to_vec()));
+        assert_eq!(sidecar.state("store", "b"), None);
+
+        grpc.execute_state_transaction(transaction(vec![
+            operation("upsert", "b", None),
+            operation("delete", "a", None),
+        ]))
+        .await
+        .unwrap();
+        assert_eq!(sidecar.state("store", "a"), None);
+        assert_eq!(sidecar.state("store", "b"), Some(b"new".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn records_published_events() {
+        let sidecar = MockSidecar::new();
+        let mut client = Client::for_testing(&sidecar);
+
+        client
+            .publish_event("pubsub", "orders", "application/json", b"{}".to_vec(), None)
+            .await
+            .unwrap();
+
+        let published = sidecar.published("pubsub", "orders");
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].data, b"{}");
+        assert_eq!(published[0].data_content_type, "application/json");
+        assert!(sidecar.published("pubsub", "other").is_empty());
+    }
+
+    #[tokio::test]
+    async fn serves_secrets() {
+        let sidecar = MockSidecar::new();
+        sidecar.set_secret("vault", "db", [("password", "hunter2")]);
+        let mut client = Client::for_testing(&sidecar);
+
+        let secret = client.get_secret("vault", "db").await.unwrap();
+        assert_eq!(secret.data["password"], "hunter2");
+
+        let error = client.get_secret("vault", "missing").await.unwrap_err();
+        assert_eq!(grpc_code(error), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn streams_configuration_updates() {
+        let sidecar = MockSidecar::new();
+        sidecar.set_configuration("config", "mode", "a");
+        let mut client = Client::for_testing(&sidecar);
+
+        let items = client
+            .get_configuration("config", vec!["mode"], None)
+            .await
+            .unwrap()
+            .items;
+        assert_eq!(items["mode"].value, "a");
+
+        let mut stream = client
+            .subscribe_configuration("config", vec!["mode"], None)
+            .await
+            .unwrap();
+        let id = stream.message().await.unwrap().unwrap().id;
+
+        sidecar.set_configuration("config", "other", "ignored");
+        sidecar.set_configuration("config", "mode", "b");
+        let update = stream.message().await.unwrap().unwrap();
+        assert_eq!(update.id, id);
+        assert_eq!(update.items["mode"].value, "b");
+
+        let response = client
+            .unsubscribe_configuration("config", id.as_str())
+            .await
+            .unwrap();
+        assert!(response.ok);
+    }
+
+    #[tokio::test]
+    async fn locks_have_a_single_owner() {
+        use dapr_v1::unlock_response::Status as UnlockStatus;
+
+        let sidecar = MockSidecar::new();
+        let mut grpc = DaprClient::new(sidecar.channel());
+        let try_lock = |owner: &str| dapr_v1::TryLockRequest {
+            store_name: "lockstore".to_string(),
+            resource_id: "order-1".to_string(),
+            lock_owner: owner.to_string(),
+            expiry_in_seconds: 60,
+        };
+        let unlock = |owner: &str| dapr_v1::UnlockRequest {
+            store_name: "lockstore".to_string(),
+            resource_id: "order-1".to_string(),
+            lock_owner: owner.to_string(),
+        };
+
+        let locked = grpc.try_lock_alpha1(try_lock("a")).await.unwrap();
+        assert!(locked.into_inner().success);
+        let locked = grpc.try_lock_alpha1(try_lock("b")).await.unwrap();
+        assert!(!locked.into_inner().success);
+        assert_eq!(
+            sidecar.lock_owner("lockstore", "order-1").as_deref(),
+            Some("a")
+        );
+
+        let unlocked = grpc.unlock_alpha1(unlock("b")).await.unwrap();
+        assert_eq!(
+            unlocked.into_inner().status(),
+            UnlockStatus::LockBelongsToOthers
+        );
+        let unlocked = grpc.unlock_alpha1(unlock("a")).await.unwrap();
+        assert_eq!(unlocked.into_inner().status(), UnlockStatus::Success);
+        assert_eq!(sidecar.lock_owner("lockstore", "order-1"), None);
+    }
+
+    #[tokio::test]
+    async fn other_apis_are_unimplemented() {
+        let sidecar = MockSidecar::new();
+        let mut grpc = DaprClient::new(sidecar.channel());
+
+        let status = grpc
+            .invoke_binding(dapr_v1::InvokeBindingRequest::default())
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unimplemented);
+    }
+}
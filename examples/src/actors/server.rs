@@ -1,14 +1,14 @@
 use async_trait::async_trait;
 use dapr::server::{
     actor::{
-        Actor, ActorError, axum::Json, context_client::ActorContextClient,
+        Actor, ActorError, ActorGuard, axum::Json, context_client::ActorContextClient,
         runtime::ActorTypeRegistration,
     },
     utils::DaprJson,
 };
 use dapr_macros::actor;
 use serde::{Deserialize, Serialize};
-use std::{str::from_utf8, sync::Arc};
+use std::str::from_utf8;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct MyResponse {
@@ -27,10 +27,12 @@ struct MyActor {
 }
 
 impl MyActor {
-    async fn do_stuff(&self, DaprJson(req): DaprJson<MyRequest>) -> Json<MyResponse> {
+    async fn do_stuff(
+        mut actor: ActorGuard<Self>,
+        DaprJson(req): DaprJson<MyRequest>,
+    ) -> Json<MyResponse> {
         println!("doing stuff with {}", req.name);
-        let mut dapr = self.client.clone();
-        let r = dapr.get_actor_state("key1").await.unwrap();
+        let r = actor.client.get_actor_state("key1").await.unwrap();
         println!("get_actor_state {r:?}");
         Json(MyResponse { available: true })
     }
@@ -38,22 +40,22 @@ impl MyActor {
 
 #[async_trait]
 impl Actor for MyActor {
-    async fn on_activate(&self) -> Result<(), ActorError> {
+    async fn on_activate(&mut self) -> Result<(), ActorError> {
         println!("on_activate {}", self.id);
         Ok(())
     }
 
-    async fn on_deactivate(&self) -> Result<(), ActorError> {
+    async fn on_deactivate(&mut self) -> Result<(), ActorError> {
         println!("on_deactivate");
         Ok(())
     }
 
-    async fn on_reminder(&self, reminder_name: &str, data: Vec<u8>) -> Result<(), ActorError> {
+    async fn on_reminder(&mut self, reminder_name: &str, data: Vec<u8>) -> Result<(), ActorError> {
         println!("on_reminder {} {:?}", reminder_name, from_utf8(&data));
         Ok(())
     }
 
-    async fn on_timer(&self, timer_name: &str, data: Vec<u8>) -> Result<(), ActorError> {
+    async fn on_timer(&mut self, timer_name: &str, data: Vec<u8>) -> Result<(), ActorError> {
         println!("on_timer {} {:?}", timer_name, from_utf8(&data));
         Ok(())
     }
@@ -69,7 +71,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             ActorTypeRegistration::new::<MyActor>(
                 "MyActor",
                 Box::new(|_actor_type, actor_id, context| {
-                    Arc::new(MyActor {
+                    Box::new(MyActor {
                         id: actor_id.to_string(),
                         client: context,
                     })
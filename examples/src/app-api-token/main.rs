@@ -1,6 +1,6 @@
-//! Demonstrates wiring [`dapr::client::AppApiTokenLayer`] into a tonic
-//! [`Server`] to enforce the `APP_API_TOKEN` env var on inbound requests
-//! from the Dapr sidecar.
+//! Demonstrates enforcing the `APP_API_TOKEN` env var on inbound requests
+//! from the Dapr sidecar with [`dapr::client::AppApiTokenLayer`], which
+//! [`DaprGrpcServer`] installs by default.
 //!
 //! When `APP_API_TOKEN` is set, the layer requires every incoming gRPC
 //! request to carry a matching `dapr-api-token` metadata header — otherwise
@@ -10,8 +10,9 @@
 //! This example is designed to be run under `dapr run` with `APP_API_TOKEN`
 //! set in the environment. The sidecar inherits the env var and injects the
 //! matching `dapr-api-token` metadata on every callback to the app. The app
-//! installs `AppApiTokenLayer::from_env()` on its tonic server and advertises
-//! a single `cron` input binding (`probe`, defined in `./config/cron.yaml`).
+//! serves its callback with `DaprGrpcServer` (which applies
+//! `AppApiTokenLayer::from_env()`) and advertises a single `cron` input
+//! binding (`probe`, defined in `./config/cron.yaml`).
 //! The sidecar delivers the first cron tick to the app's `on_binding_event`,
 //! which proves authenticated callbacks succeed end-to-end and triggers a
 //! graceful shutdown.
@@ -19,10 +20,9 @@
 use std::sync::Arc;
 
 use dapr::appcallback::*;
-use dapr::client::AppApiTokenLayer;
-use dapr::dapr::proto::runtime::v1::app_callback_server::{AppCallback, AppCallbackServer};
+use dapr::dapr::proto::runtime::v1::app_callback_server::AppCallback;
+use dapr::server::DaprGrpcServer;
 use tokio::sync::Notify;
-use tonic::transport::Server;
 use tonic::{Request, Response, Status};
 
 /// Minimal `AppCallback` impl that logs each authenticated sidecar call
@@ -97,7 +97,7 @@ impl AppCallback for LoggingCallback {
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let addr = "127.0.0.1:50051".parse()?;
+    let addr: std::net::SocketAddr = "127.0.0.1:50051".parse()?;
     let shutdown = Arc::new(Notify::new());
     let callback = LoggingCallback {
         shutdown: shutdown.clone(),
@@ -105,11 +105,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("AppCallback server listening on {addr}");
 
-    Server::builder()
-        // `from_env()` reads `APP_API_TOKEN`. When unset, this is a no-op.
-        .layer(AppApiTokenLayer::from_env())
-        .add_service(AppCallbackServer::new(callback))
-        .serve_with_shutdown(addr, async move { shutdown.notified().await })
+    // `DaprGrpcServer` reads `APP_API_TOKEN` via `AppApiTokenLayer::from_env()`.
+    // When unset, the token check is a no-op.
+    DaprGrpcServer::from_app_callback(callback)
+        .with_address(addr)
+        .with_graceful_shutdown(async move { shutdown.notified().await })
+        .serve()
         .await?;
 
     println!("app-api-token example: ok");